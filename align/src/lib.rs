@@ -1,6 +1,11 @@
-use console::traits::Renderable;
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, MeasuredRenderable, Measurement};
+use segment::Segment;
 use style::Style;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum AlignMethod {
     Left,
     Center,
@@ -8,8 +13,8 @@ pub enum AlignMethod {
 }
 
 /// Align a renderable by adding spaces if necessary
-struct Align {
-    inner_renderable: Box<dyn Renderable>,
+pub struct Align {
+    inner_renderable: Box<dyn MeasuredRenderable>,
     method: AlignMethod,
     style: Option<Style>,
     padding: bool,
@@ -18,7 +23,7 @@ struct Align {
 
 impl Align {
     pub fn new(
-        renderable: Box<impl Renderable + 'static>,
+        renderable: Box<impl MeasuredRenderable + 'static>,
         method: AlignMethod,
         style: Option<Style>,
         padding: Option<bool>,
@@ -34,7 +39,7 @@ impl Align {
     }
 
     pub fn left(
-        renderable: Box<impl Renderable + 'static>,
+        renderable: Box<impl MeasuredRenderable + 'static>,
         style: Option<Style>,
         padding: Option<bool>,
         width: Option<usize>,
@@ -43,7 +48,7 @@ impl Align {
     }
 
     pub fn center(
-        renderable: Box<impl Renderable + 'static>,
+        renderable: Box<impl MeasuredRenderable + 'static>,
         style: Option<Style>,
         padding: Option<bool>,
         width: Option<usize>,
@@ -52,7 +57,7 @@ impl Align {
     }
 
     pub fn right(
-        renderable: Box<impl Renderable + 'static>,
+        renderable: Box<impl MeasuredRenderable + 'static>,
         style: Option<Style>,
         padding: Option<bool>,
         width: Option<usize>,
@@ -60,3 +65,84 @@ impl Align {
         Self::new(renderable, AlignMethod::Right, style, padding, width)
     }
 }
+
+impl Renderable for Align {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let width =
+            Measurement::get(console, &self.inner_renderable, Some(options.max_width)).maximum;
+        let width = self.width.map_or(width, |max_width| width.min(max_width));
+        let lines = console.render_lines(
+            &self.inner_renderable,
+            Some(&options.update_width(width)),
+            None,
+            Some(false),
+            None,
+        );
+        let lines: Vec<&[Segment]> = lines.iter().map(Vec::as_slice).collect();
+        let (width, height) = Segment::get_shape(&lines);
+        let lines = Segment::set_shape(&lines, width, Some(height), None);
+        let new_line = Segment::line(None);
+        let excess_space = options.max_width.saturating_sub(width);
+        let pad = |size: usize| {
+            Some(Segment::new(&" ".repeat(size), self.style.clone(), false)).filter(|_| size > 0)
+        };
+        let (left_pad, right_pad) = match self.method {
+            AlignMethod::Left => (None, pad(excess_space).filter(|_| self.padding)),
+            AlignMethod::Center => {
+                let left = excess_space / 2;
+                (pad(left), pad(excess_space - left).filter(|_| self.padding))
+            }
+            AlignMethod::Right => (pad(excess_space), None),
+        };
+        let mut segments = Vec::new();
+        for line in lines {
+            segments.extend(left_pad.clone());
+            segments.extend(line);
+            segments.extend(right_pad.clone());
+            segments.push(new_line.clone());
+        }
+        segments.into()
+    }
+}
+
+impl Measure for Align {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        Measurement::get(console, &self.inner_renderable, Some(max_width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+
+    fn render(align: Align, width: usize) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        console.begin_capture();
+        console.print(&align).unwrap();
+        console.end_capture()
+    }
+
+    #[test]
+    fn test_align() {
+        assert_eq!(
+            render(Align::left(Box::new("foo"), None, None, None), 10),
+            "foo       \n"
+        );
+        assert_eq!(
+            render(Align::center(Box::new("foo"), None, None, None), 10),
+            "   foo    \n"
+        );
+        assert_eq!(
+            render(Align::right(Box::new("foo"), None, None, None), 10),
+            "       foo\n"
+        );
+        assert_eq!(
+            render(Align::center(Box::new("foo"), None, Some(false), None), 10),
+            "   foo\n"
+        );
+    }
+}
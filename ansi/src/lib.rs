@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+
+use color::Color;
+use lazy_static::lazy_static;
+use regex::Regex;
+use style::{Style, StyleBuilder, UnderlineStyle};
+use text::Text;
+
+lazy_static! {
+    /// Escape sequences: two character sequences, OSC terminated by ST or BEL, and CSI
+    static ref RE_ANSI: Regex = Regex::new(
+        r"(?:\x1b[0-?])|(?:\x1b\](.*?)(?:\x1b\\|\x07))|(?:\x1b([(@-Z\\-_]|\[[0-?]*[ -/]*[@-~]))"
+    )
+    .unwrap();
+    /// Styles of the SGR codes that don't take parameters
+    static ref SGR_STYLE_MAP: HashMap<u8, Style> = {
+        let mut definitions: Vec<(u8, String)> = vec![
+            (1, "bold".to_string()),
+            (2, "dim".to_string()),
+            (3, "italic".to_string()),
+            (4, "underline".to_string()),
+            (5, "blink".to_string()),
+            (6, "blink2".to_string()),
+            (7, "reverse".to_string()),
+            (8, "conceal".to_string()),
+            (9, "strike".to_string()),
+            (21, "underline2".to_string()),
+            (22, "not dim not bold".to_string()),
+            (23, "not italic".to_string()),
+            (24, "not underline not underline2".to_string()),
+            (25, "not blink not blink2".to_string()),
+            (26, "not blink2".to_string()),
+            (27, "not reverse".to_string()),
+            (28, "not conceal".to_string()),
+            (29, "not strike".to_string()),
+            (39, "default".to_string()),
+            (49, "on default".to_string()),
+            (51, "frame".to_string()),
+            (52, "encircle".to_string()),
+            (53, "overline".to_string()),
+            (54, "not frame not encircle".to_string()),
+            (55, "not overline".to_string()),
+        ];
+        for number in 0..8 {
+            definitions.push((30 + number, format!("color({})", number)));
+            definitions.push((40 + number, format!("on color({})", number)));
+            definitions.push((90 + number, format!("color({})", number + 8)));
+            definitions.push((100 + number, format!("on color({})", number + 8)));
+        }
+        definitions
+            .into_iter()
+            .map(|(code, definition)| (code, Style::parse(&definition).unwrap()))
+            .collect()
+    };
+}
+
+/// A piece of text between escape sequences, or a select graphic rendition (SGR) or
+/// operating system command (OSC) sequence
+#[derive(Debug, Eq, PartialEq)]
+enum AnsiToken<'a> {
+    Plain(&'a str),
+    Sgr(&'a str),
+    Osc(&'a str),
+}
+
+/// Split text in to plain text and the escape sequences that affect the style.
+/// Other escape sequences, like cursor movements, are dropped.
+fn ansi_tokenize(ansi_text: &str) -> Vec<AnsiToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    for captures in RE_ANSI.captures_iter(ansi_text) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() > position {
+            tokens.push(AnsiToken::Plain(&ansi_text[position..whole.start()]));
+        }
+        position = whole.end();
+        if let Some(osc) = captures.get(1) {
+            tokens.push(AnsiToken::Osc(osc.as_str()));
+        } else if let Some(sequence) = captures.get(2) {
+            let sequence = sequence.as_str();
+            if sequence == "(" {
+                // character set designation, skip the character set
+                position += ansi_text[position..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+            } else if sequence.starts_with('[') && sequence.ends_with('m') {
+                tokens.push(AnsiToken::Sgr(&sequence[1..sequence.len() - 1]));
+            }
+        }
+    }
+    if position < ansi_text.len() {
+        tokens.push(AnsiToken::Plain(&ansi_text[position..]));
+    }
+    tokens
+}
+
+/// Translates text with ANSI escape sequences in to `Text` with styles
+#[derive(Debug, Default)]
+pub struct AnsiDecoder {
+    /// Style in effect, it carries over from one line to the next
+    style: Style,
+}
+
+impl AnsiDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode text with escape sequences, in to a `Text` per line
+    pub fn decode<'a>(&'a mut self, terminal_text: &'a str) -> impl Iterator<Item = Text> + 'a {
+        terminal_text
+            .lines()
+            .map(move |line| self.decode_line(line))
+    }
+
+    /// Decode a line of text with escape sequences. Only the text after the last carriage
+    /// return is kept, as the rest would be overwritten in a terminal, but the escape
+    /// sequences before it still apply.
+    pub fn decode_line(&mut self, line: &str) -> Text {
+        let mut text = Text::new("", None);
+        for token in ansi_tokenize(line) {
+            match token {
+                AnsiToken::Plain(plain) => {
+                    for (index, plain) in plain.split('\r').enumerate() {
+                        if index > 0 {
+                            text = Text::new("", None);
+                        }
+                        text.append(plain, Some(self.style.clone()));
+                    }
+                }
+                AnsiToken::Osc(osc) => {
+                    // hyperlinks are `8;params;link`, with an empty link to end them
+                    if let Some(Some((_params, link))) =
+                        osc.strip_prefix("8;").map(|osc| osc.split_once(';'))
+                    {
+                        self.style = self
+                            .style
+                            .update_link(Some(link).filter(|link| !link.is_empty()));
+                    }
+                }
+                AnsiToken::Sgr(sgr) => self.apply_sgr(sgr),
+            }
+        }
+        text
+    }
+
+    fn apply_sgr(&mut self, sgr: &str) {
+        let mut parameters = sgr.split(';').filter_map(SgrParameter::parse);
+        while let Some(parameter) = parameters.next() {
+            let code = match parameter {
+                SgrParameter::Code(code) => code,
+                SgrParameter::Underline(underline) => {
+                    self.apply_underline(underline);
+                    continue;
+                }
+            };
+            let style = match code {
+                0 => {
+                    self.style = Style::null();
+                    continue;
+                }
+                // a straight underline replaces the underline style
+                4 => {
+                    self.apply_underline(1);
+                    continue;
+                }
+                38 | 48 | 58 => match Self::extended_color(&mut parameters) {
+                    Some(color) if code == 38 => Style::from_color(Some(color), None),
+                    Some(color) if code == 48 => Style::from_color(None, Some(color)),
+                    Some(color) => StyleBuilder::new().with_underline_color(color).build(),
+                    None => continue,
+                },
+                59 => {
+                    self.style = self.style.update_underline_color(None);
+                    continue;
+                }
+                code => match SGR_STYLE_MAP.get(&code) {
+                    Some(style) => style.clone(),
+                    None => continue,
+                },
+            };
+            self.style = self.style.combine(Some(&style));
+        }
+    }
+
+    /// Apply an underline style sub parameter, as in `4:3` for a curly underline
+    fn apply_underline(&mut self, underline: u8) {
+        let underline_style = match underline {
+            0 => {
+                self.style = self.style.combine(SGR_STYLE_MAP.get(&24));
+                return;
+            }
+            1 => {
+                self.style = self
+                    .style
+                    .combine(SGR_STYLE_MAP.get(&4))
+                    .update_underline_style(None);
+                return;
+            }
+            2 => {
+                self.style = self.style.combine(SGR_STYLE_MAP.get(&21));
+                return;
+            }
+            3 => UnderlineStyle::Curly,
+            4 => UnderlineStyle::Dotted,
+            5 => UnderlineStyle::Dashed,
+            _ => return,
+        };
+        let style = StyleBuilder::new()
+            .with_underline_style(underline_style)
+            .build();
+        self.style = self.style.combine(Some(&style));
+    }
+
+    /// A 256 color (`5;n`) or true color (`2;r;g;b`) parameter
+    fn extended_color(parameters: &mut impl Iterator<Item = SgrParameter>) -> Option<Color> {
+        let mut next_code = || match parameters.next()? {
+            SgrParameter::Code(code) => Some(code),
+            SgrParameter::Underline(_) => None,
+        };
+        match next_code()? {
+            5 => Some(Color::from_ansi(next_code()?)),
+            2 => Some(Color::from_rgb((next_code()?, next_code()?, next_code()?))),
+            _ => None,
+        }
+    }
+}
+
+/// A parameter of a SGR sequence
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SgrParameter {
+    Code(u8),
+    /// Underline style given as a sub parameter of `4`, like `4:3`
+    Underline(u8),
+}
+
+impl SgrParameter {
+    /// Invalid parameters are ignored and empty parameters are zero
+    fn parse(parameter: &str) -> Option<Self> {
+        let number = |number: &str| {
+            number
+                .chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| number.parse::<u32>().unwrap_or(0).min(255) as u8)
+        };
+        match parameter.split_once(':') {
+            Some(("4", underline)) => number(underline).map(SgrParameter::Underline),
+            Some(_) => None,
+            None => number(parameter).map(SgrParameter::Code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::ColorSystem;
+    use style::StyleAttribute;
+
+    fn decode(terminal_text: &str) -> Vec<Text> {
+        AnsiDecoder::new().decode(terminal_text).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            ansi_tokenize("\x1b[1mfoo\x1b[0m\x1b[2Kbar\x1b]8;;https://example.org\x1b\\"),
+            [
+                AnsiToken::Sgr("1"),
+                AnsiToken::Plain("foo"),
+                AnsiToken::Sgr("0"),
+                AnsiToken::Plain("bar"),
+                AnsiToken::Osc("8;;https://example.org"),
+            ]
+        );
+        assert_eq!(ansi_tokenize("\x1b(Bfoo"), [AnsiToken::Plain("foo")]);
+    }
+
+    #[test]
+    fn test_decode() {
+        let lines = decode("Hello \x1b[1;31mWorld\x1b[0m!\nnext \x1b[3mline\x1b[23m");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].plain(), "Hello World!");
+        assert_eq!(lines[0].get_style_at_offset(0), Style::null());
+        assert_eq!(
+            lines[0].get_style_at_offset(6),
+            Style::parse("bold color(1)").unwrap()
+        );
+        assert_eq!(lines[0].get_style_at_offset(11), Style::null());
+        assert_eq!(lines[1].plain(), "next line");
+        assert_eq!(
+            lines[1].get_style_at_offset(5),
+            Style::parse("italic").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_colors() {
+        let lines = decode("\x1b[38;5;208;48;2;10;20;30mfoo\x1b[92;104mbar\x1b[39;49mbaz");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("color(208) on #0a141e").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(3),
+            Style::parse("color(10) on color(12)").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(6),
+            Style::parse("default on default").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_attributes() {
+        let codes = [
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "21", "51", "52", "53",
+        ];
+        for (code, flag) in codes.iter().zip(StyleAttribute::all_flags().iter()) {
+            let lines = decode(&format!("\x1b[{}mfoo", code));
+            assert_eq!(
+                lines[0].get_style_at_offset(0),
+                StyleBuilder::new().with_attribute(*flag, true).build(),
+                "SGR {}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_resets() {
+        // SGR 24 and 25 end both the single and double variants of underline and blink
+        let lines = decode("\x1b[4mfoo\x1b[24mbar\x1b[21mbaz\x1b[24mqux");
+        for offset in [3, 9] {
+            assert_eq!(
+                lines[0].get_style_at_offset(offset),
+                Style::parse("not underline not underline2").unwrap()
+            );
+        }
+        let lines = decode("\x1b[5mfoo\x1b[25mbar\x1b[6mbaz\x1b[25mqux");
+        for offset in [3, 9] {
+            assert_eq!(
+                lines[0].get_style_at_offset(offset),
+                Style::parse("not blink not blink2").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_underline_styles() {
+        let lines = decode("\x1b[1;4:3;58;2;255;0;0mfoo\x1b[1;58;5;196mbar");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("bold undercurl underline_color #ff0000").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(3),
+            Style::parse("bold undercurl underline_color color(196)").unwrap()
+        );
+        let lines = decode("\x1b[4:4mfoo\x1b[4:5mbar\x1b[4:2mbaz\x1b[4:0mqux");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("underdotted").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(3),
+            Style::parse("underdashed").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(6),
+            Style::parse("underdashed underline2").unwrap()
+        );
+        assert_eq!(
+            lines[0].get_style_at_offset(9),
+            Style::parse("not underline not underline2").unwrap()
+        );
+        // colon sub parameters of other codes are ignored
+        let lines = decode("\x1b[38:2::1:2:3;1mfoo");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("bold").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_underline_resets() {
+        // a straight underline and SGR 24 end the underline style
+        let lines = decode("\x1b[4:3mfoo\x1b[4mbar\x1b[4:3mbaz\x1b[4:1mqux\x1b[4:3;24mend");
+        for offset in [3, 9] {
+            assert_eq!(
+                lines[0].get_style_at_offset(offset),
+                Style::parse("underline").unwrap()
+            );
+        }
+        assert_eq!(
+            lines[0].get_style_at_offset(12),
+            Style::parse("not underline not underline2").unwrap()
+        );
+        // SGR 59 ends the underline color
+        let lines = decode("\x1b[4:3;58;5;1mfoo\x1b[59mbar");
+        assert_eq!(
+            lines[0].get_style_at_offset(3),
+            Style::parse("undercurl").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_rendered_underlines() {
+        for definition in [
+            "bold undercurl underline_color #ff0000",
+            "underdotted underline_color color(196)",
+            "italic underdashed",
+        ] {
+            let style = Style::parse(definition).unwrap();
+            let rendered = style.render("foo", Some(ColorSystem::TrueColor), None);
+            assert_eq!(
+                decode(&rendered)[0].get_style_at_offset(0),
+                style,
+                "{}",
+                definition
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_link() {
+        let lines = decode("\x1b]8;id=1;https://example.org\x1b\\link\x1b]8;;\x1b\\ text");
+        assert_eq!(lines[0].plain(), "link text");
+        assert_eq!(
+            lines[0].get_style_at_offset(0).link().as_deref(),
+            Some("https://example.org")
+        );
+        assert_eq!(lines[0].get_style_at_offset(5).link(), &None);
+    }
+
+    #[test]
+    fn test_decode_carriage_return() {
+        let lines = decode("\x1b[32mfoo\rbar\x1b]8;;https://example.org\x1b\\ 50%\r\x1b[1mdone");
+        assert_eq!(lines[0].plain(), "done");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("bold color(2) link https://example.org").unwrap()
+        );
+        let lines = decode("\x1b[32mfoo\rbar");
+        assert_eq!(lines[0].plain(), "bar");
+        assert_eq!(
+            lines[0].get_style_at_offset(0),
+            Style::parse("color(2)").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_strip_control() {
+        let lines = decode("\x1b[2J\x1b[1;1Hfoo\x1b[?25l\rbar\x1b[K");
+        assert_eq!(lines[0].plain(), "bar");
+    }
+}
@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use cells::cell_len;
+
+const ASCII: &str = "The quick brown fox jumps over the lazy dog. ";
+const CJK: &str = "敏捷的棕色狐狸跳过了懒狗。素早い茶色の狐は怠惰な犬を飛び越える。";
+const EMOJI: &str = "😽🦊🐶🎉✨👍🏽🚀🌈";
+
+fn bench_cell_len(c: &mut Criterion) {
+    let mixed = [ASCII, CJK, EMOJI].concat();
+    let mut group = c.benchmark_group("cell_len");
+    for (name, text) in [
+        ("ascii", ASCII.repeat(20)),
+        ("cjk", CJK.repeat(20)),
+        ("emoji", EMOJI.repeat(20)),
+        ("mixed", mixed.repeat(20)),
+    ] {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(name, |b| b.iter(|| cell_len(black_box(&text))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_cell_len);
+criterion_main!(benches);
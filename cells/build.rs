@@ -0,0 +1,181 @@
+//! Generate the cell width tables of every Unicode version vendored in `ucd/`, each version is a
+//! directory with the `EastAsianWidth.txt` and `DerivedGeneralCategory.txt` files of its Unicode
+//! Character Database.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const MAX_CODEPOINT: u32 = 0x10ffff;
+
+/// Format characters and separators drawn without width
+const ZERO_WIDTH: [(u32, u32); 5] = [
+    (0x200b, 0x200f),
+    (0x2028, 0x202e),
+    (0x2060, 0x2063),
+    // hangul vowel and final consonant jamos, drawn joined to the initial consonant
+    (0x1160, 0x11ff),
+    (0xd7b0, 0xd7ff),
+];
+
+/// Parse a `code points; value` line of a UCD file
+fn parse_range(line: &str) -> (u32, u32, String) {
+    let mut fields = line.split(';').map(str::trim);
+    let codepoints = fields.next().unwrap();
+    let value = fields.next().expect("a property value").to_string();
+    let mut bounds = codepoints
+        .split("..")
+        .map(|codepoint| u32::from_str_radix(codepoint, 16).expect("a hex code point"));
+    let start = bounds.next().unwrap();
+    let end = bounds.next().unwrap_or(start);
+    (start, end, value)
+}
+
+/// Parse the `code points; value` lines of a UCD file, preceded by its `# @missing:` lines with
+/// the default values of the code points not listed
+fn parse_ucd(path: &Path) -> Vec<(u32, u32, String)> {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("can't read {}: {}", path.display(), error));
+    let mut missing = Vec::new();
+    let mut listed = Vec::new();
+    for line in content.lines() {
+        if let Some(line) = line.strip_prefix("# @missing:") {
+            missing.push(parse_range(line));
+            continue;
+        }
+        let line = line.split('#').next().unwrap().trim();
+        if !line.is_empty() {
+            listed.push(parse_range(line));
+        }
+    }
+    missing.extend(listed);
+    missing
+}
+
+/// Property value of every code point, the later ranges overriding the earlier ones
+fn property_values(path: &Path) -> Vec<String> {
+    let mut values = vec![String::new(); MAX_CODEPOINT as usize + 1];
+    for (start, end, value) in parse_ucd(path) {
+        for codepoint in start..=end {
+            values[codepoint as usize] = value.clone();
+        }
+    }
+    if let Some(codepoint) = values.iter().position(String::is_empty) {
+        panic!(
+            "{} has no value nor @missing line for U+{:04X}",
+            path.display(),
+            codepoint
+        );
+    }
+    values
+}
+
+/// Merge consecutive code points with the same value in `(start, end, value)` ranges, skipping
+/// the ones with the default value
+fn to_ranges<T: Copy + PartialEq>(values: &[T], default: T) -> Vec<(u32, u32, T)> {
+    let mut ranges: Vec<(u32, u32, T)> = Vec::new();
+    for (codepoint, value) in values.iter().enumerate() {
+        let codepoint = codepoint as u32;
+        if *value == default {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, end, last)) if *end + 1 == codepoint && last == value => *end = codepoint,
+            _ => ranges.push((codepoint, codepoint, *value)),
+        }
+    }
+    ranges
+}
+
+/// Cell width of every code point, -1 for control characters, and whether they are ambiguous
+fn cell_widths(version_dir: &Path) -> (Vec<i32>, Vec<bool>) {
+    let size = MAX_CODEPOINT as usize + 1;
+    let east_asian_width = property_values(&version_dir.join("EastAsianWidth.txt"));
+    let category = property_values(&version_dir.join("DerivedGeneralCategory.txt"));
+
+    let mut widths = vec![1; size];
+    let mut ambiguous = vec![false; size];
+    for codepoint in 0..size {
+        widths[codepoint] = match (
+            category[codepoint].as_str(),
+            east_asian_width[codepoint].as_str(),
+        ) {
+            _ if codepoint == 0 => 0,
+            ("Cc", _) => -1,
+            ("Mn", _) | ("Me", _) => 0,
+            _ if ZERO_WIDTH
+                .iter()
+                .any(|(start, end)| (*start as usize..=*end as usize).contains(&codepoint)) =>
+            {
+                0
+            }
+            (_, "W") | (_, "F") => 2,
+            (_, "A") => {
+                ambiguous[codepoint] = true;
+                1
+            }
+            _ => 1,
+        };
+    }
+    (widths, ambiguous)
+}
+
+fn main() {
+    let ucd_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("ucd");
+    println!("cargo:rerun-if-changed={}", ucd_dir.display());
+
+    let mut versions = BTreeMap::new();
+    for entry in fs::read_dir(&ucd_dir).expect("the ucd directory") {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            versions.insert(
+                path.file_name().unwrap().to_string_lossy().to_string(),
+                path,
+            );
+        }
+    }
+
+    let mut output = String::new();
+    for (version, path) in versions {
+        let name = format!("UNICODE_{}", version.replace('.', "_"));
+        let (widths, ambiguous) = cell_widths(&path);
+        let widths = to_ranges(&widths, 1);
+        let ambiguous = to_ranges(&ambiguous, false);
+
+        writeln!(output, "/// Cell widths of Unicode {}", version).unwrap();
+        writeln!(
+            output,
+            "pub const {}_WIDTHS: [(i32, i32, i32); {}] = [",
+            name,
+            widths.len()
+        )
+        .unwrap();
+        for (start, end, width) in widths {
+            writeln!(output, "    ({}, {}, {}),", start, end, width).unwrap();
+        }
+        writeln!(output, "];\n").unwrap();
+
+        writeln!(
+            output,
+            "/// East Asian Ambiguous characters of Unicode {}",
+            version
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "pub const {}_AMBIGUOUS: [(i32, i32); {}] = [",
+            name,
+            ambiguous.len()
+        )
+        .unwrap();
+        for (start, end, _) in ambiguous {
+            writeln!(output, "    ({}, {}),", start, end).unwrap();
+        }
+        writeln!(output, "];\n").unwrap();
+    }
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("unicode_widths.rs");
+    fs::write(out_path, output).expect("write the generated tables");
+}
@@ -0,0 +1,233 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::unicode_widths::{
+    UNICODE_13_0_0_AMBIGUOUS, UNICODE_13_0_0_WIDTHS, UNICODE_14_0_0_AMBIGUOUS,
+    UNICODE_14_0_0_WIDTHS, UNICODE_3_2_0_AMBIGUOUS, UNICODE_3_2_0_WIDTHS,
+};
+
+/// How the width of text is measured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WidthPolicy {
+    /// Add up the width of every codepoint, like older terminals do
+    Codepoint,
+    /// Measure each extended grapheme cluster as a single character, like modern terminals do,
+    /// so emoji sequences and flags take as many cells as the emoji they display
+    Grapheme,
+}
+
+/// Unicode version of the width tables, it should match the one implemented by the terminal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnicodeVersion {
+    /// Unicode 3.2, emoji take a single cell
+    V3_2,
+    /// Unicode 13.0
+    V13,
+    /// Unicode 14.0
+    V14,
+}
+
+impl UnicodeVersion {
+    pub(crate) const COUNT: usize = 3;
+
+    /// Cell width ranges of the version, with -1 for control characters
+    pub fn cell_widths(self) -> &'static [(i32, i32, i32)] {
+        match self {
+            UnicodeVersion::V3_2 => &UNICODE_3_2_0_WIDTHS,
+            UnicodeVersion::V13 => &UNICODE_13_0_0_WIDTHS,
+            UnicodeVersion::V14 => &UNICODE_14_0_0_WIDTHS,
+        }
+    }
+
+    /// East Asian Ambiguous character ranges of the version
+    pub fn ambiguous(self) -> &'static [(i32, i32)] {
+        match self {
+            UnicodeVersion::V3_2 => &UNICODE_3_2_0_AMBIGUOUS,
+            UnicodeVersion::V13 => &UNICODE_13_0_0_AMBIGUOUS,
+            UnicodeVersion::V14 => &UNICODE_14_0_0_AMBIGUOUS,
+        }
+    }
+}
+
+/// Cells taken by East Asian Ambiguous characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmbiguousWidth {
+    /// A single cell, like most terminals do
+    Narrow,
+    /// Two cells, like terminals do in CJK locales
+    Wide,
+}
+
+impl AmbiguousWidth {
+    /// Number of cells taken by an ambiguous character
+    pub fn cells(self) -> usize {
+        match self {
+            AmbiguousWidth::Narrow => 1,
+            AmbiguousWidth::Wide => 2,
+        }
+    }
+}
+
+/// Settings used to measure text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellWidthConfig {
+    policy: WidthPolicy,
+    ambiguous_width: AmbiguousWidth,
+    unicode_version: UnicodeVersion,
+}
+
+const DEFAULT_CONFIG: CellWidthConfig = CellWidthConfig {
+    policy: WidthPolicy::Grapheme,
+    ambiguous_width: AmbiguousWidth::Narrow,
+    unicode_version: UnicodeVersion::V13,
+};
+
+impl Default for CellWidthConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+impl CellWidthConfig {
+    pub fn with_policy(mut self, policy: WidthPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Cells taken by East Asian Ambiguous characters, narrow by default and wide in CJK locales
+    pub fn with_ambiguous_width(mut self, width: AmbiguousWidth) -> Self {
+        self.ambiguous_width = width;
+        self
+    }
+
+    pub fn with_unicode_version(mut self, unicode_version: UnicodeVersion) -> Self {
+        self.unicode_version = unicode_version;
+        self
+    }
+
+    pub fn policy(&self) -> WidthPolicy {
+        self.policy
+    }
+
+    pub fn ambiguous_width(&self) -> AmbiguousWidth {
+        self.ambiguous_width
+    }
+
+    pub fn unicode_version(&self) -> UnicodeVersion {
+        self.unicode_version
+    }
+
+    const fn to_bits(self) -> u8 {
+        self.policy as u8 | (self.ambiguous_width as u8) << 1 | (self.unicode_version as u8) << 2
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            policy: if bits & 1 == WidthPolicy::Codepoint as u8 {
+                WidthPolicy::Codepoint
+            } else {
+                WidthPolicy::Grapheme
+            },
+            ambiguous_width: if bits & 2 != 0 {
+                AmbiguousWidth::Wide
+            } else {
+                AmbiguousWidth::Narrow
+            },
+            unicode_version: match bits >> 2 {
+                version if version == UnicodeVersion::V3_2 as u8 => UnicodeVersion::V3_2,
+                version if version == UnicodeVersion::V14 as u8 => UnicodeVersion::V14,
+                _ => UnicodeVersion::V13,
+            },
+        }
+    }
+}
+
+static CELL_WIDTH_CONFIG: AtomicU8 = AtomicU8::new(DEFAULT_CONFIG.to_bits());
+
+thread_local! {
+    /// Config of the current thread, set while running `with_cell_width_config`
+    static THREAD_CELL_WIDTH_CONFIG: Cell<Option<CellWidthConfig>> = const { Cell::new(None) };
+}
+
+/// Config used to measure text in the current thread
+pub fn cell_width_config() -> CellWidthConfig {
+    THREAD_CELL_WIDTH_CONFIG
+        .with(Cell::get)
+        .unwrap_or_else(|| CellWidthConfig::from_bits(CELL_WIDTH_CONFIG.load(Ordering::Relaxed)))
+}
+
+/// Set the config used to measure text, for every thread
+pub fn set_cell_width_config(config: CellWidthConfig) {
+    CELL_WIDTH_CONFIG.store(config.to_bits(), Ordering::Relaxed);
+}
+
+/// Restores the config of the thread when dropped
+struct ThreadConfigGuard(Option<CellWidthConfig>);
+
+impl Drop for ThreadConfigGuard {
+    fn drop(&mut self) {
+        THREAD_CELL_WIDTH_CONFIG.with(|config| config.set(self.0));
+    }
+}
+
+/// Measure text with a config while running `f` in the current thread
+pub fn with_cell_width_config<T>(config: CellWidthConfig, f: impl FnOnce() -> T) -> T {
+    let _guard =
+        ThreadConfigGuard(THREAD_CELL_WIDTH_CONFIG.with(|current| current.replace(Some(config))));
+    f()
+}
+
+/// Policy used to measure text, `WidthPolicy::Grapheme` by default
+pub fn width_policy() -> WidthPolicy {
+    cell_width_config().policy()
+}
+
+/// Set the policy used to measure text, for every thread
+pub fn set_width_policy(policy: WidthPolicy) {
+    let _ = CELL_WIDTH_CONFIG.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+        Some(
+            CellWidthConfig::from_bits(bits)
+                .with_policy(policy)
+                .to_bits(),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_bits() {
+        for policy in [WidthPolicy::Codepoint, WidthPolicy::Grapheme] {
+            for ambiguous_width in [AmbiguousWidth::Narrow, AmbiguousWidth::Wide] {
+                for version in [
+                    UnicodeVersion::V3_2,
+                    UnicodeVersion::V13,
+                    UnicodeVersion::V14,
+                ] {
+                    let config = CellWidthConfig::default()
+                        .with_policy(policy)
+                        .with_ambiguous_width(ambiguous_width)
+                        .with_unicode_version(version);
+                    assert_eq!(CellWidthConfig::from_bits(config.to_bits()), config);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_cell_width_config() {
+        let wide = CellWidthConfig::default().with_ambiguous_width(AmbiguousWidth::Wide);
+        assert_eq!(
+            cell_width_config().ambiguous_width(),
+            AmbiguousWidth::Narrow
+        );
+        let nested = with_cell_width_config(wide, || {
+            assert_eq!(cell_width_config(), wide);
+            with_cell_width_config(CellWidthConfig::default(), cell_width_config)
+        });
+        assert_eq!(nested, CellWidthConfig::default());
+        assert_eq!(cell_width_config(), CellWidthConfig::default());
+    }
+}
@@ -1,133 +1,283 @@
 mod cell_widths;
+mod config;
 
-use std::ops::Div;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
-use lazy_static::lazy_static;
-use lru::LruCache;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub use cell_widths::CELL_WIDTHS;
+pub use config::{
+    cell_width_config, set_cell_width_config, set_width_policy, width_policy,
+    with_cell_width_config, AmbiguousWidth, CellWidthConfig, UnicodeVersion, WidthPolicy,
+};
 
-lazy_static! {
-    pub static ref CODEPOINT_CELL_SIZE_CACHE: Mutex<LruCache<u32, usize>> =
-        Mutex::new(LruCache::new(4096));
-    pub static ref DEFAULT_CELL_LEN_CACHE: Mutex<LruCache<String, usize>> =
-        Mutex::new(LruCache::new(4096));
+/// Width tables generated by the build script from the databases vendored in `ucd/`
+pub mod unicode_widths {
+    include!(concat!(env!("OUT_DIR"), "/unicode_widths.rs"));
 }
 
-/// Get the number of cells required to display text
-pub fn cell_len(text: &str, cache: &mut LruCache<String, usize>) -> usize {
-    let text = text.to_string();
+/// Number of bits of a codepoint used to index inside a block of the width table
+const BLOCK_BITS: u32 = 8;
+const BLOCK_SIZE: usize = 1 << BLOCK_BITS;
+
+/// Cell width of every codepoint, split in blocks of `BLOCK_SIZE` codepoints. Most blocks have
+/// the same widths, so each distinct block is stored once.
+struct WidthTable {
+    /// Index in `blocks` of the widths for each block of codepoints
+    block_index: Vec<u16>,
+    blocks: Vec<[u8; BLOCK_SIZE]>,
+}
 
-    if let Some(cached_result) = cache.get(&text) {
-        return *cached_result;
+impl WidthTable {
+    /// Table of the width ranges, with the narrow characters of the `wide` ranges taking 2 cells
+    fn from_ranges(ranges: &[(i32, i32, i32)], wide: &[(i32, i32)]) -> Self {
+        let mut widths = vec![1u8; char::MAX as usize + 1];
+        for &(start, end, width) in ranges {
+            let width = if width == -1 { 0 } else { width as u8 };
+            widths[start as usize..=end as usize]
+                .iter_mut()
+                .for_each(|cell_width| *cell_width = width);
+        }
+        for &(start, end) in wide {
+            widths[start as usize..=end as usize]
+                .iter_mut()
+                .filter(|cell_width| **cell_width == 1)
+                .for_each(|cell_width| *cell_width = 2);
+        }
+        let mut blocks: Vec<[u8; BLOCK_SIZE]> = Vec::new();
+        let mut known_blocks: HashMap<[u8; BLOCK_SIZE], u16> = HashMap::new();
+        let block_index = widths
+            .chunks(BLOCK_SIZE)
+            .map(|chunk| {
+                let mut block = [1u8; BLOCK_SIZE];
+                block[..chunk.len()].copy_from_slice(chunk);
+                *known_blocks.entry(block).or_insert_with(|| {
+                    blocks.push(block);
+                    (blocks.len() - 1) as u16
+                })
+            })
+            .collect();
+        Self {
+            block_index,
+            blocks,
+        }
     }
-    let total_size = text.as_str().chars().map(get_character_cell_size).sum();
-    if text.len() <= 64 {
-        cache.put(text, total_size);
+
+    #[inline]
+    fn get(&self, codepoint: u32) -> usize {
+        let block = self.block_index[(codepoint >> BLOCK_BITS) as usize];
+        self.blocks[block as usize][codepoint as usize & (BLOCK_SIZE - 1)] as usize
     }
-    total_size
 }
 
-/// Get the cell size of a character
-pub fn get_character_cell_size(char: char) -> usize {
-    if char.is_ascii() {
-        1
-    } else {
-        get_codepoint_cell_size(char as u32)
-    }
+/// Width tables of every Unicode version, with narrow and wide ambiguous characters
+static WIDTH_TABLES: [OnceLock<WidthTable>; UnicodeVersion::COUNT * 2] =
+    [const { OnceLock::new() }; UnicodeVersion::COUNT * 2];
+
+fn width_table(config: &CellWidthConfig) -> &'static WidthTable {
+    let version = config.unicode_version();
+    let wide_ambiguous = config.ambiguous_width() == AmbiguousWidth::Wide;
+    WIDTH_TABLES[version as usize * 2 + wide_ambiguous as usize].get_or_init(|| {
+        let wide: &[(i32, i32)] = if wide_ambiguous {
+            version.ambiguous()
+        } else {
+            &[]
+        };
+        WidthTable::from_ranges(version.cell_widths(), wide)
+    })
 }
 
-/// Get the cell size of a character
-fn get_codepoint_cell_size(codepoint: u32) -> usize {
-    let mut cache = CODEPOINT_CELL_SIZE_CACHE.lock().unwrap();
-    if let Some(result) = cache.get(&codepoint) {
-        return *result;
-    }
-    let table = &CELL_WIDTHS;
-    let (mut lower_bound, mut upper_bound): (i32, i32) = (0, table.len() as i32 - 1);
-    let mut index = (lower_bound + upper_bound).div(2);
-    loop {
-        let (start, end, width) = table[index as usize];
-        if (codepoint as i32) < start {
-            upper_bound = index - 1;
-        } else if (codepoint as i32) > end {
-            lower_bound = index + 1;
+/// Measures text with a config
+struct Widths {
+    table: &'static WidthTable,
+    policy: WidthPolicy,
+}
+
+impl Widths {
+    fn new(config: CellWidthConfig) -> Self {
+        Self {
+            table: width_table(&config),
+            policy: config.policy(),
+        }
+    }
+
+    fn current() -> Self {
+        Self::new(cell_width_config())
+    }
+
+    #[inline]
+    fn character(&self, char: char) -> usize {
+        if char.is_ascii() {
+            1
         } else {
-            let result = if width == -1 { 0 } else { width as usize };
-            cache.put(codepoint, result);
-            return result;
+            self.table.get(char as u32)
+        }
+    }
+
+    fn text(&self, text: &str) -> usize {
+        if text.is_ascii() {
+            return text.len();
         }
-        if upper_bound < lower_bound {
-            break;
+        match self.policy {
+            WidthPolicy::Grapheme if text.chars().any(changes_cluster_width) => text
+                .graphemes(true)
+                .map(|grapheme| self.grapheme(grapheme))
+                .sum(),
+            _ => text.chars().map(|char| self.character(char)).sum(),
         }
-        index = (lower_bound + upper_bound).div(2);
     }
-    1
-}
 
-/// Set the length of a string to fit within given number of cells
-pub fn set_cell_size(text: &str, total: usize) -> String {
-    let cell_size = cell_len(text, &mut DEFAULT_CELL_LEN_CACHE.lock().unwrap());
-    if cell_size == total {
-        return text.to_string();
+    /// Split text in the units measured by the policy, along with their cell size
+    fn units<'a>(&self, text: &'a str) -> Vec<(&'a str, usize)> {
+        match self.policy {
+            WidthPolicy::Grapheme if text.chars().any(changes_cluster_width) => text
+                .graphemes(true)
+                .map(|grapheme| (grapheme, self.grapheme(grapheme)))
+                .collect(),
+            _ => text
+                .char_indices()
+                .map(|(index, char)| (&text[index..index + char.len_utf8()], self.character(char)))
+                .collect(),
+        }
     }
-    if cell_size < total {
-        return format!("{}{}", text, " ".repeat(total - cell_size));
+
+    fn grapheme(&self, grapheme: &str) -> usize {
+        if grapheme.is_ascii() {
+            return grapheme.len();
+        }
+        let mut chars = grapheme.chars();
+        let first = match chars.next() {
+            Some(first) => first,
+            None => return 0,
+        };
+        // a pair of regional indicators is a flag
+        if is_regional_indicator(first) && !chars.as_str().is_empty() {
+            return 2;
+        }
+        let mut width = self.character(first);
+        let mut previous = first;
+        let mut emoji_presentation = false;
+        for char in chars {
+            emoji_presentation |= char == '\u{fe0f}';
+            // characters after a zero width joiner are drawn joined to the previous ones
+            if previous != '\u{200d}' && !is_cluster_continuation(char) {
+                width += self.character(char);
+            }
+            previous = char;
+        }
+        // the emoji presentation selector makes narrow characters wide
+        if emoji_presentation {
+            width.max(2)
+        } else {
+            width
+        }
     }
 
-    let mut character_sizes: Vec<usize> = text.chars().map(get_character_cell_size).collect();
-    let mut excess = cell_size as i32 - total as i32;
-    while excess > 0 && character_sizes.len() > 0 {
-        excess -= character_sizes.pop().unwrap() as i32;
+    fn set_cell_size(&self, text: &str, total: usize) -> String {
+        let cell_size = self.text(text);
+        if cell_size == total {
+            return text.to_string();
+        }
+        if cell_size < total {
+            return format!("{}{}", text, " ".repeat(total - cell_size));
+        }
+
+        let mut units = self.units(text);
+        let mut excess = cell_size as i32 - total as i32;
+        while excess > 0 && !units.is_empty() {
+            excess -= units.pop().unwrap().1 as i32;
+        }
+        let mut text: String = units.into_iter().map(|(unit, _)| unit).collect();
+        if excess < 0 {
+            text.push(' ');
+        }
+        text
     }
-    let mut text = text
-        .chars()
-        .take(character_sizes.len())
-        .map(|c| c.to_string())
-        .collect::<Vec<String>>()
-        .join("");
-    if excess < 0 {
-        text.push(' ');
+
+    fn chop_cells(&self, text: &str, max_size: usize, position: usize) -> Vec<String> {
+        let mut total_size = position;
+        let mut lines: Vec<String> = vec![String::new()];
+        for (unit, size) in self.units(text) {
+            if (total_size + size) > max_size {
+                lines.push(unit.to_string());
+                total_size = size;
+            } else {
+                total_size += size;
+                // there is always at least one line
+                lines.last_mut().unwrap().push_str(unit);
+            }
+        }
+        lines
     }
-    text
 }
 
-/// Break text in to equal (cell) length strings
+/// Get the number of cells required to display text
+pub fn cell_len(text: &str) -> usize {
+    if text.is_ascii() {
+        return text.len();
+    }
+    Widths::current().text(text)
+}
+
+fn is_regional_indicator(char: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&char)
+}
+
+/// Characters that don't take cells of their own when they are part of a grapheme cluster
+fn is_cluster_continuation(char: char) -> bool {
+    matches!(char,
+        // emoji skin tone modifiers
+        '\u{1f3fb}'..='\u{1f3ff}'
+        // hangul vowel and final consonant jamos
+        | '\u{1160}'..='\u{11ff}'
+        | '\u{d7b0}'..='\u{d7ff}')
+}
+
+/// Whether measuring a grapheme cluster with the character may differ from adding up its
+/// codepoints, text without any of them can be measured by codepoint
+fn changes_cluster_width(char: char) -> bool {
+    char >= '\u{1160}'
+        && (matches!(char, '\u{200d}' | '\u{fe0f}')
+            || is_regional_indicator(char)
+            || is_cluster_continuation(char))
+}
+
+/// Get the cell size of an extended grapheme cluster
+pub fn get_grapheme_cell_size(grapheme: &str) -> usize {
+    Widths::current().grapheme(grapheme)
+}
+
+/// Get the cell size of a character
+#[inline]
+pub fn get_character_cell_size(char: char) -> usize {
+    Widths::current().character(char)
+}
+
+/// Set the length of a string to fit within given number of cells
+pub fn set_cell_size(text: &str, total: usize) -> String {
+    Widths::current().set_cell_size(text, total)
+}
+
+/// Break text in to equal (cell) length strings, the first line starts at cell `position`.
+/// A character that does not fit in the current line starts the next one, so lines of double
+/// width characters may be one cell short.
 pub fn chop_cells(text: &str, max_size: usize, position: usize) -> Vec<String> {
-    let mut characters = text
-        .chars()
-        .rev()
-        .map(|c| (c, get_character_cell_size(c)))
-        .peekable();
-    let mut total_size = position;
-    let mut lines: Vec<Vec<char>> = Vec::new();
-    while let Some(_) = characters.peek() {
-        let (character, size) = characters.next().unwrap();
-        if (total_size + size) > max_size {
-            lines.push(vec![character]);
-            total_size = size
-        } else {
-            total_size = size;
-            let len = lines.len();
-            lines.get_mut(len - 1).unwrap().push(character);
-        }
-    }
-    lines
-        .iter()
-        .map(|line| {
-            line.iter()
-                .map(|c| c.to_string())
-                .collect::<Vec<String>>()
-                .join("")
-        })
-        .collect()
+    Widths::current().chop_cells(text, max_size, position)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn widths(policy: WidthPolicy) -> Widths {
+        Widths::new(CellWidthConfig::default().with_policy(policy))
+    }
+
+    fn get_codepoint_cell_size(codepoint: u32) -> usize {
+        width_table(&CellWidthConfig::default()).get(codepoint)
+    }
+
     #[test]
     fn test_get_codepoint_cell_size() {
         let codepoint = '😽' as u32;
@@ -135,6 +285,140 @@ mod tests {
         assert_eq!(get_codepoint_cell_size(codepoint), 2);
     }
 
+    #[test]
+    fn test_width_table() {
+        for (start, end, width) in CELL_WIDTHS.iter() {
+            let width = (*width).max(0) as usize;
+            assert_eq!(get_codepoint_cell_size(*start as u32), width);
+            assert_eq!(get_codepoint_cell_size(*end as u32), width);
+        }
+        // codepoints out of every range take a single cell
+        assert_eq!(get_codepoint_cell_size(0x10ffff), 1);
+        assert_eq!(get_codepoint_cell_size('é' as u32), 1);
+        assert_eq!(get_codepoint_cell_size(0x2fffe), 1);
+    }
+
+    #[test]
+    fn test_cell_len() {
+        assert_eq!(cell_len(""), 0);
+        assert_eq!(cell_len("foo bar"), 7);
+        assert_eq!(cell_len("\x1b[1m"), 4);
+        assert_eq!(cell_len("こんにちは"), 10);
+        assert_eq!(cell_len("a😽b\u{301}"), 4);
+    }
+
+    /// Tricky sequences, with their width as a grapheme cluster and adding up their codepoints
+    const GRAPHEME_CORPUS: [(&str, usize, usize); 15] = [
+        // family, joined by zero width joiners
+        ("👨\u{200d}👩\u{200d}👧", 2, 6),
+        // woman technologist with a skin tone
+        ("👩🏽\u{200d}💻", 2, 6),
+        // rainbow flag
+        ("🏳\u{fe0f}\u{200d}🌈", 2, 3),
+        // flags, pairs of regional indicators
+        ("🇪🇸", 2, 2),
+        ("🇪🇸🇫🇷", 4, 4),
+        // a lone regional indicator
+        ("🇪", 1, 1),
+        // skin tone modifier
+        ("👍🏽", 2, 4),
+        // emoji presentation selector
+        ("❤\u{fe0f}", 2, 1),
+        // keycap
+        ("1\u{fe0f}\u{20e3}", 2, 1),
+        // text presentation selector
+        ("⚠\u{fe0e}", 1, 1),
+        // combining marks
+        ("e\u{301}", 1, 1),
+        ("ก\u{e47}", 1, 1),
+        // hangul syllable made of jamos, the vowel and final consonant take no cells
+        ("\u{1112}\u{1161}\u{11ab}", 2, 2),
+        ("a\u{200d}b", 2, 2),
+        ("\r\n", 2, 2),
+    ];
+
+    #[test]
+    fn test_grapheme_corpus() {
+        for (text, grapheme_width, codepoint_width) in GRAPHEME_CORPUS.iter() {
+            assert_eq!(
+                widths(WidthPolicy::Grapheme).text(text),
+                *grapheme_width,
+                "{:?}",
+                text
+            );
+            assert_eq!(
+                widths(WidthPolicy::Codepoint).text(text),
+                *codepoint_width,
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_cell_size_graphemes() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let text = format!("{}{}", family, family);
+        assert_eq!(widths(WidthPolicy::Grapheme).set_cell_size(&text, 4), text);
+        assert_eq!(
+            widths(WidthPolicy::Grapheme).set_cell_size(&text, 3),
+            format!("{} ", family)
+        );
+        assert_eq!(widths(WidthPolicy::Grapheme).set_cell_size("🇪🇸🇫🇷", 2), "🇪🇸");
+        assert_eq!(
+            widths(WidthPolicy::Codepoint).set_cell_size("🇪🇸🇫🇷", 3),
+            "🇪🇸🇫"
+        );
+    }
+
+    #[test]
+    fn test_chop_cells_graphemes() {
+        assert_eq!(
+            widths(WidthPolicy::Grapheme).chop_cells("👍🏽👍🏽e\u{301}", 3, 0),
+            ["👍🏽", "👍🏽e\u{301}"]
+        );
+        assert_eq!(
+            widths(WidthPolicy::Codepoint).chop_cells("👍🏽👍🏽", 3, 0),
+            ["👍", "🏽", "👍", "🏽"]
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_width() {
+        let narrow = Widths::new(CellWidthConfig::default());
+        let wide =
+            Widths::new(CellWidthConfig::default().with_ambiguous_width(AmbiguousWidth::Wide));
+        assert_eq!(narrow.text("§─é"), 3);
+        assert_eq!(wide.text("§─é"), 6);
+        // combining marks are ambiguous, but still take no cells
+        assert_eq!(wide.text("e\u{301}"), 1);
+        assert_eq!(wide.text("abc"), 3);
+        assert_eq!(wide.set_cell_size("§─é", 3), "§ ");
+    }
+
+    #[test]
+    fn test_unicode_version() {
+        let version =
+            |version| Widths::new(CellWidthConfig::default().with_unicode_version(version));
+        // added in unicode 6.1
+        assert_eq!(version(UnicodeVersion::V3_2).text("😀"), 1);
+        assert_eq!(version(UnicodeVersion::V13).text("😀"), 2);
+        // added in unicode 14.0
+        assert_eq!(version(UnicodeVersion::V13).text("🫗"), 1);
+        assert_eq!(version(UnicodeVersion::V14).text("🫗"), 2);
+        for version in [
+            UnicodeVersion::V3_2,
+            UnicodeVersion::V13,
+            UnicodeVersion::V14,
+        ] {
+            let widths = Widths::new(CellWidthConfig::default().with_unicode_version(version));
+            assert_eq!(widths.text("\x1b\u{85}"), 1);
+            assert_eq!(widths.text("\u{200b}\u{301}"), 0);
+            assert_eq!(widths.text("漢字"), 4);
+            assert_eq!(version.ambiguous().first(), Some(&(0xa1, 0xa1)));
+        }
+    }
+
     #[test]
     fn test_get_character_cell_size() {
         assert_eq!(get_character_cell_size('A'), 1);
@@ -151,4 +435,16 @@ mod tests {
         assert_eq!(set_cell_size("😽😽", 2), "😽");
         assert_eq!(set_cell_size("😽😽", 1), " ");
     }
+
+    #[test]
+    fn test_chop_cells() {
+        assert_eq!(chop_cells("abcdefghijk", 3, 0), ["abc", "def", "ghi", "jk"]);
+        assert_eq!(
+            chop_cells("abcdefghijk", 3, 2),
+            ["a", "bcd", "efg", "hij", "k"]
+        );
+        assert_eq!(chop_cells("abc", 3, 3), ["", "abc"]);
+        assert_eq!(chop_cells("😽😽😽", 3, 0), ["😽", "😽", "😽"]);
+        assert_eq!(chop_cells("a😽b😽", 3, 1), ["a", "😽b", "😽"]);
+    }
 }
@@ -456,6 +456,33 @@ impl Color {
         }
     }
 
+    /// Get the ANSI escape codes to use this color for underlines (SGR 58), which only takes
+    /// colors by their number in the 256 color palette or by their RGB components
+    pub fn get_underline_ansi_codes(&self) -> Vec<String> {
+        match self.color_type {
+            ColorType::Default => vec!["59".to_string()],
+            ColorType::TrueColor => {
+                assert!(self.triplet.is_some());
+                let (r, g, b) = self.triplet.unwrap().as_raw();
+                vec![
+                    "58".to_string(),
+                    "2".to_string(),
+                    r.to_string(),
+                    g.to_string(),
+                    b.to_string(),
+                ]
+            }
+            ColorType::Standard | ColorType::EightBit | ColorType::Windows => {
+                assert!(self.number.is_some());
+                vec![
+                    "58".to_string(),
+                    "5".to_string(),
+                    self.number.unwrap().to_string(),
+                ]
+            }
+        }
+    }
+
     /// Downgrade a color system to a system with fewer colors
     pub fn downgrade(&self, system: ColorSystem) -> Self {
         if self.color_type == ColorType::Default {
@@ -527,10 +554,13 @@ fn parsed_regex_captures(
             ..Default::default()
         })
     } else if let Some(color) = color_8 {
-        let number = u8::from_str_radix(color.as_str(), 10).map_err(|_| Error::ParseColor {
-            original: original_color.to_string(),
-            message: "color number must be <= 255".to_string(),
-        })?;
+        let number = color
+            .as_str()
+            .parse::<u8>()
+            .map_err(|_| Error::ParseColor {
+                original: original_color.to_string(),
+                message: "color number must be <= 255".to_string(),
+            })?;
 
         let color_type = if number < 16 {
             ColorType::Standard
@@ -549,15 +579,15 @@ fn parsed_regex_captures(
         match &components[..] {
             [r, g, b] => {
                 let triplet = ColorTriplet::from((
-                    u8::from_str_radix(&r, 10).map_err(|_| Error::ParseColor {
+                    r.parse::<u8>().map_err(|_| Error::ParseColor {
                         original: original_color.to_string(),
                         message: "red component must be <= 255".to_string(),
                     })?,
-                    u8::from_str_radix(&g, 10).map_err(|_| Error::ParseColor {
+                    g.parse::<u8>().map_err(|_| Error::ParseColor {
                         original: original_color.to_string(),
                         message: "green component must be <= 255".to_string(),
                     })?,
-                    u8::from_str_radix(&b, 10).map_err(|_| Error::ParseColor {
+                    b.parse::<u8>().map_err(|_| Error::ParseColor {
                         original: original_color.to_string(),
                         message: "blue component must be <= 255".to_string(),
                     })?,
@@ -596,8 +626,8 @@ fn parsed_ansi_color(color_name: &str, color_number: u8) -> Color {
 fn truecolor_2_eightbit(name: &str, color: ColorTriplet) -> Color {
     let hsl = colorsys::Hsl::from(colorsys::Rgb::from(color.as_raw()));
     // If saturation is under 10% assume it is grayscale
-    if hsl.get_saturation() < 10.0 {
-        let gray = f32::round(hsl.get_lightness() as f32 * 0.25) as u8;
+    if hsl.saturation() < 10.0 {
+        let gray = f32::round(hsl.lightness() as f32 * 0.25) as u8;
         let color_number = match gray {
             0 => 16,
             25 => 231,
@@ -945,6 +975,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_underline_ansi_codes() {
+        let cases = vec![
+            ("default", vec!["59"]),
+            ("red", vec!["58", "5", "1"]),
+            ("color(200)", vec!["58", "5", "200"]),
+            ("#ff0000", vec!["58", "2", "255", "0", "0"]),
+        ];
+
+        for (case, expected) in cases {
+            assert_eq!(
+                Color::parse(case).unwrap().get_underline_ansi_codes(),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_downgrade() {
         assert_eq!(
@@ -0,0 +1,838 @@
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+
+use cells::CellWidthConfig;
+use color::ColorSystem;
+use control::Control;
+use emoji::replace_emoji;
+use highlighter::{Highlighter, ReprHighlighter};
+use segment::{Segment, DEFAULT_TAB_SIZE};
+use style::Style;
+use text::Text;
+use theme::Theme;
+
+use crate::options::{ConsoleDimensions, ConsoleOptions, Encoding};
+use crate::pager::{Pager, PagerContext, SystemPager};
+use crate::screen::ScreenContext;
+use crate::traits::{RenderIter, Renderable};
+use crate::writer::StyleWriter;
+
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_HEIGHT: usize = 25;
+/// Size of the output buffered while printing before it is written to the file
+const WRITE_BUFFER_SIZE: usize = 8 * 1024;
+
+/// A high level console interface
+pub struct Console {
+    /// Where the rendered output is written to, borrowed while the output is rendered lazily
+    file: RefCell<Box<dyn Write + Send>>,
+    width: usize,
+    height: usize,
+    /// Color system used to render styles, `None` disables colors
+    color_system: Option<ColorSystem>,
+    legacy_windows: bool,
+    is_terminal: bool,
+    encoding: Encoding,
+    /// Replace emoji shortcodes in rendered strings
+    emoji: bool,
+    /// Interpret console markup like `[bold]` in rendered strings
+    markup: bool,
+    /// Highlight printed strings
+    highlight: bool,
+    highlighter: Box<dyn Highlighter + Send>,
+    theme: Theme,
+    /// Segments captured instead of written while a capture is in progress
+    capture: Option<Vec<Segment>>,
+    /// The alternate screen buffer is enabled
+    is_alt_screen: bool,
+    /// Measure text with this config instead of the global one
+    cell_width_config: Option<CellWidthConfig>,
+    /// Cells between tab stops when the tabs of rendered text are expanded
+    tab_size: usize,
+    /// Time column of the last record logged to the console, left blank when it repeats
+    last_log_time: Option<String>,
+}
+
+/// Builder for `Console`, values not set are detected from the environment
+#[derive(Default)]
+pub struct ConsoleBuilder {
+    file: Option<Box<dyn Write + Send>>,
+    width: Option<usize>,
+    height: Option<usize>,
+    color_system: Option<Option<ColorSystem>>,
+    legacy_windows: Option<bool>,
+    force_terminal: Option<bool>,
+    encoding: Option<Encoding>,
+    emoji: Option<bool>,
+    markup: Option<bool>,
+    highlight: Option<bool>,
+    highlighter: Option<Box<dyn Highlighter + Send>>,
+    theme: Option<Theme>,
+    cell_width_config: Option<CellWidthConfig>,
+    tab_size: Option<usize>,
+}
+
+impl ConsoleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, file: Box<dyn Write + Send>) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Force a color system, `None` disables colors
+    pub fn with_color_system(mut self, color_system: Option<ColorSystem>) -> Self {
+        self.color_system = Some(color_system);
+        self
+    }
+
+    pub fn with_legacy_windows(mut self, legacy_windows: bool) -> Self {
+        self.legacy_windows = Some(legacy_windows);
+        self
+    }
+
+    /// Force the console to behave (or not) as a terminal
+    pub fn with_force_terminal(mut self, force_terminal: bool) -> Self {
+        self.force_terminal = Some(force_terminal);
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Replace emoji shortcodes like `:rocket:` in rendered strings, enabled by default
+    pub fn with_emoji(mut self, emoji: bool) -> Self {
+        self.emoji = Some(emoji);
+        self
+    }
+
+    /// Interpret console markup like `[bold]text[/bold]` in rendered strings, enabled by default
+    pub fn with_markup(mut self, markup: bool) -> Self {
+        self.markup = Some(markup);
+        self
+    }
+
+    /// Highlight printed strings, enabled by default
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+
+    /// Highlighter of printed strings, `ReprHighlighter` by default
+    pub fn with_highlighter(mut self, highlighter: Box<dyn Highlighter + Send>) -> Self {
+        self.highlighter = Some(highlighter);
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Measure text like the terminal does, e.g. with wide ambiguous characters in CJK locales
+    pub fn with_cell_width_config(mut self, cell_width_config: CellWidthConfig) -> Self {
+        self.cell_width_config = Some(cell_width_config);
+        self
+    }
+
+    /// Cells between tab stops, tabs of rendered text are expanded to spaces, 8 by default
+    pub fn with_tab_size(mut self, tab_size: usize) -> Self {
+        self.tab_size = Some(tab_size);
+        self
+    }
+
+    pub fn build(self) -> Console {
+        let is_terminal = self
+            .force_terminal
+            .unwrap_or_else(|| self.file.is_none() && io::stdout().is_terminal());
+        let terminal_size = terminal_size::terminal_size();
+        let width = self
+            .width
+            .or_else(|| env_usize("COLUMNS"))
+            .or_else(|| terminal_size.map(|(width, _)| width.0 as usize))
+            .unwrap_or(DEFAULT_WIDTH);
+        let height = self
+            .height
+            .or_else(|| env_usize("LINES"))
+            .or_else(|| terminal_size.map(|(_, height)| height.0 as usize))
+            .unwrap_or(DEFAULT_HEIGHT);
+        let color_system = self
+            .color_system
+            .unwrap_or_else(|| detect_color_system(is_terminal));
+        Console {
+            file: RefCell::new(self.file.unwrap_or_else(|| Box::new(io::stdout()))),
+            width,
+            height,
+            color_system,
+            legacy_windows: self.legacy_windows.unwrap_or(false),
+            is_terminal,
+            encoding: self.encoding.unwrap_or_default(),
+            emoji: self.emoji.unwrap_or(true),
+            markup: self.markup.unwrap_or(true),
+            highlight: self.highlight.unwrap_or(true),
+            highlighter: self
+                .highlighter
+                .unwrap_or_else(|| Box::new(ReprHighlighter)),
+            theme: self.theme.unwrap_or_default(),
+            capture: None,
+            is_alt_screen: false,
+            cell_width_config: self.cell_width_config,
+            tab_size: self.tab_size.unwrap_or(DEFAULT_TAB_SIZE),
+            last_log_time: None,
+        }
+    }
+}
+
+fn env_usize(name: &str) -> Option<usize> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Detect the color system supported by the terminal from the environment
+fn detect_color_system(is_terminal: bool) -> Option<ColorSystem> {
+    if !is_terminal || env::var_os("NO_COLOR").is_some() {
+        return None;
+    }
+    let color_term = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+    if color_term == "truecolor" || color_term == "24bit" {
+        return Some(ColorSystem::TrueColor);
+    }
+    let term = env::var("TERM").unwrap_or_default().to_lowercase();
+    match term.rsplit('-').next().unwrap_or("") {
+        "dumb" => None,
+        "kitty" | "truecolor" => Some(ColorSystem::TrueColor),
+        "256color" => Some(ColorSystem::EightBit),
+        _ => Some(ColorSystem::Standard),
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        ConsoleBuilder::new().build()
+    }
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> ConsoleDimensions {
+        ConsoleDimensions {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn color_system(&self) -> Option<ColorSystem> {
+        self.color_system
+    }
+
+    pub fn legacy_windows(&self) -> bool {
+        self.legacy_windows
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.is_terminal
+    }
+
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    pub fn emoji(&self) -> bool {
+        self.emoji
+    }
+
+    pub fn markup(&self) -> bool {
+        self.markup
+    }
+
+    pub fn highlight(&self) -> bool {
+        self.highlight
+    }
+
+    pub fn highlighter(&self) -> &dyn Highlighter {
+        self.highlighter.as_ref()
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn is_alt_screen(&self) -> bool {
+        self.is_alt_screen
+    }
+
+    pub fn tab_size(&self) -> usize {
+        self.tab_size
+    }
+
+    /// Time column of the last record logged to the console
+    pub fn last_log_time(&self) -> Option<&str> {
+        self.last_log_time.as_deref()
+    }
+
+    pub fn set_last_log_time(&mut self, time: Option<String>) {
+        self.last_log_time = time;
+    }
+
+    /// Config used to measure text rendered by the console
+    pub fn cell_width_config(&self) -> CellWidthConfig {
+        self.cell_width_config
+            .unwrap_or_else(cells::cell_width_config)
+    }
+
+    /// Run `f` measuring text with the config of the console
+    pub(crate) fn with_cell_widths<T>(&self, f: impl FnOnce() -> T) -> T {
+        match self.cell_width_config {
+            Some(config) => cells::with_cell_width_config(config, f),
+            None => f(),
+        }
+    }
+
+    /// Get default console options
+    pub fn options(&self) -> ConsoleOptions {
+        ConsoleOptions {
+            legacy_windows: self.legacy_windows,
+            min_width: 1,
+            max_width: self.width,
+            is_terminal: self.is_terminal,
+            encoding: self.encoding.clone(),
+            justify: None,
+            overflow: None,
+            no_wrap: None,
+            highlight: None,
+        }
+    }
+
+    /// Get a style by theme name or parse a style definition.
+    /// Names that can not be found nor parsed resolve to the null style.
+    pub fn get_style(&self, name: &str) -> Style {
+        self.theme
+            .get(name)
+            .cloned()
+            .or_else(|| Style::parse(name).ok())
+            .unwrap_or_default()
+    }
+
+    /// Convert a string to a Text instance, with an optional style name or definition.
+    /// Emoji shortcodes are replaced if enabled and the encoding supports them, and console
+    /// markup is rendered if enabled. Strings with invalid markup are kept as they are.
+    pub fn render_str(&self, text: &str, style: Option<&str>) -> Text {
+        let style = style.map(|style| self.get_style(style));
+        let replaced;
+        let text = if self.emoji && !self.options().ascii_only() {
+            replaced = replace_emoji(text, None);
+            replaced.as_str()
+        } else {
+            text
+        };
+        if self.markup {
+            if let Ok(text) = markup::render(text, style.clone(), |name| self.get_style(name)) {
+                return text;
+            }
+        }
+        Text::new(text, style)
+    }
+
+    /// Render an object in to a list of segments
+    pub fn render(
+        &self,
+        renderable: &dyn Renderable,
+        options: Option<&ConsoleOptions>,
+    ) -> Vec<Segment> {
+        match options {
+            Some(options) => self.render_iter(renderable, options).collect(),
+            None => self.render_iter(renderable, &self.options()).collect(),
+        }
+    }
+
+    /// Render an object lazily, nested renderables are rendered as the segments are consumed
+    pub fn render_iter<'a>(
+        &'a self,
+        renderable: &'a dyn Renderable,
+        options: &'a ConsoleOptions,
+    ) -> RenderIter<'a> {
+        RenderIter::new(self, renderable, options)
+    }
+
+    /// Render objects in to a list of lines, each line is cropped (and optionally padded) to the
+    /// options max width
+    pub fn render_lines(
+        &self,
+        renderable: &dyn Renderable,
+        options: Option<&ConsoleOptions>,
+        style: Option<Style>,
+        pad: Option<bool>,
+        new_lines: Option<bool>,
+    ) -> Vec<Vec<Segment>> {
+        let default_options = self.options();
+        let options = options.unwrap_or(&default_options);
+        let segments = self.render(renderable, Some(options));
+        let segments: Vec<Segment> = match style.as_ref().filter(|style| style.as_bool()) {
+            Some(style) => Segment::apply_style(&segments, Some(style.clone())).collect(),
+            None => segments,
+        };
+        self.with_cell_widths(|| {
+            Segment::split_and_crop_lines_with_tab_size(
+                &segments,
+                options.max_width,
+                style,
+                pad,
+                Some(new_lines.unwrap_or(false)),
+                self.tab_size,
+            )
+        })
+    }
+
+    /// Print a renderable to the console. The output is written as it is rendered, so it is
+    /// never held in memory as a whole.
+    pub fn print<R: Renderable + ?Sized>(&mut self, renderable: &R) -> io::Result<()> {
+        let options = self.options();
+        if self.capture.is_some() {
+            let segments = self.render(&renderable, Some(&options));
+            return self.write_segments(segments);
+        }
+        let mut file = self.file.borrow_mut();
+        let mut writer = StyleWriter::new(self.color_system, self.legacy_windows);
+        let mut output = String::new();
+        for segment in self.render_iter(&renderable, &options) {
+            writer.write(&segment, &mut output);
+            if output.len() >= WRITE_BUFFER_SIZE {
+                file.write_all(output.as_bytes())?;
+                output.clear();
+            }
+        }
+        writer.reset(&mut output);
+        file.write_all(output.as_bytes())?;
+        file.flush()
+    }
+
+    /// Write new lines to the console
+    pub fn line(&mut self, count: usize) -> io::Result<()> {
+        self.write_segments(vec![Segment::new(&"\n".repeat(count), None, false)])
+    }
+
+    /// Show or hide the cursor, returns false if the console is not a terminal
+    pub fn show_cursor(&mut self, show: bool) -> io::Result<bool> {
+        if !self.is_terminal {
+            return Ok(false);
+        }
+        self.print(&Control::show_cursor(show))?;
+        Ok(true)
+    }
+
+    /// Enable or disable the alternate screen buffer, returns false if it is not supported.
+    /// The alternate screen has its own contents, so the scrollback is left untouched.
+    pub fn set_alt_screen(&mut self, enable: bool) -> io::Result<bool> {
+        if !self.is_terminal || self.legacy_windows {
+            return Ok(false);
+        }
+        self.print(&Control::alt_screen(enable))?;
+        self.is_alt_screen = enable;
+        Ok(true)
+    }
+
+    /// Enter the alternate screen, optionally hiding the cursor, until the returned guard is
+    /// dropped
+    pub fn screen(&mut self, hide_cursor: bool) -> io::Result<ScreenContext<'_>> {
+        ScreenContext::new(self, hide_cursor)
+    }
+
+    /// Start capturing the console output instead of writing it
+    pub fn begin_capture(&mut self) {
+        self.capture.get_or_insert_with(Vec::new);
+    }
+
+    /// End capture mode and return the captured output
+    pub fn end_capture(&mut self) -> String {
+        let segments = self.end_capture_segments();
+        self.render_segments(&segments)
+    }
+
+    pub(crate) fn end_capture_segments(&mut self) -> Vec<Segment> {
+        self.capture.take().unwrap_or_default()
+    }
+
+    /// Collect the output until the returned guard is dropped, then show it in the system pager.
+    /// Styles are stripped unless `styles` is set. The output is written directly if the
+    /// console is not a terminal.
+    pub fn pager(&mut self, styles: bool) -> PagerContext<'_> {
+        if self.is_terminal {
+            PagerContext::new(self, Some(Box::new(SystemPager::new())), styles)
+        } else {
+            PagerContext::new(self, None, styles)
+        }
+    }
+
+    /// Collect the output until the returned guard is dropped, then show it in a custom pager
+    pub fn pager_with<'a>(
+        &'a mut self,
+        pager: Box<dyn Pager + 'a>,
+        styles: bool,
+    ) -> PagerContext<'a> {
+        PagerContext::new(self, Some(pager), styles)
+    }
+
+    /// Write segments to the output file, or to the capture buffer while capturing
+    pub fn write_segments(&mut self, segments: Vec<Segment>) -> io::Result<()> {
+        if let Some(capture) = self.capture.as_mut() {
+            capture.extend(segments);
+            return Ok(());
+        }
+        let output = self.render_segments(&segments);
+        let file = self.file.get_mut();
+        file.write_all(output.as_bytes())?;
+        file.flush()
+    }
+
+    /// Render segments to a string with the ANSI codes required by the console color system.
+    /// Only the changes between the styles of consecutive segments are written.
+    pub fn render_segments(&self, segments: &[Segment]) -> String {
+        let mut writer = StyleWriter::new(self.color_system, self.legacy_windows);
+        let mut output = String::new();
+        for segment in segments {
+            writer.write(segment, &mut output);
+        }
+        writer.reset(&mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::RenderResult;
+    use cells::AmbiguousWidth;
+    use emoji::{Emoji, EmojiVariant};
+    use std::panic;
+    use std::sync::{Arc, Mutex};
+
+    fn console(width: usize) -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(width)
+            .with_color_system(Some(ColorSystem::TrueColor))
+            .build()
+    }
+
+    #[test]
+    fn test_options() {
+        let console = console(30);
+        let options = console.options();
+        assert_eq!(options.max_width, 30);
+        assert!(!options.is_terminal);
+        assert!(!options.ascii_only());
+    }
+
+    #[test]
+    fn test_get_style() {
+        let console = console(30);
+        assert_eq!(
+            console.get_style("repr.brace"),
+            Style::parse("bold").unwrap()
+        );
+        assert_eq!(console.get_style("red"), Style::parse("red").unwrap());
+        assert_eq!(console.get_style("not a style"), Style::null());
+    }
+
+    #[test]
+    fn test_capture() {
+        let mut console = console(30);
+        console.begin_capture();
+        console.print("Hello").unwrap();
+        console
+            .print(&Text::styled("World", Style::parse("bold").unwrap()))
+            .unwrap();
+        assert_eq!(console.end_capture(), "Hello\n\x1b[1mWorld\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_cell_width_config() {
+        let mut cjk_console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(10)
+            .with_cell_width_config(
+                CellWidthConfig::default().with_ambiguous_width(AmbiguousWidth::Wide),
+            )
+            .build();
+        assert_eq!(
+            cjk_console.cell_width_config().ambiguous_width(),
+            AmbiguousWidth::Wide
+        );
+        cjk_console.begin_capture();
+        cjk_console.print("§§§§§§§§").unwrap();
+        assert_eq!(cjk_console.end_capture(), "§§§§§\n§§§\n");
+        let lines = cjk_console.render_lines(&"─ §", None, None, Some(true), None);
+        assert_eq!(lines[0].last().unwrap().text(), "     ");
+        // text is measured with the global config out of the console
+        assert_eq!(Text::new("§", None).cell_len(), 1);
+
+        let mut console = console(10);
+        console.begin_capture();
+        console.print("§§§§§§§§").unwrap();
+        assert_eq!(console.end_capture(), "§§§§§§§§\n");
+    }
+
+    #[test]
+    fn test_tab_size() {
+        let mut console = console(12);
+        assert_eq!(console.tab_size(), 8);
+        console.begin_capture();
+        console.print("id\tname\nall\tgood").unwrap();
+        console
+            .print(&Text::new("a\tb", None).with_tab_size(2))
+            .unwrap();
+        assert_eq!(console.end_capture(), "id      name\nall     good\na b\n");
+
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(12)
+            .with_tab_size(4)
+            .build();
+        console.begin_capture();
+        console.print("id\tname\tstatus").unwrap();
+        assert_eq!(console.end_capture(), "id  name    \nstatus\n");
+    }
+
+    struct Tabbed;
+
+    impl Renderable for Tabbed {
+        fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+            RenderResult::from_segments(std::iter::once(Segment::new("a\tb", None, false)))
+        }
+    }
+
+    #[test]
+    fn test_render_lines_tab_size() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(10)
+            .with_tab_size(4)
+            .build();
+        assert_eq!(
+            console.render_lines(&Tabbed, None, None, Some(true), None),
+            [[
+                Segment::new("a   b", None, false),
+                Segment::new("     ", None, false),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_emoji() {
+        let mut console = console(30);
+        console.begin_capture();
+        console.print("Hello :rocket:").unwrap();
+        console
+            .print(
+                &Emoji::new("rocket")
+                    .unwrap()
+                    .with_variant(EmojiVariant::Text),
+            )
+            .unwrap();
+        assert_eq!(console.end_capture(), "Hello 🚀\n🚀\u{FE0E}");
+        assert_eq!(console.render_str(":rocket:", None).cell_len(), 2);
+
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_emoji(false)
+            .build();
+        assert_eq!(console.render_str(":rocket:", None).plain(), ":rocket:");
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_encoding(Encoding::new("ascii"))
+            .build();
+        assert_eq!(console.render_str(":rocket:", None).plain(), ":rocket:");
+    }
+
+    #[test]
+    fn test_highlight() {
+        let mut console = console(30);
+        console.begin_capture();
+        console.print("count 42").unwrap();
+        let mut options = console.options();
+        options.highlight = Some(false);
+        let segments = console.render(&"count 42", Some(&options));
+        console.write_segments(segments).unwrap();
+        assert_eq!(
+            console.end_capture(),
+            "count \x1b[1;36m42\x1b[0m\ncount 42\n"
+        );
+    }
+
+    #[test]
+    fn test_markup() {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_color_system(Some(ColorSystem::Standard))
+            .with_highlight(false)
+            .build();
+        console.begin_capture();
+        console.print("[bold]foo[/bold] [1, 2]").unwrap();
+        console.print("[/bold] is not closing").unwrap();
+        assert_eq!(
+            console.end_capture(),
+            "\x1b[1mfoo\x1b[0m [1, 2]\n[/bold] is not closing\n"
+        );
+
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_markup(false)
+            .build();
+        assert_eq!(console.render_str("[bold]foo", None).plain(), "[bold]foo");
+    }
+
+    #[test]
+    fn test_screen() {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_force_terminal(true)
+            .build();
+        console.begin_capture();
+        {
+            let mut screen = console.screen(true).unwrap();
+            assert!(screen.is_alt_screen());
+            screen.print("foo").unwrap();
+        }
+        assert!(!console.is_alt_screen());
+        assert_eq!(
+            console.end_capture(),
+            "\x1b[?1049h\x1b[H\x1b[?25lfoo\n\x1b[?1049l\x1b[?25h"
+        );
+    }
+
+    #[test]
+    fn test_screen_panic() {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_force_terminal(true)
+            .build();
+        console.begin_capture();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let _screen = console.screen(false).unwrap();
+            panic!("failed to render");
+        }));
+        assert!(result.is_err());
+        assert_eq!(console.end_capture(), "\x1b[?1049h\x1b[H\x1b[?1049l");
+    }
+
+    #[test]
+    fn test_screen_not_terminal() {
+        let mut console = console(30);
+        console.begin_capture();
+        assert!(!console.screen(true).unwrap().is_alt_screen());
+        assert_eq!(console.end_capture(), "");
+    }
+
+    /// Keeps the size of every write
+    #[derive(Clone, Default)]
+    struct Writes(Arc<Mutex<Vec<usize>>>);
+
+    impl Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().push(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Lines(usize);
+
+    impl Renderable for Lines {
+        fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+            RenderResult::from_segments(
+                (0..self.0).map(|index| Segment::new(&format!("line {}\n", index), None, false)),
+            )
+        }
+    }
+
+    #[test]
+    fn test_print_streaming() {
+        let writes = Writes::default();
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(writes.clone()))
+            .build();
+        console.print(&Lines(100_000)).unwrap();
+        let writes = writes.0.lock().unwrap();
+        assert!(writes.len() > 100);
+        assert!(writes.iter().all(|size| *size < WRITE_BUFFER_SIZE * 2));
+        assert_eq!(writes.iter().sum::<usize>(), 1_088_890);
+    }
+
+    #[derive(Clone, Default)]
+    struct MemoryPager(Arc<Mutex<String>>);
+
+    impl Pager for MemoryPager {
+        fn show(&mut self, content: &str) -> io::Result<()> {
+            self.0.lock().unwrap().push_str(content);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pager() {
+        let mut console = console(30);
+        let pager = MemoryPager::default();
+        {
+            let mut paged = console.pager_with(Box::new(pager.clone()), false);
+            paged.print("count 42").unwrap();
+            assert!(pager.0.lock().unwrap().is_empty());
+        }
+        assert_eq!(*pager.0.lock().unwrap(), "count 42\n");
+
+        let pager = MemoryPager::default();
+        console
+            .pager_with(Box::new(pager.clone()), true)
+            .print("count 42")
+            .unwrap();
+        assert_eq!(*pager.0.lock().unwrap(), "count \x1b[1;36m42\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_pager_not_terminal() {
+        let mut console = console(30);
+        console.begin_capture();
+        console.pager(false).print("foo").unwrap();
+        assert_eq!(console.end_capture(), "foo\n");
+    }
+
+    #[test]
+    fn test_render_lines() {
+        let console = console(5);
+        let lines = console.render_lines(&"foo bar", None, None, None, None);
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| line.iter().map(Segment::text).collect())
+            .collect();
+        assert_eq!(lines, ["foo  ", "bar  "]);
+    }
+}
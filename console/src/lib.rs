@@ -1,4 +1,11 @@
+mod console;
 pub mod options;
+mod pager;
+mod screen;
 pub mod traits;
+mod writer;
 
-pub struct Console {}
+pub use self::console::{Console, ConsoleBuilder};
+pub use self::pager::{Pager, PagerContext, SystemPager};
+pub use self::screen::ScreenContext;
+pub use cells::{AmbiguousWidth, CellWidthConfig, UnicodeVersion, WidthPolicy};
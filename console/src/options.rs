@@ -1,31 +1,35 @@
-#[derive(PartialEq)]
-pub struct Encoding(&'static str);
-
-pub enum JustifyMethod {
-    Full,
-    Left,
-    Center,
-    Right,
-}
+pub use text::{JustifyMethod, OverflowMethod};
 
-pub enum OverflowMethod {
-    Crop,
-    Fold,
-    Ellipsis,
-}
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Encoding(&'static str);
 
 impl Encoding {
     pub fn new(encoding: &'static str) -> Self {
         Self(encoding)
     }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
 }
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self("utf-8")
+    }
+}
+
 /// Size of the terminal
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct ConsoleDimensions {
-    width: usize,
-    height: usize,
+    /// The width of the console in cells
+    pub width: usize,
+    /// The height of the console in lines
+    pub height: usize,
 }
 
 /// Options for `rich_console` method
+#[derive(Clone, Debug)]
 pub struct ConsoleOptions {
     /// flag for legacy windows
     pub legacy_windows: bool,
@@ -49,13 +53,13 @@ pub struct ConsoleOptions {
 
 #[derive(Default)]
 pub struct UpdateConsoleOptions {
-    width: Option<usize>,
-    min_width: Option<usize>,
-    max_width: Option<usize>,
-    justify: Option<JustifyMethod>,
-    overflow: Option<OverflowMethod>,
-    no_wrap: Option<bool>,
-    highlight: Option<bool>,
+    pub width: Option<usize>,
+    pub min_width: Option<usize>,
+    pub max_width: Option<usize>,
+    pub justify: Option<JustifyMethod>,
+    pub overflow: Option<OverflowMethod>,
+    pub no_wrap: Option<bool>,
+    pub highlight: Option<bool>,
 }
 
 impl ConsoleOptions {
@@ -89,4 +93,12 @@ impl ConsoleOptions {
             self.highlight = Some(highlight);
         }
     }
+
+    /// Get a copy of the options with the width set to the given value
+    pub fn update_width(&self, width: usize) -> Self {
+        let mut options = self.clone();
+        options.min_width = width;
+        options.max_width = width;
+        options
+    }
 }
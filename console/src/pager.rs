@@ -0,0 +1,122 @@
+use std::env;
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::process::{Command, Stdio};
+
+use segment::Segment;
+
+use crate::Console;
+
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Shows content to the user, typically allowing to scroll through it
+pub trait Pager {
+    fn show(&mut self, content: &str) -> io::Result<()>;
+}
+
+/// Pipes the content to the command in `$PAGER`, or `less -R` if it is not set
+#[derive(Clone, Debug)]
+pub struct SystemPager {
+    command: String,
+}
+
+impl SystemPager {
+    pub fn new() -> Self {
+        let command = env::var("PAGER")
+            .ok()
+            .filter(|command| !command.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+        Self { command }
+    }
+
+    /// Use a pager command, with its arguments separated by whitespace
+    pub fn with_command(mut self, command: &str) -> Self {
+        self.command = command.to_string();
+        self
+    }
+}
+
+impl Default for SystemPager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pager for SystemPager {
+    fn show(&mut self, content: &str) -> io::Result<()> {
+        let mut arguments = self.command.split_whitespace();
+        let program = arguments.next().unwrap_or("less");
+        let mut child = Command::new(program)
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            match stdin.write_all(content.as_bytes()) {
+                // the user quit the pager before reading everything
+                Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {}
+                result => result?,
+            }
+        }
+        child.wait()?;
+        Ok(())
+    }
+}
+
+/// Collects the console output while alive and shows it in a pager when dropped.
+/// Without a pager the output is written as usual.
+pub struct PagerContext<'a> {
+    console: &'a mut Console,
+    pager: Option<Box<dyn Pager + 'a>>,
+    styles: bool,
+}
+
+impl<'a> PagerContext<'a> {
+    pub(crate) fn new(
+        console: &'a mut Console,
+        pager: Option<Box<dyn Pager + 'a>>,
+        styles: bool,
+    ) -> Self {
+        if pager.is_some() {
+            console.begin_capture();
+        }
+        Self {
+            console,
+            pager,
+            styles,
+        }
+    }
+}
+
+impl Deref for PagerContext<'_> {
+    type Target = Console;
+
+    fn deref(&self) -> &Console {
+        self.console
+    }
+}
+
+impl DerefMut for PagerContext<'_> {
+    fn deref_mut(&mut self) -> &mut Console {
+        self.console
+    }
+}
+
+impl Drop for PagerContext<'_> {
+    fn drop(&mut self) {
+        let pager = match self.pager.as_mut() {
+            Some(pager) => pager,
+            None => return,
+        };
+        let segments = self.console.end_capture_segments();
+        let segments: Vec<Segment> = if self.styles {
+            segments
+        } else {
+            Segment::strip_styles(&segments).collect()
+        };
+        let content = self.console.render_segments(&segments);
+        if pager.show(&content).is_err() {
+            // errors can't be reported from drop, print the output if the pager failed
+            let _ = self.console.write_segments(segments);
+        }
+    }
+}
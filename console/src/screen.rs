@@ -0,0 +1,53 @@
+use std::io;
+use std::ops::{Deref, DerefMut};
+
+use crate::Console;
+
+/// Keeps the console in the alternate screen while alive, the cursor and the screen are
+/// restored when dropped, including when unwinding from a panic
+pub struct ScreenContext<'a> {
+    console: &'a mut Console,
+    hide_cursor: bool,
+    /// The alternate screen was enabled, so there is something to restore
+    changed: bool,
+}
+
+impl<'a> ScreenContext<'a> {
+    pub(crate) fn new(console: &'a mut Console, hide_cursor: bool) -> io::Result<Self> {
+        let changed = console.set_alt_screen(true)?;
+        if changed && hide_cursor {
+            console.show_cursor(false)?;
+        }
+        Ok(Self {
+            console,
+            hide_cursor,
+            changed,
+        })
+    }
+}
+
+impl Deref for ScreenContext<'_> {
+    type Target = Console;
+
+    fn deref(&self) -> &Console {
+        self.console
+    }
+}
+
+impl DerefMut for ScreenContext<'_> {
+    fn deref_mut(&mut self) -> &mut Console {
+        self.console
+    }
+}
+
+impl Drop for ScreenContext<'_> {
+    fn drop(&mut self) {
+        if self.changed {
+            // errors can't be reported from drop, restoring is best effort
+            let _ = self.console.set_alt_screen(false);
+            if self.hide_cursor {
+                let _ = self.console.show_cursor(true);
+            }
+        }
+    }
+}
@@ -1,27 +1,192 @@
 use crate::options::ConsoleOptions;
 use crate::Console;
+use control::Control;
+use emoji::Emoji;
 use segment::Segment;
+use self_cell::self_cell;
+use std::iter::{self, FromIterator};
+use text::Text;
+use utils::iter::loop_first;
+use utils::pick::pick_bool;
 
-// TODO: Use an Iterator to correct type instead of vec when RFC 2515 is implemented
-// https://github.com/rust-lang/rust/issues/63063
-//pub type RenderResult = impl Iterator<Item = &Segment>;
-pub type RenderResult = Vec<Segment>;
+/// An item produced while rendering, either a segment or a renderable that is rendered in turn
+pub enum RenderItem<'a> {
+    Segment(Segment),
+    /// A renderable that outlives the render, it is rendered lazily
+    Renderable(&'a dyn Renderable),
+    /// A renderable created while rendering, it is rendered lazily and dropped once exhausted
+    Owned(Box<dyn Renderable + 'a>),
+}
+
+impl From<Segment> for RenderItem<'_> {
+    fn from(segment: Segment) -> Self {
+        Self::Segment(segment)
+    }
+}
+
+/// The lazily produced output of a renderable, `Console` flattens the nested renderables
+pub struct RenderResult<'a>(Box<dyn Iterator<Item = RenderItem<'a>> + 'a>);
+
+impl<'a> RenderResult<'a> {
+    pub fn new<Items>(items: Items) -> Self
+    where
+        Items: IntoIterator<Item = RenderItem<'a>>,
+        Items::IntoIter: 'a,
+    {
+        Self(Box::new(items.into_iter()))
+    }
+
+    pub fn from_segments<Segments>(segments: Segments) -> Self
+    where
+        Segments: IntoIterator<Item = Segment>,
+        Segments::IntoIter: 'a,
+    {
+        Self::new(segments.into_iter().map(RenderItem::Segment))
+    }
+
+    pub fn empty() -> Self {
+        Self::new(std::iter::empty())
+    }
+}
+
+/// Adapter for renderables that produce all their segments up front
+impl From<Vec<Segment>> for RenderResult<'_> {
+    fn from(segments: Vec<Segment>) -> Self {
+        Self::from_segments(segments)
+    }
+}
+
+impl FromIterator<Segment> for RenderResult<'_> {
+    fn from_iter<Segments: IntoIterator<Item = Segment>>(segments: Segments) -> Self {
+        segments.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<'a> Iterator for RenderResult<'a> {
+    type Item = RenderItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+self_cell!(
+    /// An owned renderable together with the segments being rendered from it
+    struct OwnedRenderIter<'a> {
+        owner: Box<dyn Renderable + 'a>,
+
+        #[not_covariant]
+        dependent: RenderIter,
+    }
+);
+
+/// A level of the renderables being flattened
+enum RenderFrame<'a> {
+    Items(RenderResult<'a>),
+    Owned(OwnedRenderIter<'a>),
+}
+
+/// Flattens the output of a renderable in to segments, rendering nested renderables as they
+/// are reached
+pub struct RenderIter<'a> {
+    console: &'a Console,
+    options: &'a ConsoleOptions,
+    stack: Vec<RenderFrame<'a>>,
+}
+
+impl<'a> RenderIter<'a> {
+    pub(crate) fn new(
+        console: &'a Console,
+        renderable: &'a dyn Renderable,
+        options: &'a ConsoleOptions,
+    ) -> Self {
+        let items = console.with_cell_widths(|| renderable.rich_console(console, options));
+        Self {
+            console,
+            options,
+            stack: vec![RenderFrame::Items(items)],
+        }
+    }
+
+    fn next_segment(&mut self) -> Option<Segment> {
+        let (console, options) = (self.console, self.options);
+        loop {
+            match self.stack.last_mut()? {
+                RenderFrame::Items(items) => match items.next() {
+                    Some(RenderItem::Segment(segment)) => return Some(segment),
+                    Some(RenderItem::Renderable(renderable)) => {
+                        let items = renderable.rich_console(console, options);
+                        self.stack.push(RenderFrame::Items(items));
+                    }
+                    Some(RenderItem::Owned(renderable)) => {
+                        let iter = OwnedRenderIter::new(renderable, |renderable| RenderIter {
+                            console,
+                            options,
+                            stack: vec![RenderFrame::Items(
+                                renderable.rich_console(console, options),
+                            )],
+                        });
+                        self.stack.push(RenderFrame::Owned(iter));
+                    }
+                    None => {
+                        self.stack.pop();
+                    }
+                },
+                RenderFrame::Owned(iter) => {
+                    match iter.with_dependent_mut(|_, iter| iter.next_segment()) {
+                        Some(segment) => return Some(segment),
+                        None => {
+                            self.stack.pop();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for RenderIter<'_> {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Segment> {
+        let console = self.console;
+        console.with_cell_widths(|| self.next_segment())
+    }
+}
 
 pub trait Renderable {
-    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult;
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a>;
+}
+
+impl<T: Renderable + ?Sized> Renderable for &T {
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a> {
+        (**self).rich_console(console, options)
+    }
 }
 
-impl<'a, T: Renderable> Renderable for &T {
-    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult {
-        self.rich_console(console, options)
+impl<T: Renderable + ?Sized> Renderable for Box<T> {
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a> {
+        (**self).rich_console(console, options)
     }
 }
 
-trait ConsoleRenderable {
-    fn rich_console(&self) -> RenderResult;
+pub trait ConsoleRenderable {
+    fn rich_console(&self) -> RenderResult<'_>;
 }
 
-trait RichCast {
+pub trait RichCast {
     fn rich(&self) -> Segment;
 }
 
@@ -32,15 +197,210 @@ impl RichCast for &str {
 }
 
 impl Renderable for dyn ToString {
-    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult {
+    fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
         // get console style
         // let style = console.style.clone;
-        vec![Segment::new(self.to_string().as_str(), None, false)]
+        vec![Segment::new(self.to_string().as_str(), None, false)].into()
+    }
+}
+
+impl Renderable for str {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let mut text = console.render_str(self, None);
+        if options.highlight.unwrap_or_else(|| console.highlight()) {
+            console.highlighter().highlight(&mut text, console.theme());
+        }
+        RenderResult::new(iter::once(RenderItem::Owned(Box::new(text))))
+    }
+}
+
+impl Renderable for String {
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a> {
+        self.as_str().rich_console(console, options)
+    }
+}
+
+impl Renderable for Control {
+    fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+        vec![self.segment().clone()].into()
+    }
+}
+
+impl Renderable for Emoji {
+    fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+        vec![Segment::new(
+            &self.to_string(),
+            self.style().cloned(),
+            false,
+        )]
+        .into()
+    }
+}
+
+impl Renderable for Text {
+    /// Lines are wrapped and rendered as the segments are consumed
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a> {
+        let wrap = |text: &Text| {
+            text.wrap_iter(
+                options.max_width,
+                self.justify().or(options.justify),
+                self.overflow().or(options.overflow),
+                Some(pick_bool(&[self.no_wrap(), options.no_wrap])),
+            )
+        };
+        let lines = if self.plain().contains('\t') {
+            let mut text = self.clone();
+            text.expand_tabs(Some(self.tab_size().unwrap_or_else(|| console.tab_size())));
+            wrap(&text)
+        } else {
+            wrap(self)
+        };
+        let end = self.end();
+        RenderResult::from_segments(
+            loop_first(lines)
+                .flat_map(|(first, line)| {
+                    let new_line = (!first).then(|| Segment::new("\n", None, false));
+                    new_line.into_iter().chain(line.render(""))
+                })
+                .chain((!end.is_empty()).then(|| Segment::new(end, None, false))),
+        )
     }
 }
 
-impl Renderable for &str {
-    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult {
-        vec![Segment::new(self.to_string().as_str(), None, false)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConsoleBuilder;
+    use std::cell::Cell;
+    use std::io;
+
+    /// Lines created as they are rendered
+    struct Lines {
+        count: usize,
+        created: Cell<usize>,
+    }
+
+    impl Renderable for Lines {
+        fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+            RenderResult::new((0..self.count).map(move |index| {
+                self.created.set(self.created.get() + 1);
+                RenderItem::Owned(Box::new(Text::new(&format!("line {}", index), None)))
+            }))
+        }
+    }
+
+    struct Nested(Vec<Text>);
+
+    impl Renderable for Nested {
+        fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+            RenderResult::new(self.0.iter().flat_map(|text| {
+                vec![
+                    Segment::new("> ", None, false).into(),
+                    RenderItem::Renderable(text),
+                ]
+            }))
+        }
+    }
+
+    fn console() -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(20)
+            .build()
+    }
+
+    #[test]
+    fn test_render_lazily() {
+        let console = console();
+        let options = console.options();
+        let lines = Lines {
+            count: 1_000_000,
+            created: Cell::new(0),
+        };
+        let segments: Vec<Segment> = console.render_iter(&lines, &options).take(2).collect();
+        let texts: Vec<&str> = segments.iter().map(Segment::text).collect();
+        assert_eq!(texts, ["line 0", "\n"]);
+        assert_eq!(lines.created.get(), 1);
+    }
+
+    #[test]
+    fn test_render_owned_lazily() {
+        /// Lines created as they are rendered, counted by their owner
+        struct CountedLines<'a>(&'a Cell<usize>);
+
+        impl Renderable for CountedLines<'_> {
+            fn rich_console(
+                &self,
+                _console: &Console,
+                _options: &ConsoleOptions,
+            ) -> RenderResult<'_> {
+                RenderResult::new((0..1_000_000).map(move |index| {
+                    self.0.set(self.0.get() + 1);
+                    Segment::new(&format!("line {}\n", index), None, false).into()
+                }))
+            }
+        }
+
+        struct Owner(Cell<usize>);
+
+        impl Renderable for Owner {
+            fn rich_console(
+                &self,
+                _console: &Console,
+                _options: &ConsoleOptions,
+            ) -> RenderResult<'_> {
+                RenderResult::new(iter::once(RenderItem::Owned(Box::new(CountedLines(
+                    &self.0,
+                )))))
+            }
+        }
+
+        let console = console();
+        let options = console.options();
+        let owner = Owner(Cell::new(0));
+        let segments: Vec<Segment> = console.render_iter(&owner, &options).take(2).collect();
+        let texts: Vec<&str> = segments.iter().map(Segment::text).collect();
+        assert_eq!(texts, ["line 0\n", "line 1\n"]);
+        assert_eq!(owner.0.get(), 2);
+    }
+
+    #[test]
+    fn test_render_text_lazily() {
+        let console = console();
+        let options = console.options();
+        let text = Text::new(&"foo bar baz\n".repeat(1_000), None);
+        let mut segments = console.render_iter(&text, &options);
+        let texts: Vec<String> = segments
+            .by_ref()
+            .take(3)
+            .map(|segment| segment.text().to_string())
+            .collect();
+        assert_eq!(texts, ["foo bar baz", "\n", "foo bar baz"]);
+        assert_eq!(segments.count(), 1_998);
+    }
+
+    #[test]
+    fn test_render_nested() {
+        let console = console();
+        let nested = Nested(vec![Text::new("foo", None), Text::new("bar", None)]);
+        let output = console.render_segments(&console.render(&nested, None));
+        assert_eq!(output, "> foo\n> bar\n");
+    }
+
+    #[test]
+    fn test_vec_adapter() {
+        let segments = vec![Segment::new("foo", None, false)];
+        let items: Vec<RenderItem> = RenderResult::from(segments).collect();
+        assert!(
+            matches!(items.as_slice(), [RenderItem::Segment(segment)] if segment.text() == "foo")
+        );
     }
 }
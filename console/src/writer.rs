@@ -0,0 +1,258 @@
+use color::ColorSystem;
+use segment::Segment;
+use style::{Style, NULL_STYLE};
+
+/// Renders consecutive segments tracking the style of the terminal, so only the changes
+/// between styles are written. The terminal is reset at the end of every line.
+pub(crate) struct StyleWriter {
+    color_system: Option<ColorSystem>,
+    legacy_windows: bool,
+    /// Style the terminal is rendering
+    style: Style,
+    /// Id of the open link
+    link_id: Option<String>,
+}
+
+impl StyleWriter {
+    pub(crate) fn new(color_system: Option<ColorSystem>, legacy_windows: bool) -> Self {
+        Self {
+            color_system,
+            legacy_windows,
+            style: Style::null(),
+            link_id: None,
+        }
+    }
+
+    pub(crate) fn write(&mut self, segment: &Segment, output: &mut String) {
+        let (text, style, is_control) = segment.as_tuple();
+        if is_control || self.color_system.is_none() {
+            output.push_str(text);
+            return;
+        }
+        let style = style.as_ref().unwrap_or(&NULL_STYLE);
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.reset(output);
+                output.push('\n');
+            }
+            if !line.is_empty() {
+                self.set_style(style, output);
+                output.push_str(line);
+            }
+        }
+    }
+
+    /// Reset the terminal to the default style
+    pub(crate) fn reset(&mut self, output: &mut String) {
+        self.set_style(&NULL_STYLE, output);
+    }
+
+    fn set_style(&mut self, style: &Style, output: &mut String) {
+        let color_system = match self.color_system {
+            Some(color_system) => color_system,
+            None => return,
+        };
+        let link = style.link().as_ref().filter(|_| !self.legacy_windows);
+        let link_id = link.map(|_| style.link_id());
+        if self.link_id.is_some() && self.link_id.as_deref() != link_id {
+            output.push_str("\x1b]8;;\x1b\\");
+            self.link_id = None;
+        }
+        let codes = style.ansi_transition_codes(&self.style, color_system);
+        if !codes.is_empty() {
+            output.push_str(&format!("\x1b[{}m", codes));
+            if codes == "0" || codes.starts_with("0;") {
+                // decoders may drop the link on a reset, open it again to be safe
+                self.link_id = None;
+            }
+        }
+        if let (Some(link), None) = (link, &self.link_id) {
+            output.push_str(&format!("\x1b]8;id={};{}\x1b\\", style.link_id(), link));
+            self.link_id = Some(style.link_id().to_string());
+        }
+        self.style = style.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ansi::AnsiDecoder;
+    use color::Color;
+    use proptest::prelude::*;
+    use style::{StyleAttribute, StyleBuilder, UnderlineStyle};
+
+    fn render(segments: &[Segment], color_system: ColorSystem) -> String {
+        let mut writer = StyleWriter::new(Some(color_system), false);
+        let mut output = String::new();
+        for segment in segments {
+            writer.write(segment, &mut output);
+        }
+        writer.reset(&mut output);
+        output
+    }
+
+    /// Every segment wrapped in its own codes
+    fn render_each(segments: &[Segment], color_system: ColorSystem) -> String {
+        segments
+            .iter()
+            .map(|segment| match segment.as_tuple() {
+                (text, Some(style), _) => style.render(text, Some(color_system), Some(false)),
+                (text, None, _) => text.to_string(),
+            })
+            .collect()
+    }
+
+    /// What the terminal shows for a style: enabled attributes, colors, underline style and
+    /// color, and link. An underline style replaces a straight underline.
+    type Appearance = (
+        StyleAttribute,
+        Option<Color>,
+        Option<Color>,
+        Option<UnderlineStyle>,
+        Option<Color>,
+        Option<String>,
+    );
+
+    fn appearance(style: &Style) -> Appearance {
+        let color = |color: Option<&Color>| color.filter(|color| !color.is_default()).cloned();
+        let mut attributes = style.enabled_attributes();
+        if style.underline_style().is_some() {
+            attributes.remove(StyleAttribute::UNDERLINE);
+        }
+        (
+            attributes,
+            color(style.color()),
+            color(style.background_color()),
+            style.underline_style(),
+            color(style.underline_color()),
+            style.link().clone(),
+        )
+    }
+
+    /// Plain text and appearance of every character of every line. A plain character is
+    /// appended, so codes after the last line break don't count as an empty line.
+    fn decode(output: &str) -> Vec<(String, Vec<Appearance>)> {
+        AnsiDecoder::new()
+            .decode(&format!("{}.", output))
+            .map(|text| {
+                let appearances = (0..text.plain().len())
+                    .map(|offset| appearance(&text.get_style_at_offset(offset)))
+                    .collect();
+                (text.plain().to_string(), appearances)
+            })
+            .collect()
+    }
+
+    fn color() -> impl Strategy<Value = Option<Color>> {
+        prop_oneof![
+            Just(None),
+            any::<u8>().prop_map(|number| Some(Color::from_ansi(number))),
+            any::<(u8, u8, u8)>().prop_map(|rgb| Some(Color::from_rgb(rgb))),
+        ]
+    }
+
+    fn underline_style() -> impl Strategy<Value = Option<UnderlineStyle>> {
+        prop_oneof![
+            3 => Just(None),
+            1 => Just(Some(UnderlineStyle::Curly)),
+            1 => Just(Some(UnderlineStyle::Dotted)),
+            1 => Just(Some(UnderlineStyle::Dashed)),
+        ]
+    }
+
+    fn style() -> impl Strategy<Value = Style> {
+        let link = prop_oneof![3 => Just(None), 1 => Just(Some("https://example.org"))];
+        let underline = (underline_style(), color());
+        (
+            any::<u16>(),
+            any::<u16>(),
+            color(),
+            color(),
+            underline,
+            link,
+        )
+            .prop_map(
+                |(
+                    set,
+                    enabled,
+                    color,
+                    background_color,
+                    (underline_style, underline_color),
+                    link,
+                )| {
+                    let mut builder = StyleBuilder::new();
+                    for (index, flag) in StyleAttribute::all_flags().iter().enumerate() {
+                        if set & (1 << index) != 0 {
+                            builder = builder.with_attribute(*flag, enabled & (1 << index) != 0);
+                        }
+                    }
+                    if let Some(color) = color {
+                        builder = builder.with_color(color);
+                    }
+                    if let Some(background_color) = background_color {
+                        builder = builder.with_background_color(background_color);
+                    }
+                    if let Some(underline_style) = underline_style {
+                        builder = builder.with_underline_style(underline_style);
+                    }
+                    if let Some(underline_color) = underline_color {
+                        builder = builder.with_underline_color(underline_color);
+                    }
+                    if let Some(link) = link {
+                        builder = builder.with_link(link);
+                    }
+                    builder.build()
+                },
+            )
+    }
+
+    fn segments(text: &'static str) -> impl Strategy<Value = Vec<Segment>> {
+        let segment = (text, proptest::option::of(style()))
+            .prop_map(|(text, style)| Segment::new(&text, style, false));
+        proptest::collection::vec(segment, 0..12)
+    }
+
+    fn system() -> impl Strategy<Value = ColorSystem> {
+        prop_oneof![
+            Just(ColorSystem::Standard),
+            Just(ColorSystem::EightBit),
+            Just(ColorSystem::TrueColor),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_equivalent_output(segments in segments("[ab\n]{0,4}"), color_system in system()) {
+            prop_assert_eq!(
+                decode(&render(&segments, color_system)),
+                decode(&render_each(&segments, color_system))
+            );
+        }
+
+        #[test]
+        // resetting at line ends may take more codes than wrapping lines in a single style
+        fn test_shorter_output(segments in segments("[ab]{0,4}"), color_system in system()) {
+            prop_assert!(
+                render(&segments, color_system).len()
+                    <= render_each(&segments, color_system).len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let bold = Style::parse("bold").unwrap();
+        let bold_red = Style::parse("bold red").unwrap();
+        let segments = [
+            Segment::new("foo", Some(bold.clone()), false),
+            Segment::new("bar", Some(bold_red), false),
+            Segment::new(" baz\nqux", Some(bold), false),
+            Segment::new("\n", None, false),
+        ];
+        assert_eq!(
+            render(&segments, ColorSystem::TrueColor),
+            "\x1b[1mfoo\x1b[31mbar\x1b[39m baz\x1b[0m\n\x1b[1mqux\x1b[0m\n"
+        );
+    }
+}
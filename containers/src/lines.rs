@@ -1,10 +1,8 @@
-use std::ops::{Deref, DerefMut};
-
 use console::{
     options::{JustifyMethod, OverflowMethod},
     Console,
 };
-use text::Text;
+use text::{justify_lines, Text};
 
 pub struct Lines {
     inner: Vec<Text>,
@@ -26,19 +24,13 @@ impl Lines {
 
     pub fn justify(
         &mut self,
-        console: Console,
+        _console: &Console,
         width: usize,
         justify: Option<JustifyMethod>,
         overflow: Option<OverflowMethod>,
     ) {
         let justify = justify.unwrap_or(JustifyMethod::Left);
         let overflow = overflow.unwrap_or(OverflowMethod::Fold);
-        match justify {
-            JustifyMethod::Full => {}
-            JustifyMethod::Left => {}
-            JustifyMethod::Center => {}
-            JustifyMethod::Right => {}
-        }
-        unimplemented!()
+        justify_lines(&mut self.inner, width, justify, overflow);
     }
 }
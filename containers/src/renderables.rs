@@ -7,6 +7,12 @@ pub struct Renderables<T>(Vec<T>)
 where
     T: Renderable + Clone;
 
+impl<T: Renderable + Clone> Default for Renderables<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: Renderable + Clone> Renderables<T> {
     pub fn from_slice(renderables: &[T]) -> Self {
         Self(renderables.to_vec())
@@ -38,7 +44,7 @@ impl<T: Renderable + Clone> Deref for Renderables<T> {
     }
 }
 
-impl<T: Renderable + Clone> Measure for Renderables<T> {
+impl<T: Renderable + Measure + Clone> Measure for Renderables<T> {
     fn measure(&self, console: &Console, max_width: usize) -> Measurement {
         let dimensions: Vec<Measurement> = self
             .0
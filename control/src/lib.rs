@@ -3,7 +3,60 @@ use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 
 lazy_static::lazy_static! {
-pub static ref STRIP_CONTROL_CODES : HashSet<char> = [8u8, 11, 12, 13].iter().map(|code| *code as char).collect();
+pub static ref STRIP_CONTROL_CODES : HashSet<char> = [7u8, 8, 11, 12, 13].iter().map(|code| *code as char).collect();
+}
+
+/// Non printable terminal codes and the parameters they take
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ControlType {
+    Bell,
+    CarriageReturn,
+    /// Move the cursor to the top left corner
+    Home,
+    /// Clear the screen
+    Clear,
+    ShowCursor,
+    HideCursor,
+    EnableAltScreen,
+    DisableAltScreen,
+    CursorUp(usize),
+    CursorDown(usize),
+    CursorForward(usize),
+    CursorBackward(usize),
+    /// Move the cursor to a column, starting from 0
+    CursorMoveToColumn(usize),
+    /// Move the cursor to a column and row, starting from 0
+    CursorMoveTo {
+        x: usize,
+        y: usize,
+    },
+    /// Erase the line from the cursor to the end (0), to the start (1) or the whole line (2)
+    EraseInLine(u8),
+    SetWindowTitle(String),
+}
+
+impl ControlType {
+    /// The escape sequence of the control code
+    pub fn escape_sequence(&self) -> String {
+        match self {
+            Self::Bell => "\x07".to_string(),
+            Self::CarriageReturn => "\r".to_string(),
+            Self::Home => "\x1b[H".to_string(),
+            Self::Clear => "\x1b[2J".to_string(),
+            Self::ShowCursor => "\x1b[?25h".to_string(),
+            Self::HideCursor => "\x1b[?25l".to_string(),
+            Self::EnableAltScreen => "\x1b[?1049h".to_string(),
+            Self::DisableAltScreen => "\x1b[?1049l".to_string(),
+            Self::CursorUp(count) => format!("\x1b[{}A", count),
+            Self::CursorDown(count) => format!("\x1b[{}B", count),
+            Self::CursorForward(count) => format!("\x1b[{}C", count),
+            Self::CursorBackward(count) => format!("\x1b[{}D", count),
+            Self::CursorMoveToColumn(x) => format!("\x1b[{}G", x + 1),
+            Self::CursorMoveTo { x, y } => format!("\x1b[{};{}H", y + 1, x + 1),
+            Self::EraseInLine(mode) => format!("\x1b[{}K", mode),
+            Self::SetWindowTitle(title) => format!("\x1b]0;{}\x07", title),
+        }
+    }
 }
 
 /// A renderable that inserts a control code (non printable but may move cursor)
@@ -17,14 +70,161 @@ impl Control {
             control_codes: Segment::control(control_codes, None),
         }
     }
+
+    pub fn from_codes(codes: &[ControlType]) -> Self {
+        let control_codes: String = codes.iter().map(ControlType::escape_sequence).collect();
+        Self::new(&control_codes)
+    }
+
+    pub fn bell() -> Self {
+        Self::from_codes(&[ControlType::Bell])
+    }
+
+    /// Move the cursor to the top left corner
+    pub fn home() -> Self {
+        Self::from_codes(&[ControlType::Home])
+    }
+
+    /// Move the cursor relative to its current position
+    pub fn move_by(x: isize, y: isize) -> Self {
+        let mut codes = Vec::new();
+        if x > 0 {
+            codes.push(ControlType::CursorForward(x as usize));
+        } else if x < 0 {
+            codes.push(ControlType::CursorBackward(-x as usize));
+        }
+        if y > 0 {
+            codes.push(ControlType::CursorDown(y as usize));
+        } else if y < 0 {
+            codes.push(ControlType::CursorUp(-y as usize));
+        }
+        Self::from_codes(&codes)
+    }
+
+    /// Move the cursor to a column, and optionally up or down a number of rows
+    pub fn move_to_column(x: usize, y: isize) -> Self {
+        let mut codes = vec![ControlType::CursorMoveToColumn(x)];
+        if y > 0 {
+            codes.push(ControlType::CursorDown(y as usize));
+        } else if y < 0 {
+            codes.push(ControlType::CursorUp(-y as usize));
+        }
+        Self::from_codes(&codes)
+    }
+
+    /// Move the cursor to a column and row, starting from 0
+    pub fn move_to(x: usize, y: usize) -> Self {
+        Self::from_codes(&[ControlType::CursorMoveTo { x, y }])
+    }
+
+    /// Clear the screen
+    pub fn clear() -> Self {
+        Self::from_codes(&[ControlType::Clear])
+    }
+
+    pub fn show_cursor(show: bool) -> Self {
+        Self::from_codes(&[if show {
+            ControlType::ShowCursor
+        } else {
+            ControlType::HideCursor
+        }])
+    }
+
+    /// Enable or disable the alternate screen
+    pub fn alt_screen(enable: bool) -> Self {
+        if enable {
+            Self::from_codes(&[ControlType::EnableAltScreen, ControlType::Home])
+        } else {
+            Self::from_codes(&[ControlType::DisableAltScreen])
+        }
+    }
+
+    /// Set the title of the terminal window
+    pub fn title(title: &str) -> Self {
+        Self::from_codes(&[ControlType::SetWindowTitle(title.to_string())])
+    }
+
+    pub fn segment(&self) -> &Segment {
+        &self.control_codes
+    }
 }
 
 pub fn strip_control_codes(text: &str, codes_set: &HashSet<char>) -> String {
     text.chars().filter(|c| !codes_set.contains(c)).collect()
 }
 
+/// Replace control codes with their escaped representation, to make them visible
+pub fn escape_control_codes(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\x07' => escaped.push_str("\\a"),
+            '\x08' => escaped.push_str("\\b"),
+            '\x0b' => escaped.push_str("\\v"),
+            '\x0c' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '\x1b' => escaped.push_str("\\x1b"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl Display for Control {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.control_codes.text())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control() {
+        assert_eq!(Control::bell().to_string(), "\x07");
+        assert_eq!(Control::home().to_string(), "\x1b[H");
+        assert_eq!(Control::clear().to_string(), "\x1b[2J");
+        assert_eq!(Control::show_cursor(false).to_string(), "\x1b[?25l");
+        assert_eq!(Control::alt_screen(true).to_string(), "\x1b[?1049h\x1b[H");
+        assert_eq!(Control::alt_screen(false).to_string(), "\x1b[?1049l");
+        assert_eq!(Control::title("hello").to_string(), "\x1b]0;hello\x07");
+        assert!(Control::title("hello").segment().as_tuple().2);
+    }
+
+    #[test]
+    fn test_move() {
+        assert_eq!(Control::move_to(2, 3).to_string(), "\x1b[4;3H");
+        assert_eq!(Control::move_by(-1, 2).to_string(), "\x1b[1D\x1b[2B");
+        assert_eq!(Control::move_by(0, 0).to_string(), "");
+        assert_eq!(
+            Control::move_to_column(10, -2).to_string(),
+            "\x1b[11G\x1b[2A"
+        );
+        assert_eq!(
+            Control::from_codes(&[ControlType::CarriageReturn, ControlType::EraseInLine(2)])
+                .to_string(),
+            "\r\x1b[2K"
+        );
+    }
+
+    #[test]
+    fn test_strip_control_codes() {
+        assert_eq!(
+            strip_control_codes("foo\x07\rbar", &STRIP_CONTROL_CODES),
+            "foobar"
+        );
+    }
+
+    #[test]
+    fn test_escape_control_codes() {
+        assert_eq!(
+            escape_control_codes(&Control::move_to(0, 0).to_string()),
+            "\\x1b[1;1H"
+        );
+        assert_eq!(
+            escape_control_codes("\x07\x08\x0b\x0c\r"),
+            "\\a\\b\\v\\f\\r"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+mod codes;
+
+use std::fmt;
+
+use style::Style;
+
+use crate::codes::EMOJI;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("No emoji called {0:?}")]
+    NoEmoji(String),
+}
+
+/// Presentation of an emoji, selected with a variation selector
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EmojiVariant {
+    /// Monochrome, like the surrounding text
+    Text,
+    /// Colorful, like a picture
+    Emoji,
+}
+
+impl EmojiVariant {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "emoji" => Some(Self::Emoji),
+            _ => None,
+        }
+    }
+
+    /// The variation selector character
+    pub fn selector(&self) -> char {
+        match self {
+            Self::Text => '\u{FE0E}',
+            Self::Emoji => '\u{FE0F}',
+        }
+    }
+}
+
+/// Get the emoji of a shortcode name, without the surrounding colons
+pub fn get_emoji(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    EMOJI
+        .binary_search_by_key(&name.as_str(), |(code, _)| code)
+        .ok()
+        .map(|index| EMOJI[index].1)
+}
+
+/// Replace `:name:` shortcodes with emoji, leaving unknown codes untouched.
+/// A code may select a variant with a `-emoji` or `-text` suffix, like `:smile-text:`,
+/// otherwise the default variant, if any, is used.
+pub fn replace_emoji(text: &str, default_variant: Option<EmojiVariant>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(':') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = match after.find(|c: char| c == ':' || c.is_whitespace()) {
+            Some(end) if after[end..].starts_with(':') => end,
+            _ => {
+                result.push(':');
+                rest = after;
+                continue;
+            }
+        };
+        let code = &after[..end];
+        let (name, variant) = match code.rsplit_once('-') {
+            Some((name, variant)) => match EmojiVariant::from_name(variant) {
+                Some(variant) => (name, Some(variant)),
+                None => (code, None),
+            },
+            None => (code, None),
+        };
+        match get_emoji(name) {
+            Some(emoji) => {
+                result.push_str(emoji);
+                if let Some(variant) = variant.or(default_variant) {
+                    result.push(variant.selector());
+                }
+            }
+            None => result.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// A single emoji, from its shortcode name
+#[derive(Clone, Debug)]
+pub struct Emoji {
+    name: String,
+    emoji: &'static str,
+    style: Option<Style>,
+    variant: Option<EmojiVariant>,
+}
+
+impl Emoji {
+    pub fn new(name: &str) -> Result<Self, Error> {
+        let emoji = get_emoji(name).ok_or_else(|| Error::NoEmoji(name.to_string()))?;
+        Ok(Self {
+            name: name.to_string(),
+            emoji,
+            style: None,
+            variant: None,
+        })
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_variant(mut self, variant: EmojiVariant) -> Self {
+        self.variant = Some(variant);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn style(&self) -> Option<&Style> {
+        self.style.as_ref()
+    }
+}
+
+impl fmt::Display for Emoji {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.emoji)?;
+        match self.variant {
+            Some(variant) => write!(f, "{}", variant.selector()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_emoji() {
+        assert_eq!(get_emoji("rocket"), Some("🚀"));
+        assert_eq!(get_emoji("Thumbs_Up"), Some("👍"));
+        assert_eq!(get_emoji("not_an_emoji"), None);
+    }
+
+    #[test]
+    fn test_replace_emoji() {
+        assert_eq!(
+            replace_emoji("Hello :rocket: world :vampire:", None),
+            "Hello 🚀 world 🧛"
+        );
+        assert_eq!(replace_emoji(":nope: :rocket:", None), ":nope: 🚀");
+        assert_eq!(replace_emoji("time 10:30 :rocket:", None), "time 10:30 🚀");
+        assert_eq!(replace_emoji("a: :rocket:", None), "a: 🚀");
+        assert_eq!(replace_emoji("trailing :", None), "trailing :");
+    }
+
+    #[test]
+    fn test_replace_emoji_variants() {
+        assert_eq!(replace_emoji(":smile-emoji:", None), "😄\u{FE0F}");
+        assert_eq!(replace_emoji(":smile-text:", None), "😄\u{FE0E}");
+        assert_eq!(
+            replace_emoji(":smile:", Some(EmojiVariant::Text)),
+            "😄\u{FE0E}"
+        );
+        assert_eq!(replace_emoji(":smile-other:", None), ":smile-other:");
+    }
+
+    #[test]
+    fn test_emoji() {
+        let emoji = Emoji::new("rocket").unwrap();
+        assert_eq!(emoji.to_string(), "🚀");
+        assert_eq!(
+            emoji.with_variant(EmojiVariant::Emoji).to_string(),
+            "🚀\u{FE0F}"
+        );
+        assert!(matches!(Emoji::new("nope"), Err(Error::NoEmoji(_))));
+    }
+}
@@ -0,0 +1,84 @@
+use lazy_static::lazy_static;
+use text::Text;
+use theme::Theme;
+
+use crate::{Highlighter, RegexHighlighter};
+
+const ISO8601_HIGHLIGHTS: &[&str] = &[
+    // Calendar month (e.g. 2008-08). The hyphen is required
+    r"^(?P<year>[0-9]{4})-(?P<month>1[0-2]|0[1-9])$",
+    // Calendar date w/o hyphens (e.g. 20080830)
+    r"^(?P<date>(?P<year>[0-9]{4})(?P<month>1[0-2]|0[1-9])(?P<day>3[01]|0[1-9]|[12][0-9]))$",
+    // Ordinal date (e.g. 2008-243). The hyphen is optional
+    r"^(?P<date>(?P<year>[0-9]{4})-?(?P<day>36[0-6]|3[0-5][0-9]|[12][0-9]{2}|0[1-9][0-9]|00[1-9]))$",
+    // Week of the year (e.g., 2008-W35). The hyphen is optional
+    r"^(?P<date>(?P<year>[0-9]{4})-?W(?P<week>5[0-3]|[1-4][0-9]|0[1-9]))$",
+    // Week date (e.g., 2008-W35-6). The hyphens are optional
+    r"^(?P<date>(?P<year>[0-9]{4})-?W(?P<week>5[0-3]|[1-4][0-9]|0[1-9])-?(?P<day>[1-7]))$",
+    // Hours and minutes (e.g., 17:21). The colon is optional
+    r"^(?P<time>(?P<hour>2[0-3]|[01][0-9]):?(?P<minute>[0-5][0-9]))$",
+    // Hours, minutes, and seconds w/o colons (e.g., 172159)
+    r"^(?P<time>(?P<hour>2[0-3]|[01][0-9])(?P<minute>[0-5][0-9])(?P<second>[0-5][0-9]))$",
+    // Time zone designator (e.g., Z, +07 or +07:00). The colons and the minutes are optional
+    r"^(?P<timezone>(Z|[+-](?:2[0-3]|[01][0-9])(?::?(?:[0-5][0-9]))?))$",
+    // Hours, minutes, and seconds with time zone designator (e.g., 172159+0700)
+    r"^(?P<time>(?P<hour>2[0-3]|[01][0-9])(?P<minute>[0-5][0-9])(?P<second>[0-5][0-9]))(?P<timezone>Z|[+-](?:2[0-3]|[01][0-9])(?::?(?:[0-5][0-9]))?)$",
+    // Calendar date with hours, minutes, and seconds (e.g., 2008-08-30 17:21:59 or 20080830 172159).
+    // Either both the hyphens and colons are given, or none of them
+    r"^(?P<date>(?P<year>[0-9]{4})-(?P<month>1[0-2]|0[1-9])-(?P<day>3[01]|0[1-9]|[12][0-9])) (?P<time>(?P<hour>2[0-3]|[01][0-9]):(?P<minute>[0-5][0-9]):(?P<second>[0-5][0-9]))$",
+    r"^(?P<date>(?P<year>[0-9]{4})(?P<month>1[0-2]|0[1-9])(?P<day>3[01]|0[1-9]|[12][0-9])) (?P<time>(?P<hour>2[0-3]|[01][0-9])(?P<minute>[0-5][0-9])(?P<second>[0-5][0-9]))$",
+    // Date, with optional time zone (e.g., 2008-08-30 or 2008-08-30+07:00)
+    r"^(?P<date>(?P<year>-?(?:[1-9][0-9]*)?[0-9]{4})-(?P<month>1[0-2]|0[1-9])-(?P<day>3[01]|0[1-9]|[12][0-9]))(?P<timezone>Z|[+-](?:2[0-3]|[01][0-9]):[0-5][0-9])?$",
+    // Time, with optional fractional seconds and time zone (e.g., 01:45:36 or 01:45:36.123+07:00)
+    r"^(?P<time>(?P<hour>2[0-3]|[01][0-9]):(?P<minute>[0-5][0-9]):(?P<second>[0-5][0-9])(?P<frac>\.[0-9]+)?)(?P<timezone>Z|[+-](?:2[0-3]|[01][0-9]):[0-5][0-9])?$",
+    // Date and time, with optional fractional seconds and time zone (e.g., 2008-08-30T01:45:36.123Z)
+    r"^(?P<date>(?P<year>-?(?:[1-9][0-9]*)?[0-9]{4})-(?P<month>1[0-2]|0[1-9])-(?P<day>3[01]|0[1-9]|[12][0-9]))T(?P<time>(?P<hour>2[0-3]|[01][0-9]):(?P<minute>[0-5][0-9]):(?P<second>[0-5][0-9])(?P<ms>\.[0-9]+)?)(?P<timezone>Z|[+-](?:2[0-3]|[01][0-9]):[0-5][0-9])?$",
+];
+
+lazy_static! {
+    static ref ISO8601_HIGHLIGHTER: RegexHighlighter =
+        RegexHighlighter::new("iso8601.", ISO8601_HIGHLIGHTS).unwrap();
+}
+
+/// Highlights the date, time and time zone of texts that are an ISO 8601 date or time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ISO8601Highlighter;
+
+impl Highlighter for ISO8601Highlighter {
+    fn highlight(&self, text: &mut Text, theme: &Theme) {
+        ISO8601_HIGHLIGHTER.highlight(text, theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use style::Style;
+
+    fn styles_at(plain: &str, offsets: &[usize]) -> Vec<Style> {
+        let text = ISO8601Highlighter.apply(&Text::new(plain, None), &Theme::default());
+        offsets
+            .iter()
+            .map(|offset| text.get_style_at_offset(*offset))
+            .collect()
+    }
+
+    #[test]
+    fn test_iso8601() {
+        let theme = Theme::default();
+        let date = theme.get("iso8601.date").unwrap().clone();
+        let time = theme.get("iso8601.time").unwrap().clone();
+        let timezone = theme.get("iso8601.timezone").unwrap().clone();
+        assert_eq!(
+            styles_at("2008-08-30T01:45:36.123Z", &[0, 11, 23]),
+            [date.clone(), time.clone(), timezone]
+        );
+        assert_eq!(
+            styles_at("2008-08-30 17:21:59", &[0, 10, 11]),
+            [date.clone(), Style::null(), time]
+        );
+        assert_eq!(styles_at("20080830", &[0]), [date]);
+        assert_eq!(styles_at("2008-08-30 172159", &[0]), [Style::null()]);
+        assert_eq!(styles_at("not a date", &[0]), [Style::null()]);
+    }
+}
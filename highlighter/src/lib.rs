@@ -0,0 +1,100 @@
+mod iso8601;
+mod repr;
+
+use regex::Regex;
+use text::Text;
+use theme::Theme;
+
+pub use self::iso8601::ISO8601Highlighter;
+pub use self::repr::ReprHighlighter;
+
+/// Applies styles to portions of a text, like numbers or strings
+pub trait Highlighter {
+    /// Highlight a text in place, with styles looked up in the theme
+    fn highlight(&self, text: &mut Text, theme: &Theme);
+
+    /// Get a highlighted copy of a text
+    fn apply(&self, text: &Text, theme: &Theme) -> Text {
+        let mut text = text.clone();
+        self.highlight(&mut text, theme);
+        text
+    }
+}
+
+/// A highlighter that leaves the text untouched
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NullHighlighter;
+
+impl Highlighter for NullHighlighter {
+    fn highlight(&self, _text: &mut Text, _theme: &Theme) {}
+}
+
+/// Highlights the named groups of regular expressions, with the theme style of the group name
+/// prefixed by the base style. Groups without a theme style are left untouched.
+#[derive(Clone, Debug)]
+pub struct RegexHighlighter {
+    base_style: String,
+    highlights: Vec<Regex>,
+}
+
+impl RegexHighlighter {
+    pub fn new(base_style: &str, highlights: &[&str]) -> Result<Self, regex::Error> {
+        Ok(Self {
+            base_style: base_style.to_string(),
+            highlights: highlights
+                .iter()
+                .map(|highlight| Regex::new(highlight))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl Highlighter for RegexHighlighter {
+    fn highlight(&self, text: &mut Text, theme: &Theme) {
+        let plain = text.plain().to_string();
+        for highlight in &self.highlights {
+            for captures in highlight.captures_iter(&plain) {
+                for name in highlight.capture_names().flatten() {
+                    let group = match captures.name(name) {
+                        Some(group) if !group.is_empty() => group,
+                        _ => continue,
+                    };
+                    if let Some(style) = theme.get(&format!("{}{}", self.base_style, name)) {
+                        text.stylize(style.clone(), group.start(), Some(group.end()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use style::Style;
+
+    #[test]
+    fn test_regex_highlighter() {
+        let highlighter =
+            RegexHighlighter::new("example.", &[r"(?P<email>[\w.-]+@[\w-]+\.[\w.]+)"]).unwrap();
+        let theme = Theme::from_definitions(vec![("example.email", "bold magenta")], None).unwrap();
+        let text = highlighter.apply(&Text::new("Send to foo@example.org now", None), &theme);
+        assert_eq!(text.get_style_at_offset(7), Style::null());
+        assert_eq!(
+            text.get_style_at_offset(8),
+            Style::parse("bold magenta").unwrap()
+        );
+        assert_eq!(text.get_style_at_offset(23), Style::null());
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(RegexHighlighter::new("example.", &["(?P<open>"]).is_err());
+    }
+
+    #[test]
+    fn test_null_highlighter() {
+        let text = NullHighlighter.apply(&Text::new("123", None), &Theme::default());
+        assert!(text.spans().is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use lazy_static::lazy_static;
+use text::Text;
+use theme::Theme;
+
+use crate::{Highlighter, RegexHighlighter};
+
+// Lookbehinds of the original expressions are written with word boundaries
+const REPR_HIGHLIGHTS: &[&str] = &[
+    r"(?P<tag_start><)(?P<tag_name>[-\w.:|]*)(?P<tag_contents>[\w\W]*)(?P<tag_end>>)",
+    r#"(?P<attrib_name>[\w_]{1,50})=(?P<attrib_value>"?[\w_]+"?)?"#,
+    r"(?P<brace>[\]\[{}()])",
+    concat!(
+        r"(?P<ipv4>[0-9]{1,3}\.[0-9]{1,3}\.[0-9]{1,3}\.[0-9]{1,3})",
+        r"|(?P<ipv6>([A-Fa-f0-9]{1,4}::?){1,7}[A-Fa-f0-9]{1,4})",
+        r"|(?P<eui64>(?:[0-9A-Fa-f]{1,2}-){7}[0-9A-Fa-f]{1,2}|(?:[0-9A-Fa-f]{1,2}:){7}[0-9A-Fa-f]{1,2}|(?:[0-9A-Fa-f]{4}\.){3}[0-9A-Fa-f]{4})",
+        r"|(?P<eui48>(?:[0-9A-Fa-f]{1,2}-){5}[0-9A-Fa-f]{1,2}|(?:[0-9A-Fa-f]{1,2}:){5}[0-9A-Fa-f]{1,2}|(?:[0-9A-Fa-f]{4}\.){2}[0-9A-Fa-f]{4})",
+        r"|(?P<uuid>[a-fA-F0-9]{8}-[a-fA-F0-9]{4}-[a-fA-F0-9]{4}-[a-fA-F0-9]{4}-[a-fA-F0-9]{12})",
+        r"|(?P<call>[\w.]*?)\(",
+        r"|\b(?P<bool_true>True|true)\b|\b(?P<bool_false>False|false)\b|\b(?P<none>None|null)\b",
+        r"|(?P<ellipsis>\.\.\.)",
+        r"|(?P<number_complex>(?:\B-[0-9]+|\b[0-9]+)\.?[0-9]*(?:e[-+]?\d+?)?(?:[-+](?:[0-9]+\.?[0-9]*(?:e[-+]?\d+)?))?j)",
+        r"|(?P<number>(?:\B-[0-9]+|\b[0-9]+)\.?[0-9]*(e[-+]?\d+?)?\b|0x[0-9a-fA-F]*)",
+        r"|(?P<path>\B(/[-\w._+]+)*/)(?P<filename>[-\w._+]*)?",
+        r#"|(?P<str>(?:\bb|\B)(?:'''(?:[^\\\n]|\\.)*?'''|'(?:[^'\\\n]|\\.)*'|"""(?:[^\\\n]|\\.)*?"""|"(?:[^"\\\n]|\\.)*"))"#,
+        r"|(?P<url>(file|https|http|ws|wss)://[-0-9a-zA-Z$_+!`(),.?/;:&=%#~@]*)",
+    ),
+];
+
+lazy_static! {
+    static ref REPR_HIGHLIGHTER: RegexHighlighter =
+        RegexHighlighter::new("repr.", REPR_HIGHLIGHTS).unwrap();
+}
+
+/// Highlights the text typically produced by `Debug` implementations: numbers, strings,
+/// booleans, `None`/`null`, calls, tags, paths, URLs, UUIDs and network addresses
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReprHighlighter;
+
+impl Highlighter for ReprHighlighter {
+    fn highlight(&self, text: &mut Text, theme: &Theme) {
+        REPR_HIGHLIGHTER.highlight(text, theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use style::Style;
+
+    /// Group names and contents of the portions of a text matched by the highlights
+    fn highlights(plain: &str) -> Vec<(String, String)> {
+        let mut highlights = Vec::new();
+        for highlight in &REPR_HIGHLIGHTER.highlights {
+            for captures in highlight.captures_iter(plain) {
+                for name in highlight.capture_names().flatten() {
+                    if let Some(group) = captures.name(name).filter(|group| !group.is_empty()) {
+                        highlights.push((name.to_string(), group.as_str().to_string()));
+                    }
+                }
+            }
+        }
+        highlights
+    }
+
+    fn has(plain: &str, name: &str, contents: &str) -> bool {
+        highlights(plain).contains(&(name.to_string(), contents.to_string()))
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert!(has("x = 12", "number", "12"));
+        assert!(has("(-1.5e10)", "number", "-1.5e10"));
+        assert!(has("0xff", "number", "0xff"));
+        assert!(has("1+2j", "number_complex", "1+2j"));
+        assert!(!has("abc12", "number", "12"));
+    }
+
+    #[test]
+    fn test_literals() {
+        assert!(has("Some(true)", "bool_true", "true"));
+        assert!(has("Some(true)", "call", "Some"));
+        assert!(has("[False]", "bool_false", "False"));
+        assert!(has("null", "none", "null"));
+        assert!(has("name: \"foo\", 'bar'", "str", "\"foo\""));
+        assert!(has("name: \"foo\", 'bar'", "str", "'bar'"));
+        assert!(has(r#""a \" b""#, "str", r#""a \" b""#));
+        assert!(has("...", "ellipsis", "..."));
+        assert!(has("Point { x: 1 }", "brace", "{"));
+    }
+
+    #[test]
+    fn test_addresses() {
+        assert!(has("127.0.0.1", "ipv4", "127.0.0.1"));
+        assert!(has(
+            "2001:0db8:85a3:0000:0000:8a2e:0370:7334",
+            "ipv6",
+            "2001:0db8:85a3:0000:0000:8a2e:0370:7334"
+        ));
+        assert!(has("00-1B-44-11-3A-B7", "eui48", "00-1B-44-11-3A-B7"));
+        assert!(has(
+            "00-1B-44-11-3A-B7-00-01",
+            "eui64",
+            "00-1B-44-11-3A-B7-00-01"
+        ));
+        assert!(has(
+            "id 67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "uuid",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        ));
+        assert!(has(
+            "see https://example.org?foo=bar#header",
+            "url",
+            "https://example.org?foo=bar#header"
+        ));
+    }
+
+    #[test]
+    fn test_paths_and_tags() {
+        assert!(has("foo /foo/bar/baz/egg.py word", "path", "/foo/bar/baz/"));
+        assert!(has("foo /foo/bar/baz/egg.py word", "filename", "egg.py"));
+        assert!(has("<Point x=1>", "tag_name", "Point"));
+        assert!(has("<Point x=1>", "attrib_name", "x"));
+        assert!(has("<Point x=1>", "attrib_value", "1"));
+    }
+
+    #[test]
+    fn test_highlight_text() {
+        let theme = Theme::default();
+        let text = ReprHighlighter.apply(&Text::new("count 42", None), &theme);
+        assert_eq!(text.get_style_at_offset(0), Style::null());
+        assert_eq!(
+            text.get_style_at_offset(6),
+            theme.get("repr.number").unwrap().clone()
+        );
+    }
+}
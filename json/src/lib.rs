@@ -0,0 +1,264 @@
+use std::str::FromStr;
+
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, Measurement};
+use serde_json::Value;
+use text::Text;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid JSON: {0}")]
+    ParseError(#[from] serde_json::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// A console renderable that pretty prints JSON
+#[derive(Clone, Debug)]
+pub struct Json {
+    value: Value,
+    indent: Option<usize>,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    highlight: bool,
+}
+
+impl Json {
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            indent: Some(2),
+            sort_keys: false,
+            ensure_ascii: false,
+            highlight: true,
+        }
+    }
+
+    /// Number of spaces to indent each level, or `None` to print in a single line
+    pub fn with_indent(mut self, indent: Option<usize>) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Print object keys in sorted order instead of their original one
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Escape non ASCII characters in strings
+    pub fn with_ensure_ascii(mut self, ensure_ascii: bool) -> Self {
+        self.ensure_ascii = ensure_ascii;
+        self
+    }
+
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    fn append(&self, console: &Console, text: &mut Text, content: &str, style_name: &str) {
+        let style = if self.highlight {
+            Some(console.get_style(style_name))
+        } else {
+            None
+        };
+        text.append(content, style);
+    }
+
+    fn encode_str(&self, value: &str) -> String {
+        let mut encoded = String::with_capacity(value.len() + 2);
+        encoded.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => encoded.push_str("\\\""),
+                '\\' => encoded.push_str("\\\\"),
+                '\n' => encoded.push_str("\\n"),
+                '\r' => encoded.push_str("\\r"),
+                '\t' => encoded.push_str("\\t"),
+                '\u{08}' => encoded.push_str("\\b"),
+                '\u{0c}' => encoded.push_str("\\f"),
+                c if c.is_control() || (self.ensure_ascii && !c.is_ascii()) => {
+                    let mut buffer = [0u16; 2];
+                    for unit in c.encode_utf16(&mut buffer) {
+                        encoded.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+                c => encoded.push(c),
+            }
+        }
+        encoded.push('"');
+        encoded
+    }
+
+    /// Separator between entries of a container, and the indentation of the entries and closing brace
+    fn new_line(&self, depth: usize) -> String {
+        match self.indent {
+            Some(indent) => format!("\n{}", " ".repeat(indent * depth)),
+            None => String::new(),
+        }
+    }
+
+    fn append_value(&self, console: &Console, text: &mut Text, value: &Value, depth: usize) {
+        match value {
+            Value::Null => self.append(console, text, "null", "json.null"),
+            Value::Bool(true) => self.append(console, text, "true", "json.bool_true"),
+            Value::Bool(false) => self.append(console, text, "false", "json.bool_false"),
+            Value::Number(number) => self.append(console, text, &number.to_string(), "json.number"),
+            Value::String(string) => {
+                self.append(console, text, &self.encode_str(string), "json.str")
+            }
+            Value::Array(values) => {
+                let values: Vec<_> = values.iter().map(|value| (None, value)).collect();
+                self.append_container(console, text, ("[", "]"), values, depth);
+            }
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                if self.sort_keys {
+                    entries.sort_by_key(|(key, _)| *key);
+                }
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (Some(key.as_str()), value))
+                    .collect();
+                self.append_container(console, text, ("{", "}"), entries, depth);
+            }
+        }
+    }
+
+    fn append_container(
+        &self,
+        console: &Console,
+        text: &mut Text,
+        (open, close): (&str, &str),
+        entries: Vec<(Option<&str>, &Value)>,
+        depth: usize,
+    ) {
+        self.append(console, text, open, "json.brace");
+        if !entries.is_empty() {
+            let separator = if self.indent.is_some() { "," } else { ", " };
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    text.append(separator, None);
+                }
+                text.append(&self.new_line(depth + 1), None);
+                if let Some(key) = key {
+                    self.append(console, text, &self.encode_str(key), "json.key");
+                    text.append(": ", None);
+                }
+                self.append_value(console, text, value, depth + 1);
+            }
+            text.append(&self.new_line(depth), None);
+        }
+        self.append(console, text, close, "json.brace");
+    }
+
+    /// Highlighted text of the encoded JSON
+    pub fn to_text(&self, console: &Console) -> Text {
+        let mut text = Text::new("", None).with_no_wrap(true);
+        self.append_value(console, &mut text, &self.value, 0);
+        text
+    }
+}
+
+impl Renderable for Json {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        console.render(&self.to_text(console), Some(options)).into()
+    }
+}
+
+impl Measure for Json {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        self.to_text(console).measure(console, max_width)
+    }
+}
+
+impl FromStr for Json {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<Self, Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+}
+
+/// Extends the console to print JSON strings
+pub trait PrintJson {
+    /// Pretty print a JSON string
+    fn print_json(&mut self, json: &str) -> Result<(), Error>;
+}
+
+impl PrintJson for Console {
+    fn print_json(&mut self, json: &str) -> Result<(), Error> {
+        let json = Json::from_str(json)?;
+        self.print(&json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+
+    fn console() -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build()
+    }
+
+    #[test]
+    fn test_indent() {
+        let json =
+            Json::from_str(r#"{"b": [1, 2.5], "a": {"c": null}, "d": [], "e": true}"#).unwrap();
+        assert_eq!(
+            json.to_text(&console()).plain(),
+            "{\n  \"b\": [\n    1,\n    2.5\n  ],\n  \"a\": {\n    \"c\": null\n  },\n  \"d\": [],\n  \"e\": true\n}"
+        );
+        assert_eq!(
+            json.clone()
+                .with_indent(None)
+                .with_sort_keys(true)
+                .to_text(&console())
+                .plain(),
+            r#"{"a": {"c": null}, "b": [1, 2.5], "d": [], "e": true}"#
+        );
+    }
+
+    #[test]
+    fn test_ensure_ascii() {
+        let json = Json::from_str(r#"["café \"🎉\""]"#)
+            .unwrap()
+            .with_indent(None);
+        assert_eq!(json.to_text(&console()).plain(), "[\"café \\\"🎉\\\"\"]");
+        assert_eq!(
+            json.with_ensure_ascii(true).to_text(&console()).plain(),
+            "[\"caf\\u00e9 \\\"\\ud83c\\udf89\\\"\"]"
+        );
+    }
+
+    #[test]
+    fn test_highlight() {
+        let console = console();
+        let text = Json::from_str(r#"{"key": false}"#)
+            .unwrap()
+            .with_indent(None)
+            .to_text(&console);
+        assert_eq!(text.get_style_at_offset(2), console.get_style("json.key"));
+        assert_eq!(
+            text.get_style_at_offset(9),
+            console.get_style("json.bool_false")
+        );
+    }
+
+    #[test]
+    fn test_print_json() {
+        let mut console = console();
+        console.begin_capture();
+        console.print_json("[1, \"a\"]").unwrap();
+        assert_eq!(console.end_capture(), "[\n  1,\n  \"a\"\n]\n");
+        assert!(console.print_json("[1,").is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::Debug;
+use std::io;
+use std::path::Path;
+
+use chrono::Local;
+use console::options::{ConsoleOptions, JustifyMethod};
+use console::traits::{RenderItem, RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, MeasuredRenderable, Measurement};
+use padding::PaddingDimensions;
+use panel::Panel;
+use pretty::Pretty;
+use table::{Column, Table};
+use text::Text;
+
+use crate::LogRender;
+
+/// Renderables printed one after the other in the message column
+struct Group(Vec<Box<dyn MeasuredRenderable>>);
+
+impl Renderable for Group {
+    fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+        RenderResult::new(
+            self.0
+                .iter()
+                .map(|renderable| RenderItem::Renderable(renderable)),
+        )
+    }
+}
+
+impl Measure for Group {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        let measurements: Vec<Measurement> = self
+            .0
+            .iter()
+            .map(|renderable| Measurement::get(console, renderable, Some(max_width)))
+            .collect();
+        Measurement::new(
+            measurements.iter().map(|m| m.minimum).max().unwrap_or(1),
+            measurements.iter().map(|m| m.maximum).max().unwrap_or(1),
+        )
+    }
+}
+
+/// A panel with a table of named values
+pub fn render_scope(
+    console: &Console,
+    locals: &[(&str, &dyn Debug)],
+    title: Option<Text>,
+) -> Panel {
+    let mut table = Table::grid()
+        .with_padding(PaddingDimensions::new(0, 1, 0, 0))
+        .with_pad_edge(false)
+        .with_column(Column::default().with_justify(JustifyMethod::Right))
+        .with_column(Column::default());
+    for (key, value) in locals {
+        let key_style = if key.starts_with('_') {
+            "scope.key.special"
+        } else {
+            "scope.key"
+        };
+        let key = Text::assemble(vec![
+            (*key, Some(console.get_style(key_style))),
+            (" =", Some(console.get_style("scope.equals"))),
+        ]);
+        table.add_row(vec![Box::new(key), Box::new(Pretty::from_debug(*value))]);
+    }
+    let mut panel =
+        Panel::fit(Box::new(table)).with_border_style(console.get_style("scope.border"));
+    if let Some(title) = title {
+        panel = panel.with_title(title);
+    }
+    panel
+}
+
+/// Debug printing to a console, in the same columns as the log handlers.
+/// Use the `rich_log!` macro to fill in the location of the caller.
+pub trait ConsoleLog {
+    /// Print renderables with the current time on the left and the caller location on the right
+    fn log(
+        &mut self,
+        objects: Vec<Box<dyn MeasuredRenderable>>,
+        file: &str,
+        line: u32,
+    ) -> io::Result<()>;
+
+    /// Same as `log`, followed by a table of the named values
+    fn log_locals(
+        &mut self,
+        objects: Vec<Box<dyn MeasuredRenderable>>,
+        locals: &[(&str, &dyn Debug)],
+        file: &str,
+        line: u32,
+    ) -> io::Result<()>;
+}
+
+impl ConsoleLog for Console {
+    fn log(
+        &mut self,
+        objects: Vec<Box<dyn MeasuredRenderable>>,
+        file: &str,
+        line: u32,
+    ) -> io::Result<()> {
+        let file_name = Path::new(file)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(file);
+        // the last time is kept by each console, so repeated times are omitted across calls
+        let mut log_render = LogRender::new()
+            .with_show_level(false)
+            .with_last_time(self.last_log_time().map(str::to_string));
+        let table = log_render.render(
+            self,
+            Box::new(Group(objects)),
+            Local::now(),
+            Text::new("", None),
+            Some(file_name),
+            Some(line),
+        );
+        self.set_last_log_time(log_render.last_time().map(str::to_string));
+        self.print(&table)
+    }
+
+    fn log_locals(
+        &mut self,
+        mut objects: Vec<Box<dyn MeasuredRenderable>>,
+        locals: &[(&str, &dyn Debug)],
+        file: &str,
+        line: u32,
+    ) -> io::Result<()> {
+        let title = Text::new("locals", Some(self.get_style("italic")));
+        objects.push(Box::new(render_scope(self, locals, Some(title))));
+        self.log(objects, file, line)
+    }
+}
+
+/// Log renderables to a console with the location of the call.
+/// Values after a `;` are shown by name in a table of locals.
+///
+/// ```
+/// # use console::ConsoleBuilder;
+/// # use logging::rich_log;
+/// # let mut console = ConsoleBuilder::new().with_file(Box::new(std::io::sink())).build();
+/// # let (attempt, delay) = (2, 0.5);
+/// rich_log!(console, "[bold]connected", String::from("to the database")).unwrap();
+/// rich_log!(console, "retrying"; attempt, delay).unwrap();
+/// ```
+#[macro_export]
+macro_rules! rich_log {
+    ($console:expr $(, $object:expr)* ; $($local:ident),+ $(,)?) => {
+        $crate::ConsoleLog::log_locals(
+            &mut $console,
+            vec![$(Box::new($object) as Box<dyn $crate::MeasuredRenderable>),*],
+            &[$((stringify!($local), &$local as &dyn ::std::fmt::Debug)),+],
+            file!(),
+            line!(),
+        )
+    };
+    ($console:expr $(, $object:expr)* $(,)?) => {
+        $crate::ConsoleLog::log(
+            &mut $console,
+            vec![$(Box::new($object) as Box<dyn $crate::MeasuredRenderable>),*],
+            file!(),
+            line!(),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use console::ConsoleBuilder;
+
+    fn console() -> console::Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(60)
+            .build()
+    }
+
+    /// Output without the time column, which changes between runs
+    fn without_time(output: &str) -> Vec<&str> {
+        output.lines().map(|line| &line[11..]).collect()
+    }
+
+    #[test]
+    fn test_log() {
+        let mut console = console();
+        console.begin_capture();
+        rich_log!(console, "Hello", String::from("World")).unwrap();
+        let output = console.end_capture();
+        let line = line!() - 2;
+        let path = format!("console_log.rs:{}", line);
+        assert_eq!(
+            without_time(&output),
+            [
+                format!("Hello{}{}", " ".repeat(49 - path.len() - 5), path),
+                format!("World{}", " ".repeat(49 - 5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_markup() {
+        let mut console = console();
+        console.begin_capture();
+        rich_log!(console, "[bold]connected").unwrap();
+        let output = console.end_capture();
+        assert!(without_time(&output)[0].starts_with("connected "));
+    }
+
+    #[test]
+    fn test_log_time_per_console() {
+        let mut first = console();
+        let mut second = console();
+        first.begin_capture();
+        rich_log!(first, "first").unwrap();
+        assert!(first.end_capture().starts_with('['));
+        // the other console never showed the time, so it is not omitted
+        second.begin_capture();
+        rich_log!(second, "second").unwrap();
+        assert!(second.end_capture().starts_with('['));
+        assert!(second.last_log_time().is_some());
+    }
+
+    #[test]
+    fn test_log_locals() {
+        let mut console = console();
+        let retries = 3;
+        let name = "db";
+        console.begin_capture();
+        rich_log!(console, "connecting"; retries, name).unwrap();
+        let output = console.end_capture();
+        let lines: Vec<&str> = without_time(&output)
+            .into_iter()
+            .map(str::trim_end)
+            .collect();
+        assert!(lines[0].starts_with("connecting "));
+        assert_eq!(
+            lines[1..],
+            [
+                "╭──── locals ────╮",
+                "│ retries = 3    │",
+                "│    name = \"db\" │",
+                "╰────────────────╯",
+            ]
+        );
+    }
+}
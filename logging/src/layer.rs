@@ -0,0 +1,330 @@
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use chrono::Local;
+use console::{Console, ConsoleBuilder};
+use text::Text;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::{LookupSpan, SpanRef};
+use tracing_subscriber::Layer;
+
+use crate::LogRender;
+
+/// Fields of a span, stored in its extensions
+struct SpanData {
+    fields: String,
+    opened: Instant,
+}
+
+/// Collects the message and the rest of fields of events and spans
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl FieldVisitor {
+    fn record(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            self.fields.push((field.name().to_string(), value));
+        }
+    }
+
+    fn fields(&self) -> String {
+        self.fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+/// A `tracing` layer that renders events with the columns of `RichLogger`, and the spans they belong to
+pub struct RichLayer {
+    console: Mutex<Console>,
+    render: Mutex<LogRender>,
+    tree: bool,
+}
+
+impl Default for RichLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RichLayer {
+    /// Layer writing to stderr
+    pub fn new() -> Self {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::stderr()))
+            .with_force_terminal(io::stderr().is_terminal())
+            .build();
+        Self {
+            console: Mutex::new(console),
+            render: Mutex::new(LogRender::new()),
+            tree: false,
+        }
+    }
+
+    pub fn with_console(mut self, console: Console) -> Self {
+        self.console = Mutex::new(console);
+        self
+    }
+
+    /// Columns, time format and other settings of the rendered events
+    pub fn with_log_render(mut self, render: LogRender) -> Self {
+        self.render = Mutex::new(render);
+        self
+    }
+
+    /// Show spans as a tree, with events indented under the spans they belong to,
+    /// instead of a breadcrumb of the active spans before each event
+    pub fn with_tree(mut self, tree: bool) -> Self {
+        self.tree = tree;
+        self
+    }
+
+    fn print(&self, message: Text, metadata: &Metadata) {
+        let mut console = self.console.lock().unwrap_or_else(|e| e.into_inner());
+        let level = LogRender::level_text(&console, metadata.level().as_str());
+        let table = self
+            .render
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .render(
+                &console,
+                Box::new(message),
+                Local::now(),
+                level,
+                metadata.module_path().or_else(|| Some(metadata.target())),
+                metadata.line(),
+            );
+        // a failure to write an event can't be reported anywhere else
+        let _ = console.print(&table);
+    }
+
+    fn append_guides(&self, console: &Console, text: &mut Text, depth: usize) {
+        text.append(&"│ ".repeat(depth), Some(console.get_style("tree.line")));
+    }
+
+    fn append_span<S>(&self, console: &Console, text: &mut Text, span: &SpanRef<S>)
+    where
+        S: for<'a> LookupSpan<'a>,
+    {
+        text.append(span.name(), Some(console.get_style("log.span")));
+        if let Some(data) = span.extensions().get::<SpanData>() {
+            if !data.fields.is_empty() {
+                text.append(
+                    &format!("{{{}}}", data.fields),
+                    Some(console.get_style("log.field")),
+                );
+            }
+        }
+    }
+
+    /// Active spans from the root, separated by colons
+    fn append_breadcrumb<'a, S>(
+        &self,
+        console: &Console,
+        text: &mut Text,
+        spans: impl Iterator<Item = SpanRef<'a, S>>,
+    ) where
+        S: for<'b> LookupSpan<'b> + 'a,
+    {
+        for (index, span) in spans.enumerate() {
+            if index > 0 {
+                text.append(":", None);
+            }
+            self.append_span(console, text, &span);
+        }
+    }
+}
+
+impl<S> Layer<S> for RichLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanData {
+            fields: visitor.fields(),
+            opened: Instant::now(),
+        });
+        if self.tree {
+            let message = {
+                let console = self.console.lock().unwrap_or_else(|e| e.into_inner());
+                let mut message = Text::new("", None);
+                let depth = span.scope().count() - 1;
+                self.append_guides(&console, &mut message, depth);
+                message.append("┌ ", Some(console.get_style("tree.line")));
+                self.append_span(&console, &mut message, &span);
+                message
+            };
+            self.print(message, span.metadata());
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = FieldVisitor::default();
+            values.record(&mut visitor);
+            if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+                if !data.fields.is_empty() {
+                    data.fields.push(' ');
+                }
+                data.fields.push_str(&visitor.fields());
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let message = {
+            let console = self.console.lock().unwrap_or_else(|e| e.into_inner());
+            let mut message = Text::new("", None);
+            if let Some(scope) = ctx.event_scope(event) {
+                if self.tree {
+                    self.append_guides(&console, &mut message, scope.count());
+                } else {
+                    let length = message.len();
+                    self.append_breadcrumb(&console, &mut message, scope.from_root());
+                    if message.len() > length {
+                        message.append(": ", None);
+                    }
+                }
+            }
+            message.append_text(&LogRender::message_text(
+                &console,
+                visitor.message.as_deref().unwrap_or(""),
+            ));
+            let fields = visitor.fields();
+            if !fields.is_empty() {
+                message.append(" ", None);
+                message.append(&fields, Some(console.get_style("log.field")));
+            }
+            message
+        };
+        self.print(message, event.metadata());
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let duration = span
+            .extensions()
+            .get::<SpanData>()
+            .map(|data| data.opened.elapsed());
+        let message = {
+            let console = self.console.lock().unwrap_or_else(|e| e.into_inner());
+            let mut message = Text::new("", None);
+            if self.tree {
+                let depth = span.scope().count() - 1;
+                self.append_guides(&console, &mut message, depth);
+                message.append("└ ", Some(console.get_style("tree.line")));
+                self.append_span(&console, &mut message, &span);
+            } else {
+                self.append_breadcrumb(&console, &mut message, span.scope().from_root());
+                message.append(" closed", None);
+            }
+            if let Some(duration) = duration {
+                message.append(" in ", None);
+                message.append(
+                    &format!("{:.2?}", duration),
+                    Some(console.get_style("log.duration")),
+                );
+            }
+            message
+        };
+        self.print(message, span.metadata());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::Registry;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(tree: bool) -> Vec<String> {
+        let buffer = Buffer::default();
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(buffer.clone()))
+            .with_width(80)
+            .build();
+        let layer = RichLayer::new()
+            .with_console(console)
+            .with_log_render(LogRender::new().with_show_time(false).with_show_path(false))
+            .with_tree(tree);
+        let subscriber = Registry::default().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("request", id = 7);
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("query");
+            inner.in_scope(|| tracing::warn!(rows = 3, "slow query"));
+        });
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_breadcrumb() {
+        let lines = capture(false);
+        assert_eq!(lines[0], "WARN     request{id=7}:query: slow query rows=3");
+        assert!(lines[1].starts_with("INFO     request{id=7}:query closed in "));
+        assert!(lines[2].starts_with("INFO     request{id=7} closed in "));
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_tree() {
+        let lines = capture(true);
+        assert_eq!(lines[0], "INFO     ┌ request{id=7}");
+        assert_eq!(lines[1], "INFO     │ ┌ query");
+        assert_eq!(lines[2], "WARN     │ │ slow query rows=3");
+        assert!(lines[3].starts_with("INFO     │ └ query in "));
+        assert!(lines[4].starts_with("INFO     └ request{id=7} in "));
+    }
+}
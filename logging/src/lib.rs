@@ -0,0 +1,224 @@
+mod console_log;
+mod layer;
+mod log_render;
+
+use std::io::{self, IsTerminal};
+use std::sync::Mutex;
+
+use chrono::Local;
+use console::{Console, ConsoleBuilder};
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+#[doc(hidden)]
+pub use measure::MeasuredRenderable;
+
+pub use self::console_log::{render_scope, ConsoleLog};
+pub use self::layer::RichLayer;
+pub use self::log_render::{LogRender, DEFAULT_TIME_FORMAT};
+
+/// A `log` backend that renders records through a console
+pub struct RichLogger {
+    console: Mutex<Console>,
+    level: LevelFilter,
+    render: Mutex<LogRender>,
+}
+
+impl Default for RichLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RichLogger {
+    /// Logger writing to stderr records of `Info` level or higher
+    pub fn new() -> Self {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(io::stderr()))
+            .with_force_terminal(io::stderr().is_terminal())
+            .build();
+        Self {
+            console: Mutex::new(console),
+            level: LevelFilter::Info,
+            render: Mutex::new(LogRender::new()),
+        }
+    }
+
+    pub fn with_console(mut self, console: Console) -> Self {
+        self.console = Mutex::new(console);
+        self
+    }
+
+    pub fn with_level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Columns, time format and other settings of the rendered records
+    pub fn with_log_render(mut self, render: LogRender) -> Self {
+        self.render = Mutex::new(render);
+        self
+    }
+
+    /// Set this logger as the `log` backend
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        log::set_max_level(self.level);
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl Log for RichLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut console = self.console.lock().unwrap_or_else(|e| e.into_inner());
+        let message = LogRender::message_text(&console, &record.args().to_string());
+        let level = LogRender::level_text(&console, record.level().as_str());
+        let table = self
+            .render
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .render(
+                &console,
+                Box::new(message),
+                Local::now(),
+                level,
+                record.module_path(),
+                record.line(),
+            );
+        // a failure to write a log record can't be reported anywhere else
+        let _ = console.print(&table);
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use log::Level;
+    use segment::Segment;
+    use style::Style;
+    use text::Text;
+
+    fn console(width: usize) -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(width)
+            .build()
+    }
+
+    #[test]
+    fn test_log_render() {
+        let console = console(41);
+        let mut render = LogRender::new();
+        let time = Local.with_ymd_and_hms(2021, 1, 1, 10, 30, 0).unwrap();
+        let mut render_record = |message: &str| {
+            let table = render.render(
+                &console,
+                Box::new(message.to_string()),
+                time,
+                LogRender::level_text(&console, "info"),
+                Some("app::config"),
+                Some(12),
+            );
+            console.render_segments(&console.render(&table, None))
+        };
+        assert_eq!(
+            render_record("loaded"),
+            "[10:30:00] INFO     loaded app::config:12\n"
+        );
+        assert_eq!(
+            render_record("a longer message that wraps"),
+            "           INFO     a      app::config:12\n                    longer               \n                    messag               \n                    e that               \n                    wraps                \n"
+        );
+    }
+
+    #[test]
+    fn test_log_render_plain_columns() {
+        let console = console(60);
+        let mut render = LogRender::new().with_show_level(false);
+        let time = Local.with_ymd_and_hms(2021, 1, 1, 10, 30, 0).unwrap();
+        let mut render_segments = |path: &str| {
+            let table = render.render(
+                &console,
+                Box::new(Text::new("ok", None)),
+                time,
+                LogRender::level_text(&console, "info"),
+                Some(path),
+                Some(42),
+            );
+            console.render(&table, None)
+        };
+        // the time and the path are neither markup nor highlighted
+        let segments = render_segments("a[b]");
+        let styles: Vec<(&str, &Option<Style>)> = segments
+            .iter()
+            .map(Segment::as_tuple)
+            .filter(|(text, style, _)| style.is_some() && !text.trim().is_empty())
+            .map(|(text, style, _)| (text, style))
+            .collect();
+        assert_eq!(
+            styles,
+            [
+                ("[10:30:00]", &Some(console.get_style("log.time"))),
+                ("a[b]:42", &Some(console.get_style("log.path"))),
+            ]
+        );
+        let segments = render_segments("my_mod");
+        assert!(segments.iter().any(|segment| segment.as_tuple()
+            == ("my_mod:42", &Some(console.get_style("log.path")), false)));
+    }
+
+    #[test]
+    fn test_logger() {
+        let logger = RichLogger::new()
+            .with_console(console(60))
+            .with_level(LevelFilter::Warn)
+            .with_log_render(LogRender::new().with_show_time(false));
+        logger.console.lock().unwrap().begin_capture();
+        for (level, message) in [(Level::Info, "hidden"), (Level::Warn, "careful")] {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", message))
+                    .level(level)
+                    .module_path(Some("app"))
+                    .line(Some(3))
+                    .build(),
+            );
+        }
+        let output = logger.console.lock().unwrap().end_capture();
+        assert_eq!(
+            output,
+            format!("WARN     careful{}app:3\n", " ".repeat(60 - 9 - 7 - 5))
+        );
+    }
+
+    #[test]
+    fn test_message_text() {
+        let console = console(60);
+        let message = LogRender::message_text(&console, "[bold]retry[/bold] 3 of \"job\"");
+        assert_eq!(message.plain(), "retry 3 of \"job\"");
+        let spans: Vec<(&str, &Style)> = message
+            .spans()
+            .iter()
+            .map(|span| {
+                let (start, end, style) = span.as_tuple();
+                (&message.plain()[start..end], style)
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("retry", &console.get_style("bold")),
+                ("3", &console.get_style("repr.number")),
+                ("\"job\"", &console.get_style("repr.str")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Local};
+use console::options::OverflowMethod;
+use console::Console;
+use measure::MeasuredRenderable;
+use padding::PaddingDimensions;
+use table::{Column, Table};
+use text::Text;
+
+pub const DEFAULT_TIME_FORMAT: &str = "[%X]";
+
+/// Lays out log records in columns for the time, level, message and path
+#[derive(Clone, Debug)]
+pub struct LogRender {
+    show_time: bool,
+    show_level: bool,
+    show_path: bool,
+    time_format: String,
+    omit_repeated_times: bool,
+    level_width: Option<usize>,
+    last_time: Option<String>,
+}
+
+impl Default for LogRender {
+    fn default() -> Self {
+        Self {
+            show_time: true,
+            show_level: true,
+            show_path: true,
+            time_format: DEFAULT_TIME_FORMAT.to_string(),
+            omit_repeated_times: true,
+            level_width: Some(8),
+            last_time: None,
+        }
+    }
+}
+
+impl LogRender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_show_time(mut self, show_time: bool) -> Self {
+        self.show_time = show_time;
+        self
+    }
+
+    pub fn with_show_level(mut self, show_level: bool) -> Self {
+        self.show_level = show_level;
+        self
+    }
+
+    pub fn with_show_path(mut self, show_path: bool) -> Self {
+        self.show_path = show_path;
+        self
+    }
+
+    /// Format of the time column, as accepted by `chrono::format::strftime`
+    pub fn with_time_format(mut self, time_format: &str) -> Self {
+        self.time_format = time_format.to_string();
+        self
+    }
+
+    /// Leave the time column blank when it is the same as the previous record
+    pub fn with_omit_repeated_times(mut self, omit_repeated_times: bool) -> Self {
+        self.omit_repeated_times = omit_repeated_times;
+        self
+    }
+
+    /// Time of the previous record, to omit it from the next one when it repeats
+    pub fn with_last_time(mut self, last_time: Option<String>) -> Self {
+        self.last_time = last_time;
+        self
+    }
+
+    /// Width of the level column, or `None` to fit the level
+    pub fn with_level_width(mut self, level_width: Option<usize>) -> Self {
+        self.level_width = level_width;
+        self
+    }
+
+    pub fn last_time(&self) -> Option<&str> {
+        self.last_time.as_deref()
+    }
+
+    /// Text of the level column, styled with the `logging.level.<level>` theme style
+    pub fn level_text(console: &Console, level: &str) -> Text {
+        let style = console.get_style(&format!("logging.level.{}", level.to_lowercase()));
+        Text::new(&level.to_uppercase(), Some(style))
+    }
+
+    /// Text of the message column, with console markup rendered and highlighted when the
+    /// console highlights strings
+    pub fn message_text(console: &Console, message: &str) -> Text {
+        let mut text = console.render_str(message, None);
+        if console.highlight() {
+            console.highlighter().highlight(&mut text, console.theme());
+        }
+        text
+    }
+
+    /// Lay out a record in a grid that expands to the console width
+    pub fn render(
+        &mut self,
+        console: &Console,
+        message: Box<dyn MeasuredRenderable>,
+        log_time: DateTime<Local>,
+        level: Text,
+        path: Option<&str>,
+        line: Option<u32>,
+    ) -> Table {
+        let mut table = Table::grid()
+            .with_padding(PaddingDimensions::new(0, 1, 0, 0))
+            .with_pad_edge(false)
+            .with_expand(true);
+        let mut row: Vec<Box<dyn MeasuredRenderable>> = Vec::new();
+        if self.show_time {
+            table.add_column(Column::default().with_style(console.get_style("log.time")));
+            let time = log_time.format(&self.time_format).to_string();
+            if self.omit_repeated_times && self.last_time.as_ref() == Some(&time) {
+                row.push(Box::new(Text::new(&" ".repeat(time.chars().count()), None)));
+            } else {
+                row.push(Box::new(Text::new(&time, None)));
+                self.last_time = Some(time);
+            }
+        }
+        if self.show_level {
+            let mut column = Column::default().with_style(console.get_style("log.level"));
+            if let Some(level_width) = self.level_width {
+                column = column.with_width(level_width);
+            }
+            table.add_column(column);
+            row.push(Box::new(level));
+        }
+        table.add_column(
+            Column::default()
+                .with_style(console.get_style("log.message"))
+                .with_overflow(OverflowMethod::Fold)
+                .with_ratio(1),
+        );
+        row.push(message);
+        if self.show_path {
+            if let Some(path) = path {
+                table.add_column(Column::default().with_style(console.get_style("log.path")));
+                let path = match line {
+                    Some(line) => format!("{}:{}", path, line),
+                    None => path.to_string(),
+                };
+                row.push(Box::new(Text::new(&path, None)));
+            }
+        }
+        table.add_row(row);
+        table
+    }
+}
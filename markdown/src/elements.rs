@@ -0,0 +1,213 @@
+use console::options::{ConsoleOptions, JustifyMethod};
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use padding::PaddingDimensions;
+use panel::Panel;
+use render_box::{HEAVY, SIMPLE_HEAVY};
+use segment::Segment;
+use syntax::Syntax;
+use table::{Column, Table};
+use text::Text;
+use utils::iter::loop_first;
+
+/// Block level elements of a markdown document
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Element {
+    Paragraph(Text),
+    Heading(usize, Text),
+    CodeBlock {
+        lexer: String,
+        code: String,
+    },
+    BlockQuote(Vec<Element>),
+    List {
+        start: Option<u64>,
+        items: Vec<Vec<Element>>,
+    },
+    Rule,
+    Table {
+        justify: Vec<JustifyMethod>,
+        header: Vec<Text>,
+        rows: Vec<Vec<Text>>,
+    },
+}
+
+/// Settings shared by every element while rendering
+pub(crate) struct RenderSettings<'a> {
+    pub code_theme: &'a str,
+    pub justify: JustifyMethod,
+}
+
+/// A group of elements rendered one after the other, optionally separated by blank lines
+pub(crate) struct Elements<'a> {
+    pub elements: &'a [Element],
+    pub settings: &'a RenderSettings<'a>,
+    pub separate: bool,
+}
+
+impl Renderable for Elements<'_> {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let mut segments = Vec::new();
+        for (first, element) in loop_first(self.elements) {
+            if self.separate && !first {
+                segments.push(Segment::line(None));
+            }
+            segments.extend(element.render(self.settings, console, options));
+        }
+        segments.into()
+    }
+}
+
+/// Render lines of nested elements, with a prefix for the first line and another one for the rest
+fn render_prefixed(
+    elements: &[Element],
+    settings: &RenderSettings,
+    console: &Console,
+    options: &ConsoleOptions,
+    first_prefix: Segment,
+    prefix: Segment,
+) -> Vec<Segment> {
+    let render_options = options.update_width(options.max_width.saturating_sub(prefix.cell_len()));
+    let elements = Elements {
+        elements,
+        settings,
+        separate: false,
+    };
+    let lines = console.render_lines(&elements, Some(&render_options), None, None, None);
+    let mut segments = Vec::new();
+    for (first, line) in loop_first(lines) {
+        segments.push(if first {
+            first_prefix.clone()
+        } else {
+            prefix.clone()
+        });
+        segments.extend(line);
+        segments.push(Segment::line(None));
+    }
+    segments
+}
+
+impl Element {
+    pub(crate) fn render(
+        &self,
+        settings: &RenderSettings,
+        console: &Console,
+        options: &ConsoleOptions,
+    ) -> Vec<Segment> {
+        match self {
+            Element::Paragraph(text) => {
+                console.render(&text.clone().with_justify(settings.justify), Some(options))
+            }
+            Element::Heading(level, text) => {
+                let text = text.clone().with_justify(JustifyMethod::Center);
+                if *level == 1 {
+                    let panel = Panel::new(Box::new(text))
+                        .with_box(HEAVY.clone())
+                        .with_style(console.get_style("markdown.h1.border"));
+                    console.render(&panel, Some(options))
+                } else {
+                    let mut segments = Vec::new();
+                    if *level == 2 {
+                        segments.push(Segment::line(None));
+                    }
+                    segments.extend(console.render(&text, Some(options)));
+                    segments
+                }
+            }
+            Element::CodeBlock { lexer, code } => {
+                let syntax = Syntax::new(code.trim_end(), lexer)
+                    .with_theme(settings.code_theme)
+                    .with_word_wrap(true)
+                    .with_padding(PaddingDimensions::all(1));
+                console.render(&syntax, Some(options))
+            }
+            Element::BlockQuote(elements) => {
+                let style = console.get_style("markdown.block_quote");
+                let prefix = Segment::new("▌ ", Some(style.clone()), false);
+                let render_options = options.update_width(options.max_width.saturating_sub(4));
+                let elements = Elements {
+                    elements,
+                    settings,
+                    separate: true,
+                };
+                let lines =
+                    console.render_lines(&elements, Some(&render_options), Some(style), None, None);
+                let mut segments = Vec::new();
+                for line in lines {
+                    segments.push(prefix.clone());
+                    segments.extend(line);
+                    segments.push(Segment::line(None));
+                }
+                segments
+            }
+            Element::List { start, items } => {
+                let mut segments = Vec::new();
+                match start {
+                    Some(start) => {
+                        let style = Some(console.get_style("markdown.item.number"));
+                        let last_number = *start as usize + items.len().saturating_sub(1);
+                        let number_width = last_number.to_string().len() + 2;
+                        let padding = Segment::new(&" ".repeat(number_width), style.clone(), false);
+                        for (index, item) in items.iter().enumerate() {
+                            let numeral = format!(
+                                "{:>width$} ",
+                                *start as usize + index,
+                                width = number_width - 1
+                            );
+                            segments.extend(render_prefixed(
+                                item,
+                                settings,
+                                console,
+                                options,
+                                Segment::new(&numeral, style.clone(), false),
+                                padding.clone(),
+                            ));
+                        }
+                    }
+                    None => {
+                        let style = Some(console.get_style("markdown.item.bullet"));
+                        for item in items {
+                            segments.extend(render_prefixed(
+                                item,
+                                settings,
+                                console,
+                                options,
+                                Segment::new(" • ", style.clone(), false),
+                                Segment::new("   ", style.clone(), false),
+                            ));
+                        }
+                    }
+                }
+                segments
+            }
+            Element::Rule => {
+                let rule = Text::new(
+                    &"─".repeat(options.max_width),
+                    Some(console.get_style("markdown.hr")),
+                );
+                console.render(&rule, Some(options))
+            }
+            Element::Table {
+                justify,
+                header,
+                rows,
+            } => {
+                let mut table = Table::new().with_box(Some(SIMPLE_HEAVY.clone()));
+                for (index, header) in header.iter().enumerate() {
+                    let justify = justify.get(index).cloned().unwrap_or(JustifyMethod::Left);
+                    table.add_column(Column::from_text(header.clone()).with_justify(justify));
+                }
+                for row in rows {
+                    table.add_row(
+                        row.iter()
+                            .map(|cell| {
+                                Box::new(cell.clone()) as Box<dyn measure::MeasuredRenderable>
+                            })
+                            .collect(),
+                    );
+                }
+                console.render(&table, Some(options))
+            }
+        }
+    }
+}
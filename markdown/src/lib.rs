@@ -0,0 +1,169 @@
+mod elements;
+mod parser;
+
+use console::options::{ConsoleOptions, JustifyMethod};
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, Measurement};
+
+use crate::elements::{Elements, RenderSettings};
+
+/// A console renderable for a markdown document (CommonMark with tables and strikethrough)
+#[derive(Clone, Debug)]
+pub struct Markdown {
+    markup: String,
+    code_theme: String,
+    justify: Option<JustifyMethod>,
+    hyperlinks: bool,
+}
+
+impl Markdown {
+    pub fn new(markup: &str) -> Self {
+        Self {
+            markup: markup.to_string(),
+            code_theme: syntax::DEFAULT_THEME.to_string(),
+            justify: None,
+            hyperlinks: true,
+        }
+    }
+
+    /// Theme used to highlight fenced code blocks
+    pub fn with_code_theme(mut self, code_theme: &str) -> Self {
+        self.code_theme = code_theme.to_string();
+        self
+    }
+
+    /// Justify method for paragraphs
+    pub fn with_justify(mut self, justify: JustifyMethod) -> Self {
+        self.justify = Some(justify);
+        self
+    }
+
+    /// Render links as terminal hyperlinks, otherwise the url is printed after the link text
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    pub fn markup(&self) -> &str {
+        &self.markup
+    }
+}
+
+impl Renderable for Markdown {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let elements = parser::parse(&self.markup, console, self.hyperlinks);
+        let settings = RenderSettings {
+            code_theme: &self.code_theme,
+            justify: self.justify.unwrap_or(JustifyMethod::Left),
+        };
+        let elements = Elements {
+            elements: &elements,
+            settings: &settings,
+            separate: true,
+        };
+        console.render(&elements, Some(options)).into()
+    }
+}
+
+impl Measure for Markdown {
+    fn measure(&self, _console: &Console, max_width: usize) -> Measurement {
+        Measurement::new(1, max_width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+
+    fn render(markup: &str, width: usize) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        console.begin_capture();
+        console
+            .print(&Markdown::new(markup).with_hyperlinks(false))
+            .unwrap();
+        console.end_capture()
+    }
+
+    fn render_elements(markup: &str) -> Vec<elements::Element> {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .build();
+        parser::parse(markup, &console, false)
+    }
+
+    #[test]
+    fn test_heading() {
+        assert_eq!(
+            render("# Title", 11),
+            "┏━━━━━━━━━┓\n┃  Title  ┃\n┗━━━━━━━━━┛\n"
+        );
+        assert_eq!(render("## Title", 11), "\n   Title   \n");
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert_eq!(
+            render("Hello *World*\nand `code`\n\nSecond", 30),
+            "Hello World and code          \n\nSecond                        \n"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(render("- foo\n- bar", 10), " • foo    \n • bar    \n");
+        assert_eq!(render("1. foo\n2. bar", 10), " 1 foo    \n 2 bar    \n");
+    }
+
+    #[test]
+    fn test_block_quote() {
+        assert_eq!(render("> quote", 10), "▌ quote \n");
+    }
+
+    #[test]
+    fn test_rule() {
+        assert_eq!(render("---", 5), "─────\n");
+    }
+
+    #[test]
+    fn test_link_url() {
+        assert_eq!(
+            render("[rich](https://example.com)", 30),
+            "rich (https://example.com)    \n"
+        );
+    }
+
+    #[test]
+    fn test_parse_table() {
+        match &render_elements("| a | b |\n|---|--:|\n| 1 | 2 |")[..] {
+            [elements::Element::Table {
+                justify,
+                header,
+                rows,
+            }] => {
+                assert_eq!(justify, &[JustifyMethod::Left, JustifyMethod::Right]);
+                assert_eq!(
+                    header.iter().map(|h| h.plain()).collect::<Vec<_>>(),
+                    ["a", "b"]
+                );
+                assert_eq!(rows.len(), 1);
+            }
+            elements => panic!("unexpected elements {:?}", elements),
+        }
+    }
+
+    #[test]
+    fn test_parse_code_block() {
+        assert_eq!(
+            render_elements("```rust\nfn main() {}\n```"),
+            vec![elements::Element::CodeBlock {
+                lexer: "rust".to_string(),
+                code: "fn main() {}\n".to_string()
+            }]
+        );
+    }
+}
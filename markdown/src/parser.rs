@@ -0,0 +1,274 @@
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag};
+
+use console::options::JustifyMethod;
+use console::Console;
+use style::{Style, StyleBuilder, StyleStack};
+use text::Text;
+
+use crate::elements::Element;
+
+/// Table being parsed
+struct TableState {
+    justify: Vec<JustifyMethod>,
+    header: Vec<Text>,
+    rows: Vec<Vec<Text>>,
+    row: Vec<Text>,
+}
+
+/// Builds the elements of a markdown document out of the parser events
+struct ElementsBuilder<'a> {
+    console: &'a Console,
+    hyperlinks: bool,
+    /// Elements of the block containers being parsed, the document being the first one
+    containers: Vec<Vec<Element>>,
+    /// Items of the lists being parsed
+    list_items: Vec<Vec<Vec<Element>>>,
+    /// Inline text being parsed, with its base style
+    inline: Option<Text>,
+    styles: StyleStack,
+    links: Vec<String>,
+    code_block: Option<(String, String)>,
+    table: Option<TableState>,
+}
+
+fn justify_from_alignment(alignment: &Alignment) -> JustifyMethod {
+    match alignment {
+        Alignment::None | Alignment::Left => JustifyMethod::Left,
+        Alignment::Center => JustifyMethod::Center,
+        Alignment::Right => JustifyMethod::Right,
+    }
+}
+
+impl<'a> ElementsBuilder<'a> {
+    fn new(console: &'a Console, hyperlinks: bool) -> Self {
+        Self {
+            console,
+            hyperlinks,
+            containers: vec![Vec::new()],
+            list_items: Vec::new(),
+            inline: None,
+            styles: StyleStack::new(Style::null()),
+            links: Vec::new(),
+            code_block: None,
+            table: None,
+        }
+    }
+
+    fn push_element(&mut self, element: Element) {
+        if let Some(elements) = self.containers.last_mut() {
+            elements.push(element);
+        }
+    }
+
+    fn push_style(&mut self, name: &str) {
+        let style = self.console.get_style(name);
+        self.styles.push(style);
+    }
+
+    fn start_inline(&mut self, style: &str) {
+        self.inline = Some(Text::new("", Some(self.console.get_style(style))));
+    }
+
+    fn append_text(&mut self, text: &str, style: Option<Style>) {
+        if let Some((_, code)) = self.code_block.as_mut() {
+            code.push_str(text);
+            return;
+        }
+        if self.inline.is_none() {
+            // tight list items have no paragraph events
+            self.start_inline("markdown.paragraph");
+        }
+        let style = match style {
+            Some(style) => self.styles.current().combine(Some(&style)),
+            None => self.styles.current().clone(),
+        };
+        if let Some(inline) = self.inline.as_mut() {
+            inline.append(text, Some(style));
+        }
+    }
+
+    /// Close the implicit paragraph of tight list items
+    fn flush_inline(&mut self) {
+        if let Some(text) = self.inline.take() {
+            self.push_element(Element::Paragraph(text));
+        }
+    }
+
+    fn close_container(&mut self) -> Vec<Element> {
+        self.flush_inline();
+        self.containers.pop().unwrap_or_default()
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.start_inline("markdown.paragraph"),
+            Tag::Heading(level, _, _) => {
+                self.start_inline(&format!("markdown.h{}", level as usize))
+            }
+            Tag::BlockQuote => {
+                self.flush_inline();
+                self.containers.push(Vec::new());
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_inline();
+                let lexer = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code_block = Some((lexer, String::new()));
+            }
+            Tag::List(_) => {
+                self.flush_inline();
+                self.containers.push(Vec::new());
+                self.list_items.push(Vec::new());
+            }
+            Tag::Item => self.containers.push(Vec::new()),
+            Tag::Table(alignments) => {
+                self.table = Some(TableState {
+                    justify: alignments.iter().map(justify_from_alignment).collect(),
+                    header: Vec::new(),
+                    rows: Vec::new(),
+                    row: Vec::new(),
+                })
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    table.row.clear();
+                }
+            }
+            Tag::TableCell => self.inline = Some(Text::default()),
+            Tag::Emphasis => self.push_style("markdown.emph"),
+            Tag::Strong => self.push_style("markdown.strong"),
+            Tag::Strikethrough => self.push_style("markdown.s"),
+            Tag::Link(_, ref destination, _) | Tag::Image(_, ref destination, _) => {
+                let mut style = self.console.get_style("markdown.link");
+                if self.hyperlinks {
+                    style =
+                        style.combine(Some(&StyleBuilder::new().with_link(destination).build()));
+                }
+                self.styles.push(style);
+                self.links.push(destination.to_string());
+                if matches!(tag, Tag::Image(..)) {
+                    self.append_text("🌆 ", None);
+                }
+            }
+            Tag::FootnoteDefinition(_) => {}
+        }
+    }
+
+    fn end(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.flush_inline(),
+            Tag::Heading(level, _, _) => {
+                if let Some(text) = self.inline.take() {
+                    self.push_element(Element::Heading(level as usize, text));
+                }
+            }
+            Tag::BlockQuote => {
+                let elements = self.close_container();
+                self.push_element(Element::BlockQuote(elements));
+            }
+            Tag::CodeBlock(_) => {
+                if let Some((lexer, code)) = self.code_block.take() {
+                    self.push_element(Element::CodeBlock { lexer, code });
+                }
+            }
+            Tag::List(start) => {
+                self.close_container();
+                let items = self.list_items.pop().unwrap_or_default();
+                self.push_element(Element::List { start, items });
+            }
+            Tag::Item => {
+                let elements = self.close_container();
+                if let Some(items) = self.list_items.last_mut() {
+                    items.push(elements);
+                }
+            }
+            Tag::Table(_) => {
+                if let Some(table) = self.table.take() {
+                    self.push_element(Element::Table {
+                        justify: table.justify,
+                        header: table.header,
+                        rows: table.rows,
+                    });
+                }
+            }
+            Tag::TableHead => {
+                if let Some(table) = self.table.as_mut() {
+                    table.header = std::mem::take(&mut table.row);
+                }
+            }
+            Tag::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    let row = std::mem::take(&mut table.row);
+                    table.rows.push(row);
+                }
+            }
+            Tag::TableCell => {
+                let cell = self.inline.take().unwrap_or_default();
+                if let Some(table) = self.table.as_mut() {
+                    table.row.push(cell);
+                }
+            }
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => {
+                self.styles.pop();
+            }
+            Tag::Link(..) | Tag::Image(..) => {
+                self.styles.pop();
+                let destination = self.links.pop().unwrap_or_default();
+                if !self.hyperlinks && !destination.is_empty() {
+                    self.append_text(" (", None);
+                    let url_style = self.console.get_style("markdown.link_url");
+                    self.append_text(&destination, Some(url_style));
+                    self.append_text(")", None);
+                }
+            }
+            Tag::FootnoteDefinition(_) => {}
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.append_text(&text, None),
+            Event::Code(code) => {
+                let style = self.console.get_style("markdown.code");
+                self.append_text(&code, Some(style));
+            }
+            Event::SoftBreak => self.append_text(" ", None),
+            Event::HardBreak => self.append_text("\n", None),
+            Event::Rule => {
+                self.flush_inline();
+                self.push_element(Element::Rule);
+            }
+            Event::TaskListMarker(checked) => {
+                self.append_text(if checked { "☑ " } else { "☐ " }, None)
+            }
+            Event::Html(_) | Event::FootnoteReference(_) => {}
+        }
+    }
+
+    fn build(mut self) -> Vec<Element> {
+        while self.containers.len() > 1 {
+            let elements = self.close_container();
+            for element in elements {
+                self.push_element(element);
+            }
+        }
+        self.close_container()
+    }
+}
+
+/// Parse a markdown document in to its block elements, styled with the console theme
+pub(crate) fn parse(markup: &str, console: &Console, hyperlinks: bool) -> Vec<Element> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut builder = ElementsBuilder::new(console, hyperlinks);
+    for event in Parser::new_ext(markup, options) {
+        builder.event(event);
+    }
+    builder.build()
+}
@@ -0,0 +1,214 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use style::Style;
+use text::Text;
+
+lazy_static! {
+    static ref RE_TAGS: Regex = Regex::new(r"(\\*)\[([a-z#/][^\[]*?)]").unwrap();
+}
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum Error {
+    #[error("closing tag '{tag}' at position {position} doesn't match any open tag")]
+    UnmatchedClosingTag { tag: String, position: usize },
+    #[error("closing tag '[/]' at position {position} has nothing to close")]
+    NothingToClose { position: usize },
+}
+
+/// A tag in console markup, like `[bold red]` or `[link=https://example.org]`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub parameters: Option<String>,
+}
+
+impl Tag {
+    /// Parse the contents of a tag, without the square brackets
+    pub fn parse(tag: &str) -> Self {
+        match tag.split_once('=') {
+            Some((name, parameters)) => Self {
+                name: name.trim().to_string(),
+                parameters: Some(parameters.trim().to_string()),
+            },
+            None => Self {
+                name: tag.trim().to_string(),
+                parameters: None,
+            },
+        }
+    }
+
+    /// The style definition of the tag, `[link=url]` is the `link url` style
+    pub fn style_definition(&self) -> String {
+        match &self.parameters {
+            Some(parameters) => format!("{} {}", self.name, parameters),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The tag as it is written in markup
+    pub fn markup(&self) -> String {
+        match &self.parameters {
+            Some(parameters) => format!("[{}={}]", self.name, parameters),
+            None => format!("[{}]", self.name),
+        }
+    }
+}
+
+/// Escape text so that it is not interpreted as markup, a backslash is added before what
+/// looks like a tag
+pub fn escape(text: &str) -> String {
+    let mut escaped = RE_TAGS
+        .replace_all(text, |captures: &Captures| {
+            format!("{0}{0}\\[{1}]", &captures[1], &captures[2])
+        })
+        .into_owned();
+    if escaped.ends_with('\\') && !escaped.ends_with("\\\\") {
+        escaped.push('\\');
+    }
+    escaped
+}
+
+/// Render console markup in to a `Text`, tags are styles resolved by `get_style`.
+/// Tags are closed by `[/name]` or `[/]` for the last open tag, and open tags at the end of
+/// the markup are closed there. A tag preceded by a backslash is not interpreted.
+pub fn render<GetStyle>(
+    markup: &str,
+    style: Option<Style>,
+    get_style: GetStyle,
+) -> Result<Text, Error>
+where
+    GetStyle: Fn(&str) -> Style,
+{
+    if !markup.contains('[') {
+        return Ok(Text::new(markup, style));
+    }
+    let mut text = Text::new("", style);
+    // open tags with the offset where they start
+    let mut open_tags: Vec<(usize, Tag)> = Vec::new();
+    // (start, end, style definition) of the closed tags
+    let mut spans: Vec<(usize, usize, String)> = Vec::new();
+    let mut position = 0;
+    for captures in RE_TAGS.captures_iter(markup) {
+        let tag_match = captures.get(0).unwrap();
+        text.append(&markup[position..tag_match.start()], None);
+        position = tag_match.end();
+        let backslashes = captures[1].len();
+        text.append(&"\\".repeat(backslashes / 2), None);
+        if backslashes % 2 == 1 {
+            // escaped tag
+            text.append(&format!("[{}]", &captures[2]), None);
+            continue;
+        }
+        let tag = Tag::parse(&captures[2]);
+        match tag.name.strip_prefix('/') {
+            Some(closing) => {
+                let closing = closing.trim();
+                let open_tag = if closing.is_empty() {
+                    open_tags.pop().ok_or(Error::NothingToClose {
+                        position: tag_match.start(),
+                    })?
+                } else {
+                    let index = open_tags
+                        .iter()
+                        .rposition(|(_, open_tag)| open_tag.name == closing)
+                        .ok_or_else(|| Error::UnmatchedClosingTag {
+                            tag: tag.markup(),
+                            position: tag_match.start(),
+                        })?;
+                    open_tags.remove(index)
+                };
+                let (start, open_tag) = open_tag;
+                spans.push((start, text.len(), open_tag.style_definition()));
+            }
+            None => open_tags.push((text.len(), tag)),
+        }
+    }
+    text.append(&markup[position..], None);
+    while let Some((start, open_tag)) = open_tags.pop() {
+        spans.push((start, text.len(), open_tag.style_definition()));
+    }
+    // outer tags first, so that the styles of inner tags take precedence
+    spans.reverse();
+    spans.sort_by_key(|(start, _, _)| *start);
+    for (start, end, definition) in spans {
+        text.stylize(get_style(&definition), start, Some(end));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::Span;
+
+    fn get_style(definition: &str) -> Style {
+        Style::parse(definition).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let text = render("foo [bold]bar[/bold] baz", None, get_style).unwrap();
+        assert_eq!(text.plain(), "foo bar baz");
+        assert_eq!(text.spans(), [Span::new(4, 7, get_style("bold"))]);
+    }
+
+    #[test]
+    fn test_render_nested() {
+        let text = render("[bold]foo [red]bar[/] baz", None, get_style).unwrap();
+        assert_eq!(text.plain(), "foo bar baz");
+        assert_eq!(
+            text.spans(),
+            [
+                Span::new(0, 11, get_style("bold")),
+                Span::new(4, 7, get_style("red")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_link() {
+        let text = render("[link=https://example.org]docs[/link]", None, get_style).unwrap();
+        assert_eq!(text.plain(), "docs");
+        assert_eq!(
+            text.spans(),
+            [Span::new(0, 4, get_style("link https://example.org"))]
+        );
+    }
+
+    #[test]
+    fn test_render_not_tags() {
+        let text = render("[1, 2] [] [Foo]", None, get_style).unwrap();
+        assert_eq!(text.plain(), "[1, 2] [] [Foo]");
+        assert!(text.spans().is_empty());
+    }
+
+    #[test]
+    fn test_render_escaped() {
+        let text = render(r"\[bold]foo \\[bold]bar", None, get_style).unwrap();
+        assert_eq!(text.plain(), r"[bold]foo \bar");
+        assert_eq!(text.spans(), [Span::new(11, 14, get_style("bold"))]);
+    }
+
+    #[test]
+    fn test_render_errors() {
+        assert_eq!(
+            render("foo[/bold]", None, get_style).unwrap_err(),
+            Error::UnmatchedClosingTag {
+                tag: "[/bold]".to_string(),
+                position: 3
+            }
+        );
+        assert_eq!(
+            render("[/]", None, get_style).unwrap_err(),
+            Error::NothingToClose { position: 0 }
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("[bold]foo[/]"), r"\[bold]foo\[/]");
+        assert_eq!(escape(r"foo\"), r"foo\\");
+        let text = render(&escape(r"[bold]\[red]"), None, get_style).unwrap();
+        assert_eq!(text.plain(), r"[bold]\[red]");
+    }
+}
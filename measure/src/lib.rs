@@ -1,10 +1,18 @@
+use cells::cell_len;
 use console::traits::Renderable;
 use console::Console;
+use emoji::Emoji;
+use text::Text;
 
 pub trait Measure {
     fn measure(&self, console: &Console, max_width: usize) -> Measurement;
 }
 
+/// A renderable that can also be measured
+pub trait MeasuredRenderable: Renderable + Measure {}
+
+impl<T: Renderable + Measure + ?Sized> MeasuredRenderable for T {}
+
 /// Stores the minimum and maximum widths (in characters) required to render an object
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Measurement {
@@ -31,10 +39,10 @@ impl Measurement {
     /// Get measurement that ensures that minimum <= maximum and minimum >= 0
     pub fn normalized(&self) -> Self {
         let (mut min, max) = self.as_tuple();
-        min = 0.max(min).min(max);
+        min = min.min(max);
         Self {
-            minimum: 0.max(min),
-            maximum: 0.max(min.max(max)),
+            minimum: min,
+            maximum: min.max(max),
         }
     }
 
@@ -50,7 +58,6 @@ impl Measurement {
     /// Get a Measurement where the widths are >= width
     pub fn with_minimum(&self, width: usize) -> Self {
         let (min, max) = self.as_tuple();
-        let width = 0.max(width);
         Self {
             minimum: min.max(width),
             maximum: max.max(width),
@@ -69,30 +76,95 @@ impl Measurement {
         measurement
     }
 
-    pub fn get<R>(console: &Console, rendereable: R, max_width: Option<usize>) -> Self
+    /// Get a measurement for a renderable, bounded by `max_width` (console width by default)
+    pub fn get<R>(console: &Console, renderable: &R, max_width: Option<usize>) -> Self
     where
-        R: Renderable,
+        R: Measure + ?Sized,
     {
-        // TODO: implement this when console is ready
-        unimplemented!()
+        let max_width = max_width.unwrap_or_else(|| console.width());
+        if max_width < 1 {
+            return Self::new(0, 0);
+        }
+        renderable
+            .measure(console, max_width)
+            .normalized()
+            .with_maximum(max_width)
     }
 }
 
 /// Get a measurement that would fit a number of renderables
-pub fn measure_renderables<Renderables>(
+pub fn measure_renderables<'a, Renderables, R>(
     console: &Console,
     renderables: Renderables,
     max_width: usize,
 ) -> Measurement
 where
-    Renderables: IntoIterator<Item = dyn Renderable>,
+    Renderables: IntoIterator<Item = &'a R>,
+    R: Measure + ?Sized + 'a,
 {
-    unimplemented!()
+    let measurements: Vec<Measurement> = renderables
+        .into_iter()
+        .map(|renderable| Measurement::get(console, renderable, Some(max_width)))
+        .collect();
+    Measurement::new(
+        measurements.iter().map(|m| m.minimum).max().unwrap_or(0),
+        measurements.iter().map(|m| m.maximum).max().unwrap_or(0),
+    )
+}
+
+impl<T: Measure + ?Sized> Measure for &T {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        (**self).measure(console, max_width)
+    }
+}
+
+impl<T: Measure + ?Sized> Measure for Box<T> {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        (**self).measure(console, max_width)
+    }
+}
+
+impl Measure for Text {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        if self.plain().contains('\t') {
+            let mut text = self.clone();
+            text.expand_tabs(Some(self.tab_size().unwrap_or_else(|| console.tab_size())));
+            text.plain().measure(console, max_width)
+        } else {
+            self.plain().measure(console, max_width)
+        }
+    }
+}
+
+impl Measure for Emoji {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        self.to_string().measure(console, max_width)
+    }
+}
+
+impl Measure for str {
+    fn measure(&self, _console: &Console, _max_width: usize) -> Measurement {
+        let max_text_width = self.lines().map(cell_len).max().unwrap_or(0);
+        let min_text_width = self
+            .split_whitespace()
+            .map(cell_len)
+            .max()
+            .unwrap_or(max_text_width);
+        Measurement::new(min_text_width, max_text_width)
+    }
+}
+
+impl Measure for String {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        self.as_str().measure(console, max_width)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Measurement;
+    use crate::{measure_renderables, Measurement};
+    use console::ConsoleBuilder;
+    use text::Text;
 
     #[test]
     fn test_span() {
@@ -115,4 +187,34 @@ mod tests {
         assert_eq!(measurement.clamp(Some(30), None), Measurement::new(30, 100));
         assert_eq!(measurement.clamp(None, None), Measurement::new(20, 100));
     }
+
+    #[test]
+    fn test_measure_text() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build();
+        let text = Text::new("foo bar\nbazinga", None);
+        assert_eq!(
+            Measurement::get(&console, &text, None),
+            Measurement::new(7, 7)
+        );
+        let tsv = Text::new("a\tbc\td", None);
+        assert_eq!(
+            Measurement::get(&console, &tsv, None),
+            Measurement::new(2, 17)
+        );
+        assert_eq!(
+            Measurement::get(&console, &tsv.with_tab_size(4), None),
+            Measurement::new(2, 9)
+        );
+        assert_eq!(
+            Measurement::get(&console, "Hello, World!", Some(5)),
+            Measurement::new(5, 5)
+        );
+        assert_eq!(
+            measure_renderables(&console, vec!["foo", "a b c d e f"], 80),
+            Measurement::new(3, 11)
+        );
+    }
 }
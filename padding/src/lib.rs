@@ -0,0 +1,176 @@
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, MeasuredRenderable, Measurement};
+use segment::Segment;
+use style::Style;
+
+/// Padding sizes in the css order: top, right, bottom, left
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PaddingDimensions {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl PaddingDimensions {
+    pub fn new(top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Same padding on every side
+    pub fn all(pad: usize) -> Self {
+        Self::new(pad, pad, pad, pad)
+    }
+
+    /// Vertical padding for top and bottom, horizontal padding for left and right
+    pub fn symmetric(vertical: usize, horizontal: usize) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    pub fn as_tuple(&self) -> (usize, usize, usize, usize) {
+        (self.top, self.right, self.bottom, self.left)
+    }
+
+    pub fn as_bool(&self) -> bool {
+        self.top > 0 || self.right > 0 || self.bottom > 0 || self.left > 0
+    }
+}
+
+/// Draw space around a renderable
+pub struct Padding<R: MeasuredRenderable> {
+    renderable: R,
+    padding: PaddingDimensions,
+    style: Option<Style>,
+    expand: bool,
+}
+
+impl<R: MeasuredRenderable> Padding<R> {
+    pub fn new(
+        renderable: R,
+        padding: PaddingDimensions,
+        style: Option<Style>,
+        expand: Option<bool>,
+    ) -> Self {
+        Self {
+            renderable,
+            padding,
+            style,
+            expand: expand.unwrap_or(true),
+        }
+    }
+
+    /// Make padding instance to render an indent
+    pub fn indent(renderable: R, level: usize) -> Self {
+        Self::new(
+            renderable,
+            PaddingDimensions::new(0, 0, 0, level),
+            None,
+            Some(false),
+        )
+    }
+}
+
+impl<R: MeasuredRenderable> Renderable for Padding<R> {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let (top, right, bottom, left) = self.padding.as_tuple();
+        let width = if self.expand {
+            options.max_width
+        } else {
+            (Measurement::get(console, &self.renderable, Some(options.max_width)).maximum
+                + left
+                + right)
+                .min(options.max_width)
+        };
+        let render_options = options.update_width(width.saturating_sub(left + right));
+        let lines = console.render_lines(
+            &self.renderable,
+            Some(&render_options),
+            self.style.clone(),
+            Some(true),
+            None,
+        );
+        let left_segment = Segment::new(&" ".repeat(left), self.style.clone(), false);
+        let right_segment = Segment::new(&" ".repeat(right), self.style.clone(), false);
+        let blank_line = Segment::new(
+            &format!("{}\n", " ".repeat(width)),
+            self.style.clone(),
+            false,
+        );
+
+        let mut segments = vec![blank_line.clone(); top];
+        for line in lines {
+            if left > 0 {
+                segments.push(left_segment.clone());
+            }
+            segments.extend(line);
+            if right > 0 {
+                segments.push(right_segment.clone());
+            }
+            segments.push(Segment::line(None));
+        }
+        segments.extend(vec![blank_line; bottom]);
+        segments.into()
+    }
+}
+
+impl<R: MeasuredRenderable> Measure for Padding<R> {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        let extra_width = self.padding.left + self.padding.right;
+        if max_width <= extra_width {
+            return Measurement::new(max_width, max_width);
+        }
+        let measurement =
+            Measurement::get(console, &self.renderable, Some(max_width - extra_width));
+        Measurement::new(
+            measurement.minimum + extra_width,
+            measurement.maximum + extra_width,
+        )
+        .with_maximum(max_width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+
+    fn console(width: usize) -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build()
+    }
+
+    #[test]
+    fn test_render() {
+        let mut console = console(8);
+        console.begin_capture();
+        console
+            .print(&Padding::new(
+                "foo",
+                PaddingDimensions::new(1, 2, 0, 1),
+                None,
+                None,
+            ))
+            .unwrap();
+        console.print(&Padding::indent("bar", 2)).unwrap();
+        assert_eq!(console.end_capture(), "        \n foo    \n  bar\n");
+    }
+
+    #[test]
+    fn test_measure() {
+        let console = console(8);
+        let padding = Padding::new("foo bar", PaddingDimensions::symmetric(0, 1), None, None);
+        assert_eq!(
+            Measurement::get(&console, &padding, None),
+            Measurement::new(5, 8)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use align::AlignMethod;
+use console::options::{ConsoleOptions, JustifyMethod, UpdateConsoleOptions};
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{measure_renderables, Measure, MeasuredRenderable, Measurement};
+use padding::{Padding, PaddingDimensions};
+use render_box::{RenderBox, ROUNDED};
+use segment::Segment;
+use style::Style;
+use text::Text;
+
+/// A console renderable that draws a border around its contents
+pub struct Panel {
+    renderable: Box<dyn MeasuredRenderable>,
+    render_box: RenderBox,
+    title: Option<Text>,
+    title_align: AlignMethod,
+    safe_box: Option<bool>,
+    expand: bool,
+    style: Option<Style>,
+    border_style: Option<Style>,
+    width: Option<usize>,
+    padding: PaddingDimensions,
+}
+
+impl Panel {
+    pub fn new(renderable: Box<impl MeasuredRenderable + 'static>) -> Self {
+        Self {
+            renderable,
+            render_box: ROUNDED.clone(),
+            title: None,
+            title_align: AlignMethod::Center,
+            safe_box: None,
+            expand: true,
+            style: None,
+            border_style: None,
+            width: None,
+            padding: PaddingDimensions::symmetric(0, 1),
+        }
+    }
+
+    /// A panel that fits its contents instead of expanding to the available width
+    pub fn fit(renderable: Box<impl MeasuredRenderable + 'static>) -> Self {
+        Self::new(renderable).with_expand(false)
+    }
+
+    pub fn with_box(mut self, render_box: RenderBox) -> Self {
+        self.render_box = render_box;
+        self
+    }
+
+    pub fn with_title(mut self, title: Text) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_title_align(mut self, title_align: AlignMethod) -> Self {
+        self.title_align = title_align;
+        self
+    }
+
+    pub fn with_safe_box(mut self, safe_box: bool) -> Self {
+        self.safe_box = Some(safe_box);
+        self
+    }
+
+    pub fn with_expand(mut self, expand: bool) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_border_style(mut self, border_style: Style) -> Self {
+        self.border_style = Some(border_style);
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_padding(mut self, padding: PaddingDimensions) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Title in a single line, with a space at each side
+    fn title_text(&self) -> Option<Text> {
+        self.title.as_ref().map(|title| {
+            let mut title_text = Text::new(" ", None)
+                .join(title.split("\n", false, true))
+                .with_end("")
+                .with_no_wrap(true);
+            title_text.pad_left(1, ' ');
+            title_text.pad_right(1, ' ');
+            title_text
+        })
+    }
+}
+
+impl Renderable for Panel {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let renderable = Padding::new(&self.renderable, self.padding, None, None);
+        let style = self.style.clone().unwrap_or_default();
+        let border_style = style.combine(self.border_style.as_ref());
+        let width = self
+            .width
+            .unwrap_or(options.max_width)
+            .min(options.max_width);
+        let render_box = self.render_box.substitute(options, self.safe_box);
+        let title_text = self
+            .title_text()
+            .map(|title| title.with_style(border_style.clone()));
+
+        let mut child_width = if self.expand {
+            width.saturating_sub(2)
+        } else {
+            Measurement::get(console, &renderable, Some(width.saturating_sub(2))).maximum
+        };
+        if let Some(title_text) = &title_text {
+            child_width = options
+                .max_width
+                .saturating_sub(2)
+                .min(child_width.max(title_text.cell_len() + 2));
+        }
+        let width = child_width + 2;
+        let mut child_options = options.clone();
+        child_options.update(UpdateConsoleOptions {
+            width: Some(child_width),
+            ..Default::default()
+        });
+        let lines = console.render_lines(
+            &renderable,
+            Some(&child_options),
+            Some(style).filter(Style::as_bool),
+            None,
+            None,
+        );
+
+        let border = |text: &str| Segment::new(text, Some(border_style.clone()), false);
+        let new_line = Segment::line(None);
+        let mut segments = Vec::new();
+        match title_text {
+            Some(mut title_text) if width > 4 => {
+                let justify = match self.title_align {
+                    AlignMethod::Left => JustifyMethod::Left,
+                    AlignMethod::Center => JustifyMethod::Center,
+                    AlignMethod::Right => JustifyMethod::Right,
+                };
+                let top_character = render_box.top.chars().next().unwrap_or(' ');
+                title_text.align(justify, width - 4, top_character);
+                segments.push(border(&format!(
+                    "{}{}",
+                    render_box.top_left, render_box.top
+                )));
+                segments.extend(
+                    console.render(&title_text, Some(&child_options.update_width(width - 4))),
+                );
+                segments.push(border(&format!(
+                    "{}{}",
+                    render_box.top, render_box.top_right
+                )));
+            }
+            _ => segments.push(border(&render_box.get_top(&[width - 2]))),
+        }
+        segments.push(new_line.clone());
+        for line in lines {
+            segments.push(border(&render_box.mid_left));
+            segments.extend(line);
+            segments.push(border(&render_box.mid_right));
+            segments.push(new_line.clone());
+        }
+        segments.push(border(&render_box.get_bottom(&[width - 2])));
+        segments.push(new_line);
+        segments.into()
+    }
+}
+
+impl Measure for Panel {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        let padding = self.padding.left + self.padding.right;
+        let width = match self.width {
+            Some(width) => width,
+            None => {
+                let max_width = max_width.saturating_sub(padding + 2);
+                let mut measurement =
+                    measure_renderables(console, vec![&self.renderable], max_width);
+                if let Some(title) = self.title_text() {
+                    measurement.maximum = measurement
+                        .maximum
+                        .max(Measurement::get(console, &title, Some(max_width)).maximum);
+                }
+                measurement.maximum + padding + 2
+            }
+        };
+        Measurement::new(width, width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+    use render_box::HEAVY;
+
+    fn render(panel: &Panel, width: usize) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        console.begin_capture();
+        console.print(panel).unwrap();
+        console.end_capture()
+    }
+
+    #[test]
+    fn test_render_panel() {
+        assert_eq!(
+            render(&Panel::new(Box::new("Hello, World!")), 20),
+            "╭──────────────────╮\n│ Hello, World!    │\n╰──────────────────╯\n"
+        );
+        assert_eq!(
+            render(
+                &Panel::fit(Box::new("Hello, World!")).with_box(HEAVY.clone()),
+                50
+            ),
+            "┏━━━━━━━━━━━━━━━┓\n┃ Hello, World! ┃\n┗━━━━━━━━━━━━━━━┛\n"
+        );
+    }
+
+    #[test]
+    fn test_render_title() {
+        assert_eq!(
+            render(
+                &Panel::fit(Box::new("Hello, World!")).with_title(Text::new("Title", None)),
+                50
+            ),
+            "╭──── Title ────╮\n│ Hello, World! │\n╰───────────────╯\n"
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build();
+        let panel = Panel::fit(Box::new("foo bar"));
+        assert_eq!(
+            Measurement::get(&console, &panel, None),
+            Measurement::new(11, 11)
+        );
+    }
+}
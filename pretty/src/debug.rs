@@ -0,0 +1,288 @@
+use crate::node::{AtomKind, Brackets, Entry, Node};
+
+/// Parser for the output of the `Debug` formatter, either `{:?}` or `{:#?}`
+struct DebugParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl<'a> DebugParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn consume(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? == expected {
+            self.position += expected.len_utf8();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Consume characters while the predicate holds, returning them
+    fn take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let length = rest
+            .char_indices()
+            .find(|(_, c)| !predicate(*c))
+            .map(|(index, _)| index)
+            .unwrap_or_else(|| rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+
+    fn quoted(&mut self, quote: char) -> Option<Node> {
+        let start = self.position;
+        self.position += quote.len_utf8();
+        let mut escaped = false;
+        for (index, c) in self.rest().char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                self.position += index + c.len_utf8();
+                return Some(Node::atom(&self.input[start..self.position], AtomKind::Str));
+            }
+        }
+        None
+    }
+
+    fn number(&mut self) -> Node {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        let mut previous = ' ';
+        self.take_while(|c| {
+            let accept = is_identifier_char(c)
+                || (c == '.' && previous != '.')
+                || ((c == '-' || c == '+') && (previous == 'e' || previous == 'E'));
+            previous = c;
+            accept
+        });
+        Node::atom(&self.input[start..self.position], AtomKind::Number)
+    }
+
+    fn path(&mut self) -> &'a str {
+        let start = self.position;
+        loop {
+            self.take_while(is_identifier_char);
+            let rest = self.rest();
+            if rest.starts_with("::") && rest[2..].starts_with(is_identifier_char) {
+                self.position += 2;
+            } else {
+                break;
+            }
+        }
+        &self.input[start..self.position]
+    }
+
+    fn named(&mut self) -> Option<Node> {
+        let name = self.path();
+        match name {
+            "true" => return Some(Node::atom(name, AtomKind::True)),
+            "false" => return Some(Node::atom(name, AtomKind::False)),
+            "None" => return Some(Node::atom(name, AtomKind::None)),
+            "inf" | "NaN" => return Some(Node::atom(name, AtomKind::Number)),
+            _ => {}
+        }
+        if self.peek() == Some('(') {
+            return self.entries(Some(name), Brackets::Round);
+        }
+        if self.rest().trim_start().starts_with('{') {
+            return self.entries(Some(name), Brackets::Struct);
+        }
+        Some(Node::atom(name, AtomKind::Name))
+    }
+
+    fn entry(&mut self, brackets: Brackets) -> Option<Entry> {
+        self.skip_whitespace();
+        if self.rest().starts_with("..") {
+            self.position += 2;
+            return Some(Entry::new(Node::atom("..", AtomKind::Ellipsis)));
+        }
+        let value = match brackets {
+            Brackets::Struct => Node::atom(self.path(), AtomKind::Attribute),
+            _ => self.value()?,
+        };
+        self.skip_whitespace();
+        let is_key = match brackets {
+            Brackets::Struct => true,
+            Brackets::Curly => self.peek() == Some(':'),
+            _ => false,
+        };
+        if is_key {
+            self.consume(':')?;
+            Some(Entry::with_key(value, self.value()?))
+        } else {
+            Some(Entry::new(value))
+        }
+    }
+
+    fn entries(&mut self, name: Option<&str>, brackets: Brackets) -> Option<Node> {
+        let open = match brackets {
+            Brackets::Square => '[',
+            Brackets::Round => '(',
+            Brackets::Curly | Brackets::Struct => '{',
+        };
+        let close = match open {
+            '[' => ']',
+            '(' => ')',
+            _ => '}',
+        };
+        self.consume(open)?;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == close {
+                self.position += 1;
+                break;
+            }
+            entries.push(self.entry(brackets)?);
+            self.skip_whitespace();
+            match self.peek()? {
+                ',' => self.position += 1,
+                c if c == close => {}
+                _ => return None,
+            }
+        }
+        Some(Node::container(name, brackets, entries))
+    }
+
+    fn value(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '[' => self.entries(None, Brackets::Square),
+            '(' => self.entries(None, Brackets::Round),
+            '{' => self.entries(None, Brackets::Curly),
+            '"' => self.quoted('"'),
+            '\'' => self.quoted('\''),
+            c if c == '-' || c.is_ascii_digit() => Some(self.number()),
+            c if is_identifier_char(c) => self.named(),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `Debug` output in to a node, or `None` if it is not in the usual derived format
+pub(crate) fn parse(input: &str) -> Option<Node> {
+    let mut parser = DebugParser { input, position: 0 };
+    let node = parser.value()?;
+    parser.skip_whitespace();
+    if parser.rest().is_empty() {
+        Some(node)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Point {
+        x: i32,
+        y: f64,
+        label: Option<&'static str>,
+    }
+
+    #[test]
+    fn test_parse_struct() {
+        let point = Point {
+            x: -1,
+            y: 2.5,
+            label: Some("a \"b\""),
+        };
+        let expected = Node::container(
+            Some("Point"),
+            Brackets::Struct,
+            vec![
+                Entry::with_key(
+                    Node::atom("x", AtomKind::Attribute),
+                    Node::atom("-1", AtomKind::Number),
+                ),
+                Entry::with_key(
+                    Node::atom("y", AtomKind::Attribute),
+                    Node::atom("2.5", AtomKind::Number),
+                ),
+                Entry::with_key(
+                    Node::atom("label", AtomKind::Attribute),
+                    Node::container(
+                        Some("Some"),
+                        Brackets::Round,
+                        vec![Entry::new(Node::atom("\"a \\\"b\\\"\"", AtomKind::Str))],
+                    ),
+                ),
+            ],
+        );
+        assert_eq!(parse(&format!("{:?}", point)), Some(expected.clone()));
+        assert_eq!(parse(&format!("{:#?}", point)), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_collections() {
+        let map: std::collections::BTreeMap<_, _> =
+            vec![("a", vec![1, 2]), ("b", vec![])].into_iter().collect();
+        assert_eq!(
+            parse(&format!("{:#?}", map)),
+            Some(Node::container(
+                None,
+                Brackets::Curly,
+                vec![
+                    Entry::with_key(
+                        Node::atom("\"a\"", AtomKind::Str),
+                        Node::container(
+                            None,
+                            Brackets::Square,
+                            vec![
+                                Entry::new(Node::atom("1", AtomKind::Number)),
+                                Entry::new(Node::atom("2", AtomKind::Number))
+                            ]
+                        )
+                    ),
+                    Entry::with_key(
+                        Node::atom("\"b\"", AtomKind::Str),
+                        Node::container(None, Brackets::Square, vec![])
+                    ),
+                ]
+            ))
+        );
+        assert_eq!(
+            parse("(true, None, Unit, 1e-5)"),
+            Some(Node::container(
+                None,
+                Brackets::Round,
+                vec![
+                    Entry::new(Node::atom("true", AtomKind::True)),
+                    Entry::new(Node::atom("None", AtomKind::None)),
+                    Entry::new(Node::atom("Unit", AtomKind::Name)),
+                    Entry::new(Node::atom("1e-5", AtomKind::Number)),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse("<opaque>"), None);
+        assert_eq!(parse("[1, 2"), None);
+    }
+}
@@ -0,0 +1,234 @@
+mod debug;
+mod node;
+mod ser;
+
+use std::fmt::Debug;
+
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, Measurement};
+use serde::Serialize;
+use text::Text;
+
+use crate::node::{AtomKind, Layout, Node};
+
+pub use self::ser::Error;
+
+/// A console renderable that pretty prints a value, expanding containers that don't fit in one line
+#[derive(Clone, Debug)]
+pub struct Pretty {
+    node: Node,
+    indent_size: usize,
+    max_width: Option<usize>,
+    max_length: Option<usize>,
+    max_string: Option<usize>,
+    indent_guides: bool,
+    expand_all: bool,
+}
+
+impl Pretty {
+    fn new(node: Node) -> Self {
+        Self {
+            node,
+            indent_size: 4,
+            max_width: None,
+            max_length: None,
+            max_string: None,
+            indent_guides: false,
+            expand_all: false,
+        }
+    }
+
+    /// Pretty print any value that implements `serde::Serialize`
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, Error> {
+        Ok(Self::new(value.serialize(ser::NodeSerializer)?))
+    }
+
+    /// Pretty print a value from its `Debug` output.
+    /// Output that does not follow the derived format is printed verbatim.
+    pub fn from_debug<T: Debug + ?Sized>(value: &T) -> Self {
+        let repr = format!("{:?}", value);
+        Self::new(debug::parse(&repr).unwrap_or_else(|| Node::atom(&repr, AtomKind::Other)))
+    }
+
+    /// Number of spaces to indent each nested level
+    pub fn with_indent_size(mut self, indent_size: usize) -> Self {
+        self.indent_size = indent_size;
+        self
+    }
+
+    /// Width to fit containers in, defaults to the console width
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Maximum number of entries shown for each container
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Maximum number of characters shown for each string
+    pub fn with_max_string(mut self, max_string: usize) -> Self {
+        self.max_string = Some(max_string);
+        self
+    }
+
+    /// Draw guides for the indentation of nested levels
+    pub fn with_indent_guides(mut self, indent_guides: bool) -> Self {
+        self.indent_guides = indent_guides;
+        self
+    }
+
+    /// Expand every container, even when they fit in one line
+    pub fn with_expand_all(mut self, expand_all: bool) -> Self {
+        self.expand_all = expand_all;
+        self
+    }
+
+    fn lines(&self, console: &Console, max_width: usize) -> Vec<Text> {
+        let layout = Layout {
+            console,
+            max_width: self.max_width.unwrap_or(max_width),
+            indent_size: self.indent_size,
+            max_length: self.max_length,
+            max_string: self.max_string,
+            indent_guides: self.indent_guides,
+            expand_all: self.expand_all,
+        };
+        let mut lines = Vec::new();
+        layout.lay_out(None, &self.node, 0, "", &mut lines);
+        lines
+    }
+
+    /// Highlighted text of the value laid out to fit in `max_width`
+    pub fn to_text(&self, console: &Console, max_width: usize) -> Text {
+        Text::new("\n", None).join(self.lines(console, max_width))
+    }
+}
+
+impl Renderable for Pretty {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        console
+            .render(&self.to_text(console, options.max_width), Some(options))
+            .into()
+    }
+}
+
+impl Measure for Pretty {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        let width = self
+            .lines(console, max_width)
+            .iter()
+            .map(Text::cell_len)
+            .max()
+            .unwrap_or(0);
+        Measurement::new(width, width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Debug)]
+    struct Config {
+        name: String,
+        retries: Option<u32>,
+        tags: Vec<&'static str>,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: "server".to_string(),
+            retries: None,
+            tags: vec!["a", "b"],
+        }
+    }
+
+    fn plain(pretty: &Pretty, width: usize) -> String {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        pretty.to_text(&console, width).plain().to_string()
+    }
+
+    #[test]
+    fn test_one_line() {
+        let pretty = Pretty::from_serialize(&config()).unwrap();
+        assert_eq!(
+            plain(&pretty, 80),
+            "Config { name: \"server\", retries: None, tags: [\"a\", \"b\"] }"
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let pretty = Pretty::from_serialize(&config()).unwrap();
+        assert_eq!(
+            plain(&pretty, 30),
+            "Config {\n    name: \"server\",\n    retries: None,\n    tags: [\"a\", \"b\"],\n}"
+        );
+        assert_eq!(
+            plain(&pretty.with_indent_guides(true).with_expand_all(true), 30),
+            "Config {\n│   name: \"server\",\n│   retries: None,\n│   tags: [\n│   │   \"a\",\n│   │   \"b\",\n│   ],\n}"
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let pretty = Pretty::from_debug(&config());
+        assert_eq!(
+            plain(&pretty, 80),
+            "Config { name: \"server\", retries: None, tags: [\"a\", \"b\"] }"
+        );
+        assert_eq!(plain(&Pretty::from_debug("<opaque>"), 80), "\"<opaque>\"");
+    }
+
+    #[test]
+    fn test_truncate() {
+        let map: BTreeMap<_, _> = vec![("key", "a long value"), ("other", "b")]
+            .into_iter()
+            .collect();
+        let pretty = Pretty::from_serialize(&map)
+            .unwrap()
+            .with_max_length(1)
+            .with_max_string(4);
+        assert_eq!(plain(&pretty, 80), "{\"key\": \"a lo\"+8, ...}");
+    }
+
+    #[test]
+    fn test_highlight() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .build();
+        let text = Pretty::from_serialize(&(1, true))
+            .unwrap()
+            .to_text(&console, 80);
+        assert_eq!(
+            text.get_style_at_offset(1),
+            console.get_style("repr.number")
+        );
+        assert_eq!(
+            text.get_style_at_offset(4),
+            console.get_style("repr.bool_true")
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .build();
+        let pretty = Pretty::from_serialize(&vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            Measurement::get(&console, &pretty, Some(80)),
+            Measurement::new(9, 9)
+        );
+    }
+}
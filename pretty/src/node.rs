@@ -0,0 +1,277 @@
+use console::Console;
+use text::Text;
+
+/// Kind of an atomic value, used to pick its highlighting style
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AtomKind {
+    Number,
+    Str,
+    True,
+    False,
+    None,
+    Name,
+    Attribute,
+    Ellipsis,
+    Other,
+}
+
+impl AtomKind {
+    fn style_name(self) -> Option<&'static str> {
+        match self {
+            AtomKind::Number => Some("repr.number"),
+            AtomKind::Str => Some("repr.str"),
+            AtomKind::True => Some("repr.bool_true"),
+            AtomKind::False => Some("repr.bool_false"),
+            AtomKind::None => Some("repr.none"),
+            AtomKind::Name => Some("repr.tag_name"),
+            AtomKind::Attribute => Some("repr.attrib_name"),
+            AtomKind::Ellipsis => Some("repr.ellipsis"),
+            AtomKind::Other => None,
+        }
+    }
+}
+
+/// Brackets around the entries of a container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Brackets {
+    /// `[a, b]`
+    Square,
+    /// `(a, b)`
+    Round,
+    /// `{a: b}`
+    Curly,
+    /// `Name { a: b }`
+    Struct,
+}
+
+impl Brackets {
+    fn open(self) -> &'static str {
+        match self {
+            Brackets::Square => "[",
+            Brackets::Round => "(",
+            Brackets::Curly | Brackets::Struct => "{",
+        }
+    }
+
+    fn close(self) -> &'static str {
+        match self {
+            Brackets::Square => "]",
+            Brackets::Round => ")",
+            Brackets::Curly | Brackets::Struct => "}",
+        }
+    }
+}
+
+/// An entry of a container, with an optional key
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Entry {
+    pub key: Option<Node>,
+    pub value: Node,
+}
+
+impl Entry {
+    pub fn new(value: Node) -> Self {
+        Self { key: None, value }
+    }
+
+    pub fn with_key(key: Node, value: Node) -> Self {
+        Self {
+            key: Some(key),
+            value,
+        }
+    }
+}
+
+/// Tree representation of a value to pretty print
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Node {
+    Atom {
+        repr: String,
+        kind: AtomKind,
+    },
+    Container {
+        name: Option<String>,
+        brackets: Brackets,
+        entries: Vec<Entry>,
+    },
+}
+
+impl Node {
+    pub fn atom(repr: &str, kind: AtomKind) -> Self {
+        Node::Atom {
+            repr: repr.to_string(),
+            kind,
+        }
+    }
+
+    pub fn container(name: Option<&str>, brackets: Brackets, entries: Vec<Entry>) -> Self {
+        Node::Container {
+            name: name.map(str::to_string),
+            brackets,
+            entries,
+        }
+    }
+}
+
+/// Settings to lay out a node in to lines of highlighted text
+pub(crate) struct Layout<'a> {
+    pub console: &'a Console,
+    pub max_width: usize,
+    pub indent_size: usize,
+    pub max_length: Option<usize>,
+    pub max_string: Option<usize>,
+    pub indent_guides: bool,
+    pub expand_all: bool,
+}
+
+impl Layout<'_> {
+    fn append(&self, text: &mut Text, content: &str, style_name: Option<&str>) {
+        text.append(content, style_name.map(|name| self.console.get_style(name)));
+    }
+
+    fn append_atom(&self, text: &mut Text, repr: &str, kind: AtomKind) {
+        if let (AtomKind::Str, Some(max_string)) = (kind, self.max_string) {
+            let length = repr.chars().count().saturating_sub(2);
+            if length > max_string && repr.len() >= 2 {
+                let quote = &repr[..1];
+                let contents: String = repr[1..].chars().take(max_string).collect();
+                self.append(
+                    text,
+                    &format!("{}{}{}", quote, contents, quote),
+                    kind.style_name(),
+                );
+                self.append(
+                    text,
+                    &format!("+{}", length - max_string),
+                    Some("repr.ellipsis"),
+                );
+                return;
+            }
+        }
+        self.append(text, repr, kind.style_name());
+    }
+
+    /// Entries shown after applying `max_length`, and whether any was left out
+    fn visible_entries<'n>(&self, entries: &'n [Entry]) -> (&'n [Entry], bool) {
+        match self.max_length {
+            Some(max_length) if entries.len() > max_length => (&entries[..max_length], true),
+            _ => (entries, false),
+        }
+    }
+
+    fn append_open(&self, text: &mut Text, name: &Option<String>, brackets: Brackets) {
+        if let Some(name) = name {
+            self.append(text, name, AtomKind::Name.style_name());
+            if brackets == Brackets::Struct {
+                self.append(text, " ", None);
+            }
+        }
+        self.append(text, brackets.open(), Some("repr.brace"));
+    }
+
+    fn append_key(&self, text: &mut Text, key: Option<&Node>) {
+        if let Some(key) = key {
+            self.append_one_line(text, key);
+            self.append(text, ": ", None);
+        }
+    }
+
+    fn append_one_line(&self, text: &mut Text, node: &Node) {
+        match node {
+            Node::Atom { repr, kind } => self.append_atom(text, repr, *kind),
+            Node::Container {
+                name,
+                brackets,
+                entries,
+            } => {
+                self.append_open(text, name, *brackets);
+                let (visible, truncated) = self.visible_entries(entries);
+                let padding = if *brackets == Brackets::Struct && !entries.is_empty() {
+                    " "
+                } else {
+                    ""
+                };
+                self.append(text, padding, None);
+                for (index, entry) in visible.iter().enumerate() {
+                    if index > 0 {
+                        self.append(text, ", ", Some("repr.comma"));
+                    }
+                    self.append_key(text, entry.key.as_ref());
+                    self.append_one_line(text, &entry.value);
+                }
+                if truncated {
+                    if !visible.is_empty() {
+                        self.append(text, ", ", Some("repr.comma"));
+                    }
+                    self.append(text, "...", Some("repr.ellipsis"));
+                }
+                self.append(text, padding, None);
+                self.append(text, brackets.close(), Some("repr.brace"));
+            }
+        }
+    }
+
+    fn indent(&self, depth: usize) -> Text {
+        let mut text = Text::new("", None);
+        for _ in 0..depth {
+            if self.indent_guides && self.indent_size > 0 {
+                self.append(&mut text, "│", Some("repr.indent"));
+                self.append(&mut text, &" ".repeat(self.indent_size - 1), None);
+            } else {
+                self.append(&mut text, &" ".repeat(self.indent_size), None);
+            }
+        }
+        text
+    }
+
+    /// Lay out a node, on a single line if it fits or expanded one entry per line otherwise
+    pub fn lay_out(
+        &self,
+        key: Option<&Node>,
+        node: &Node,
+        depth: usize,
+        suffix: &str,
+        lines: &mut Vec<Text>,
+    ) {
+        let mut line = self.indent(depth);
+        self.append_key(&mut line, key);
+        let expandable = matches!(node, Node::Container { entries, .. } if !entries.is_empty());
+        if expandable && !self.expand_all {
+            let mut one_line = line.clone();
+            self.append_one_line(&mut one_line, node);
+            self.append(&mut one_line, suffix, Some("repr.comma"));
+            if one_line.cell_len() <= self.max_width {
+                lines.push(one_line);
+                return;
+            }
+        }
+        match node {
+            Node::Container {
+                name,
+                brackets,
+                entries,
+            } if expandable => {
+                self.append_open(&mut line, name, *brackets);
+                lines.push(line);
+                let (visible, truncated) = self.visible_entries(entries);
+                for entry in visible {
+                    self.lay_out(entry.key.as_ref(), &entry.value, depth + 1, ",", lines);
+                }
+                if truncated {
+                    let mut ellipsis = self.indent(depth + 1);
+                    self.append(&mut ellipsis, "...", Some("repr.ellipsis"));
+                    lines.push(ellipsis);
+                }
+                let mut close = self.indent(depth);
+                self.append(&mut close, brackets.close(), Some("repr.brace"));
+                self.append(&mut close, suffix, Some("repr.comma"));
+                lines.push(close);
+            }
+            _ => {
+                self.append_one_line(&mut line, node);
+                self.append(&mut line, suffix, Some("repr.comma"));
+                lines.push(line);
+            }
+        }
+    }
+}
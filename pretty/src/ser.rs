@@ -0,0 +1,338 @@
+use serde::ser::{self, Serialize};
+
+use crate::node::{AtomKind, Brackets, Entry, Node};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Error serializing value: {0}")]
+    Custom(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+/// Serializer that builds the node tree of a value
+pub(crate) struct NodeSerializer;
+
+/// Serializer state for any compound value
+pub(crate) struct Compound {
+    name: Option<&'static str>,
+    brackets: Brackets,
+    entries: Vec<Entry>,
+    key: Option<Node>,
+}
+
+impl Compound {
+    fn new(name: Option<&'static str>, brackets: Brackets, capacity: Option<usize>) -> Self {
+        Self {
+            name,
+            brackets,
+            entries: Vec::with_capacity(capacity.unwrap_or(0)),
+            key: None,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let value = value.serialize(NodeSerializer)?;
+        self.entries.push(Entry {
+            key: self.key.take(),
+            value,
+        });
+        Ok(())
+    }
+
+    fn push_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.key = Some(Node::atom(key, AtomKind::Attribute));
+        self.push(value)
+    }
+
+    fn finish(self) -> Result<Node, Error> {
+        Ok(Node::container(self.name, self.brackets, self.entries))
+    }
+}
+
+fn number(value: impl ToString) -> Result<Node, Error> {
+    Ok(Node::atom(&value.to_string(), AtomKind::Number))
+}
+
+impl ser::Serializer for NodeSerializer {
+    type Ok = Node;
+    type Error = Error;
+    type SerializeSeq = Compound;
+    type SerializeTuple = Compound;
+    type SerializeTupleStruct = Compound;
+    type SerializeTupleVariant = Compound;
+    type SerializeMap = Compound;
+    type SerializeStruct = Compound;
+    type SerializeStructVariant = Compound;
+
+    fn serialize_bool(self, v: bool) -> Result<Node, Error> {
+        Ok(if v {
+            Node::atom("true", AtomKind::True)
+        } else {
+            Node::atom("false", AtomKind::False)
+        })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Node, Error> {
+        number(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Node, Error> {
+        number(format!("{:?}", v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Node, Error> {
+        number(format!("{:?}", v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Node, Error> {
+        Ok(Node::atom(&format!("{:?}", v), AtomKind::Str))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Node, Error> {
+        Ok(Node::atom(&format!("{:?}", v), AtomKind::Str))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Node, Error> {
+        let mut seq = Compound::new(None, Brackets::Square, Some(v.len()));
+        for byte in v {
+            seq.push(byte)?;
+        }
+        seq.finish()
+    }
+
+    fn serialize_none(self) -> Result<Node, Error> {
+        Ok(Node::atom("None", AtomKind::None))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Node, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Node, Error> {
+        Ok(Node::atom("()", AtomKind::Other))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Node, Error> {
+        Ok(Node::atom(name, AtomKind::Name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Node, Error> {
+        Ok(Node::atom(variant, AtomKind::Name))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Node, Error> {
+        let mut tuple = Compound::new(Some(name), Brackets::Round, Some(1));
+        tuple.push(value)?;
+        tuple.finish()
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Node, Error> {
+        let mut tuple = Compound::new(Some(variant), Brackets::Round, Some(1));
+        tuple.push(value)?;
+        tuple.finish()
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::new(None, Brackets::Square, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(None, Brackets::Round, Some(len)))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(Some(name), Brackets::Round, Some(len)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound, Error> {
+        Ok(Compound::new(Some(variant), Brackets::Round, Some(len)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::new(None, Brackets::Curly, len))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(Some(name), Brackets::Struct, Some(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound, Error> {
+        Ok(Compound::new(Some(variant), Brackets::Struct, Some(len)))
+    }
+}
+
+impl ser::SerializeSeq for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound {
+    type Ok = Node;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<Node, Error> {
+        self.finish()
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::BufRead;
+
+use console::Console;
+
+use crate::{Error, Prompt};
+
+/// Ask the user a yes or no question
+#[derive(Clone, Debug)]
+pub struct Confirm {
+    prompt: Prompt<String>,
+}
+
+impl Confirm {
+    /// Prompt with a question, which may contain console markup
+    pub fn new(prompt: &str) -> Self {
+        let mut prompt = Prompt::new(prompt)
+            .with_choices(&["y", "n"])
+            .with_case_sensitive(false);
+        prompt.illegal_choice_message = "Please enter Y or N".to_string();
+        Self { prompt }
+    }
+
+    /// Answer returned when the response is empty
+    pub fn with_default(mut self, default: bool) -> Self {
+        self.prompt = self
+            .prompt
+            .with_default(if default { "y" } else { "n" }.to_string());
+        self
+    }
+
+    pub fn with_show_default(mut self, show_default: bool) -> Self {
+        self.prompt = self.prompt.with_show_default(show_default);
+        self
+    }
+
+    pub fn with_show_choices(mut self, show_choices: bool) -> Self {
+        self.prompt = self.prompt.with_show_choices(show_choices);
+        self
+    }
+
+    /// Shortcut to prompt once and read the answer from stdin
+    pub fn ask(console: &mut Console, prompt: &str, default: Option<bool>) -> Result<bool, Error> {
+        let mut confirm = Self::new(prompt);
+        if let Some(default) = default {
+            confirm = confirm.with_default(default);
+        }
+        confirm.run(console)
+    }
+
+    /// Prompt until a valid answer is read from stdin
+    pub fn run(&self, console: &mut Console) -> Result<bool, Error> {
+        Ok(self.prompt.run(console)? == "y")
+    }
+
+    /// Prompt until a valid answer is read from `input`
+    pub fn run_with_input<R: BufRead>(
+        &self,
+        console: &mut Console,
+        input: &mut R,
+    ) -> Result<bool, Error> {
+        Ok(self.prompt.run_with_input(console, input)? == "y")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+    use std::io::{self, Cursor};
+
+    fn run(confirm: &Confirm, input: &str) -> (bool, String) {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(80)
+            .build();
+        console.begin_capture();
+        let answer = confirm
+            .run_with_input(&mut console, &mut Cursor::new(input))
+            .unwrap();
+        (answer, console.end_capture())
+    }
+
+    #[test]
+    fn test_confirm() {
+        let (answer, output) = run(&Confirm::new("continue?"), "maybe\nY\n");
+        assert!(answer);
+        assert_eq!(
+            output,
+            "continue? [y/n]: Please enter Y or N\ncontinue? [y/n]: "
+        );
+    }
+
+    #[test]
+    fn test_confirm_default() {
+        let (answer, output) = run(&Confirm::new("continue?").with_default(false), "\n");
+        assert!(!answer);
+        assert_eq!(output, "continue? [y/n] (n): ");
+    }
+}
@@ -0,0 +1,325 @@
+mod confirm;
+
+use std::fmt::Display;
+use std::io::{self, BufRead};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+
+use console::Console;
+use text::Text;
+
+pub use self::confirm::Confirm;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Input ended before a valid response")]
+    EndOfInput,
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+}
+
+/// Prompt for an integer
+pub type IntPrompt = Prompt<i64>;
+/// Prompt for a floating point number
+pub type FloatPrompt = Prompt<f64>;
+
+/// Ask the user for a value, and ask again until the response can be parsed
+#[derive(Clone, Debug)]
+pub struct Prompt<T = String> {
+    prompt: String,
+    default: Option<T>,
+    choices: Option<Vec<String>>,
+    password: bool,
+    case_sensitive: bool,
+    show_default: bool,
+    show_choices: bool,
+    invalid_message: String,
+    illegal_choice_message: String,
+}
+
+/// A value that can be parsed from a prompt response, other types can be prompted for by
+/// implementing it, like `impl PromptType for Url {}`
+pub trait PromptType: FromStr + Display + Clone {
+    /// Message shown when a response can't be parsed
+    const INVALID_MESSAGE: &'static str = "Please enter a valid value";
+}
+
+macro_rules! impl_prompt_type {
+    ($message:expr => $($type:ty),+) => {
+        $(
+            impl PromptType for $type {
+                const INVALID_MESSAGE: &'static str = $message;
+            }
+        )+
+    };
+    ($($type:ty),+) => {
+        $(impl PromptType for $type {})+
+    };
+}
+
+impl_prompt_type!(
+    "Please enter a valid integer number" =>
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+impl_prompt_type!("Please enter a number" => f32, f64);
+impl_prompt_type!(String, char, bool, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr);
+
+/// Read a line, without the line terminator
+fn read_line<R: BufRead>(input: &mut R) -> Result<String, Error> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(Error::EndOfInput);
+    }
+    let length = line.trim_end_matches(&['\r', '\n'][..]).len();
+    line.truncate(length);
+    Ok(line)
+}
+
+impl<T: PromptType> Prompt<T> {
+    /// Prompt with a question, which may contain console markup
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            prompt: prompt.to_string(),
+            default: None,
+            choices: None,
+            password: false,
+            case_sensitive: true,
+            show_default: true,
+            show_choices: true,
+            invalid_message: T::INVALID_MESSAGE.to_string(),
+            illegal_choice_message: "Please select one of the available options".to_string(),
+        }
+    }
+
+    /// Value returned when the response is empty
+    pub fn with_default(mut self, default: T) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Accept only one of these responses
+    pub fn with_choices(mut self, choices: &[&str]) -> Self {
+        self.choices = Some(choices.iter().map(|choice| choice.to_string()).collect());
+        self
+    }
+
+    /// Don't echo the response when reading from a terminal
+    pub fn with_password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Match choices taking case in to account
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    pub fn with_show_default(mut self, show_default: bool) -> Self {
+        self.show_default = show_default;
+        self
+    }
+
+    pub fn with_show_choices(mut self, show_choices: bool) -> Self {
+        self.show_choices = show_choices;
+        self
+    }
+
+    /// Message shown when the response can't be parsed
+    pub fn with_invalid_message(mut self, invalid_message: &str) -> Self {
+        self.invalid_message = invalid_message.to_string();
+        self
+    }
+
+    /// Shortcut to prompt once and read the response from stdin
+    pub fn ask(
+        console: &mut Console,
+        prompt: &str,
+        default: Option<T>,
+        choices: Option<&[&str]>,
+        password: Option<bool>,
+    ) -> Result<T, Error> {
+        let mut prompt = Self::new(prompt).with_password(password.unwrap_or(false));
+        if let Some(default) = default {
+            prompt = prompt.with_default(default);
+        }
+        if let Some(choices) = choices {
+            prompt = prompt.with_choices(choices);
+        }
+        prompt.run(console)
+    }
+
+    /// Prompt until a valid response is read from stdin
+    pub fn run(&self, console: &mut Console) -> Result<T, Error> {
+        if self.password && console.is_terminal() {
+            self.prompt_loop(console, || Ok(rpassword::read_password()?))
+        } else {
+            self.run_with_input(console, &mut io::stdin().lock())
+        }
+    }
+
+    /// Prompt until a valid response is read from `input`
+    pub fn run_with_input<R: BufRead>(
+        &self,
+        console: &mut Console,
+        input: &mut R,
+    ) -> Result<T, Error> {
+        self.prompt_loop(console, || read_line(input))
+    }
+
+    fn prompt_loop(
+        &self,
+        console: &mut Console,
+        mut read_line: impl FnMut() -> Result<String, Error>,
+    ) -> Result<T, Error> {
+        loop {
+            let prompt = self.make_prompt(console);
+            console.print(&prompt)?;
+            match self.process_response(&read_line()?) {
+                Ok(value) => return Ok(value),
+                Err(message) => console.print(&Text::new(
+                    message,
+                    Some(console.get_style("prompt.invalid")),
+                ))?,
+            }
+        }
+    }
+
+    /// The question followed by the choices and default
+    fn make_prompt(&self, console: &Console) -> Text {
+        let mut prompt = console
+            .render_str(&self.prompt, Some("prompt"))
+            .with_end("");
+        if self.show_choices {
+            if let Some(choices) = &self.choices {
+                prompt.append(" ", None);
+                prompt.append(
+                    &format!("[{}]", choices.join("/")),
+                    Some(console.get_style("prompt.choices")),
+                );
+            }
+        }
+        if self.show_default {
+            if let Some(default) = &self.default {
+                prompt.append(" ", None);
+                prompt.append(
+                    &format!("({})", default),
+                    Some(console.get_style("prompt.default")),
+                );
+            }
+        }
+        prompt.append(": ", None);
+        prompt
+    }
+
+    /// Parse a response, or get the message to show when it is not valid
+    fn process_response(&self, response: &str) -> Result<T, &str> {
+        let response = response.trim();
+        if response.is_empty() {
+            if let Some(default) = &self.default {
+                return Ok(default.clone());
+            }
+        }
+        let response = match &self.choices {
+            Some(choices) => choices
+                .iter()
+                .find(|choice| {
+                    if self.case_sensitive {
+                        choice.as_str() == response
+                    } else {
+                        choice.to_lowercase() == response.to_lowercase()
+                    }
+                })
+                .ok_or(self.illegal_choice_message.as_str())?,
+            None => response,
+        };
+        response.parse().map_err(|_| self.invalid_message.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::ColorSystem;
+    use console::ConsoleBuilder;
+    use std::io::Cursor;
+
+    fn run<T: PromptType>(prompt: &Prompt<T>, input: &str) -> (Result<T, Error>, String) {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(80)
+            .build();
+        console.begin_capture();
+        let result = prompt.run_with_input(&mut console, &mut Cursor::new(input));
+        (result, console.end_capture())
+    }
+
+    #[test]
+    fn test_prompt_str() {
+        let prompt = Prompt::<String>::new("what is your name?").with_default("Will".to_string());
+        let (result, output) = run(&prompt, "  Jessica \n");
+        assert_eq!(result.unwrap(), "Jessica");
+        assert_eq!(output, "what is your name? (Will): ");
+        let (result, _) = run(&prompt, "\n");
+        assert_eq!(result.unwrap(), "Will");
+    }
+
+    #[test]
+    fn test_prompt_choices() {
+        let prompt = Prompt::<String>::new("what is your name?")
+            .with_choices(&["foo", "bar"])
+            .with_case_sensitive(false);
+        let (result, output) = run(&prompt, "egg\nBAR\n");
+        assert_eq!(result.unwrap(), "bar");
+        assert_eq!(
+            output,
+            "what is your name? [foo/bar]: Please select one of the available options\nwhat is your name? [foo/bar]: "
+        );
+    }
+
+    #[test]
+    fn test_prompt_markup() {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_color_system(Some(ColorSystem::Standard))
+            .with_width(80)
+            .build();
+        console.begin_capture();
+        let prompt = Prompt::<String>::new("what is your [bold]name[/bold]?");
+        prompt
+            .run_with_input(&mut console, &mut Cursor::new("Jessica\n"))
+            .unwrap();
+        assert_eq!(console.end_capture(), "what is your \x1b[1mname\x1b[0m?: ");
+    }
+
+    #[test]
+    fn test_int_prompt() {
+        let (result, output) = run(&IntPrompt::new("guess").with_default(5), "foo\n100\n");
+        assert_eq!(result.unwrap(), 100);
+        assert_eq!(
+            output,
+            "guess (5): Please enter a valid integer number\nguess (5): "
+        );
+    }
+
+    #[test]
+    fn test_float_prompt() {
+        let (result, output) = run(&FloatPrompt::new("ratio"), "half\n0.5\n");
+        assert_eq!(result.unwrap(), 0.5);
+        assert_eq!(output, "ratio: Please enter a number\nratio: ");
+    }
+
+    #[test]
+    fn test_from_str_prompt() {
+        let prompt = Prompt::<std::net::Ipv4Addr>::new("address");
+        let (result, output) = run(&prompt, "localhost\n127.0.0.1\n");
+        assert_eq!(result.unwrap(), std::net::Ipv4Addr::LOCALHOST);
+        assert_eq!(output, "address: Please enter a valid value\naddress: ");
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let (result, _) = run(&IntPrompt::new("guess"), "foo\n");
+        assert!(matches!(result, Err(Error::EndOfInput)));
+    }
+}
@@ -26,40 +26,39 @@ pub enum RenderBoxLevel {
 pub struct RenderBox {
     inner_box: String,
     ascii: bool,
-    top: String,
-    top_divider: String,
-    top_left: String,
-    top_right: String,
-    head_left: String,
-    head_vertical: String,
-    head_right: String,
-    head_row_left: String,
-    head_row_horizontal: String,
-    head_row_cross: String,
-    head_row_right: String,
-    mid_left: String,
-    mid_vertical: String,
-    mid_right: String,
-    row_left: String,
-    row_horizontal: String,
-    row_cross: String,
-    row_right: String,
-    foot_row_left: String,
-    foot_row_horizontal: String,
-    foot_row_cross: String,
-    foot_row_right: String,
-    foot_left: String,
-    foot_vertical: String,
-    foot_right: String,
-    bottom: String,
-    bottom_divider: String,
-    bottom_left: String,
-    bottom_right: String,
+    pub top: String,
+    pub top_divider: String,
+    pub top_left: String,
+    pub top_right: String,
+    pub head_left: String,
+    pub head_vertical: String,
+    pub head_right: String,
+    pub head_row_left: String,
+    pub head_row_horizontal: String,
+    pub head_row_cross: String,
+    pub head_row_right: String,
+    pub mid_left: String,
+    pub mid_vertical: String,
+    pub mid_right: String,
+    pub row_left: String,
+    pub row_horizontal: String,
+    pub row_cross: String,
+    pub row_right: String,
+    pub foot_row_left: String,
+    pub foot_row_horizontal: String,
+    pub foot_row_cross: String,
+    pub foot_row_right: String,
+    pub foot_left: String,
+    pub foot_vertical: String,
+    pub foot_right: String,
+    pub bottom: String,
+    pub bottom_divider: String,
+    pub bottom_left: String,
+    pub bottom_right: String,
 }
 
 impl RenderBox {
     pub fn new(inner_box: &str, ascii: Option<bool>) -> Self {
-        println!("{}", inner_box);
         let ascii = ascii.unwrap_or(false);
 
         let mut lines = inner_box.split('\n');
@@ -200,7 +199,7 @@ impl RenderBox {
         Widths: IntoIterator<Item = &'a usize>,
     {
         let mut parts = vec![self.top_left.clone()];
-        for (last, width) in iter::loop_last(widths.into_iter()) {
+        for (last, width) in iter::loop_last(widths) {
             parts.push(self.top.repeat(*width));
             if !last {
                 parts.push(self.top_divider.clone());
@@ -249,7 +248,7 @@ impl RenderBox {
         if edge {
             parts.push(left.clone());
         }
-        for (last, width) in iter::loop_last(withds.into_iter()) {
+        for (last, width) in iter::loop_last(withds) {
             parts.push(horizontal.repeat(*width));
             if !last {
                 parts.push(cross.to_string());
@@ -266,7 +265,7 @@ impl RenderBox {
         Widths: IntoIterator<Item = &'a usize>,
     {
         let mut parts = vec![self.bottom_left.clone()];
-        for (last, width) in iter::loop_last(widths.into_iter()) {
+        for (last, width) in iter::loop_last(widths) {
             parts.push(self.bottom.repeat(*width));
             if !last {
                 parts.push(self.bottom_divider.clone());
@@ -541,7 +540,6 @@ lazy_static! {
 mod tests {
     use super::*;
     use console::options::Encoding;
-    use std::ops::Deref;
 
     #[test]
     fn test_string() {
@@ -582,24 +580,24 @@ mod tests {
     // TODO: Use a macro to generate independent tests for each of them
     #[test]
     fn test_static_boxes_build() {
-        ASCII.clone();
-        ASCII2.clone();
-        ASCII_DOUBLE_HEAD.clone();
-        SQUARE.clone();
-        SQUARE_DOUBLE_HEAD.clone();
-        MINIMAL.clone();
-        MINIMAL_HEAVY_HEAD.clone();
-        MINIMAL_DOUBLE_HEAD.clone();
-        SIMPLE.clone();
-        SIMPLE_HEAD.clone();
-        SIMPLE_HEAVY.clone();
-        HORIZONTALS.clone();
-        ROUNDED.clone();
-        HEAVY.clone();
-        HEAVY_EDGE.clone();
-        HEAVY_HEAD.clone();
-        DOUBLE.clone();
-        DOUBLE_EDGE.clone();
+        let _ = ASCII.clone();
+        let _ = ASCII2.clone();
+        let _ = ASCII_DOUBLE_HEAD.clone();
+        let _ = SQUARE.clone();
+        let _ = SQUARE_DOUBLE_HEAD.clone();
+        let _ = MINIMAL.clone();
+        let _ = MINIMAL_HEAVY_HEAD.clone();
+        let _ = MINIMAL_DOUBLE_HEAD.clone();
+        let _ = SIMPLE.clone();
+        let _ = SIMPLE_HEAD.clone();
+        let _ = SIMPLE_HEAVY.clone();
+        let _ = HORIZONTALS.clone();
+        let _ = ROUNDED.clone();
+        let _ = HEAVY.clone();
+        let _ = HEAVY_EDGE.clone();
+        let _ = HEAVY_HEAD.clone();
+        let _ = DOUBLE.clone();
+        let _ = DOUBLE_EDGE.clone();
     }
 
     #[test]
@@ -0,0 +1,111 @@
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use segment::Segment;
+use style::Style;
+
+/// A renderable that fills the terminal screen, its contents are cropped and padded to the
+/// width and height of the console
+pub struct Screen {
+    renderable: Box<dyn Renderable>,
+    style: Option<Style>,
+    /// Lines end in `\n\r`, for terminals in application mode
+    application_mode: bool,
+}
+
+impl Screen {
+    pub fn new(renderable: Box<impl Renderable + 'static>) -> Self {
+        Self {
+            renderable,
+            style: None,
+            application_mode: false,
+        }
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_application_mode(mut self, application_mode: bool) -> Self {
+        self.application_mode = application_mode;
+        self
+    }
+}
+
+impl Renderable for Screen {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let width = options.max_width;
+        let height = console.height();
+        let render_options = options.update_width(width);
+        let lines = console.render_lines(
+            self.renderable.as_ref(),
+            Some(&render_options),
+            self.style.clone(),
+            Some(true),
+            None,
+        );
+        let lines: Vec<&[Segment]> = lines.iter().map(Vec::as_slice).collect();
+        let lines = Segment::set_shape(&lines, width, Some(height), self.style.clone());
+        let new_line = if self.application_mode {
+            Segment::new("\n\r", None, false)
+        } else {
+            Segment::line(None)
+        };
+        let mut segments = Vec::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            if index > 0 {
+                segments.push(new_line.clone());
+            }
+            segments.extend(line);
+        }
+        segments.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::ColorSystem;
+    use console::ConsoleBuilder;
+    use std::io;
+
+    fn console() -> Console {
+        ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(6)
+            .with_height(3)
+            .with_color_system(Some(ColorSystem::TrueColor))
+            .build()
+    }
+
+    #[test]
+    fn test_screen() {
+        let mut console = console();
+        console.begin_capture();
+        console.print(&Screen::new(Box::new("foo"))).unwrap();
+        assert_eq!(console.end_capture(), "foo   \n      \n      ");
+    }
+
+    #[test]
+    fn test_screen_crop() {
+        let mut console = console();
+        console.begin_capture();
+        console
+            .print(&Screen::new(Box::new("a\nb\nc\nd\ntoo long")).with_application_mode(true))
+            .unwrap();
+        assert_eq!(console.end_capture(), "a     \n\rb     \n\rc     ");
+    }
+
+    #[test]
+    fn test_screen_style() {
+        let mut console = console();
+        console.begin_capture();
+        let screen = Screen::new(Box::new("")).with_style(Style::parse("on blue").unwrap());
+        console.print(&screen).unwrap();
+        assert_eq!(
+            console.end_capture(),
+            "\x1b[44m      \x1b[0m\n\x1b[44m      \x1b[0m\n\x1b[44m      \x1b[0m"
+        );
+    }
+}
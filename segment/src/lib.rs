@@ -1,6 +1,9 @@
-use cells::{cell_len, set_cell_size, DEFAULT_CELL_LEN_CACHE};
-use itertools::{EitherOrBoth, Itertools};
-use style::{Style, StyleBuilder};
+use cells::{cell_len, set_cell_size};
+use itertools::Itertools;
+use style::Style;
+
+/// Cells between tab stops when tabs are expanded
+pub const DEFAULT_TAB_SIZE: usize = 8;
 
 /// A piece of text with associated style
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -49,7 +52,7 @@ impl Segment {
         if self.is_control {
             0
         } else {
-            cell_len(&self.text, &mut DEFAULT_CELL_LEN_CACHE.lock().unwrap())
+            cell_len(&self.text)
         }
     }
 
@@ -105,13 +108,61 @@ impl Segment {
             .filter(move |s| s.is_control == is_control)
     }
 
-    /// Adjust a line to a given width (cropping or padding as required)
+    /// Replace the tabs of a line with spaces up to the next tab stop, there is a tab stop every
+    /// `tab_size` cells from the start of the line
+    pub fn expand_tabs(line: &[Segment], tab_size: usize) -> Vec<Segment> {
+        let mut cell_position = 0;
+        line.iter()
+            .map(|segment| {
+                if segment.is_control || !segment.text.contains('\t') {
+                    cell_position += segment.cell_len();
+                    return segment.clone();
+                }
+                let mut text = String::with_capacity(segment.text.len() + tab_size);
+                for (index, part) in segment.text.split('\t').enumerate() {
+                    if index > 0 {
+                        let spaces = tab_stop_distance(cell_position, tab_size);
+                        text.extend(std::iter::repeat_n(' ', spaces));
+                        cell_position += spaces;
+                    }
+                    text.push_str(part);
+                    cell_position += cell_len(part);
+                }
+                Segment::new(&text, segment.style.clone(), false)
+            })
+            .collect()
+    }
+
+    /// Adjust a line to a given width (cropping or padding as required), tabs are expanded to
+    /// measure it
     pub fn adjust_line_length(
         line: &[Segment],
         length: usize,
         style: Option<Style>,
         padding: Option<bool>,
     ) -> Vec<Segment> {
+        Segment::adjust_line_length_with_tab_size(line, length, style, padding, DEFAULT_TAB_SIZE)
+    }
+
+    /// Adjust a line to a given width like `adjust_line_length`, with a tab stop every
+    /// `tab_size` cells
+    pub fn adjust_line_length_with_tab_size(
+        line: &[Segment],
+        length: usize,
+        style: Option<Style>,
+        padding: Option<bool>,
+        tab_size: usize,
+    ) -> Vec<Segment> {
+        let expanded;
+        let line = if line
+            .iter()
+            .any(|segment| !segment.is_control && segment.text.contains('\t'))
+        {
+            expanded = Segment::expand_tabs(line, tab_size);
+            &expanded
+        } else {
+            line
+        };
         let padding = padding.unwrap_or(true);
         let line_length: usize = line.iter().map(|s| s.cell_len()).sum();
         if line_length < length {
@@ -128,7 +179,7 @@ impl Segment {
                     .cloned()
                     .collect()
             } else {
-                line.iter().cloned().collect()
+                line.to_vec()
             }
         } else if line_length > length {
             let mut new_line: Vec<Segment> = Vec::new();
@@ -142,11 +193,12 @@ impl Segment {
                     let (text, segment_style, _) = segment.as_tuple();
                     let text = set_cell_size(text, length - line_length);
                     new_line.push(Segment::new(&text, segment_style.clone(), false));
+                    break;
                 }
             }
             new_line
         } else {
-            line.iter().cloned().collect()
+            line.to_vec()
         }
     }
 
@@ -193,6 +245,29 @@ impl Segment {
         padding: Option<bool>,
         include_new_lines: Option<bool>,
     ) -> Vec<Vec<Segment>>
+    where
+        Segments: IntoIterator<Item = &'a Segment>,
+    {
+        Segment::split_and_crop_lines_with_tab_size(
+            segments,
+            length,
+            style,
+            padding,
+            include_new_lines,
+            DEFAULT_TAB_SIZE,
+        )
+    }
+
+    /// Split segments in to lines and crop them like `split_and_crop_lines`, with a tab stop
+    /// every `tab_size` cells
+    pub fn split_and_crop_lines_with_tab_size<'a, Segments>(
+        segments: Segments,
+        length: usize,
+        style: Option<Style>,
+        padding: Option<bool>,
+        include_new_lines: Option<bool>,
+        tab_size: usize,
+    ) -> Vec<Vec<Segment>>
     where
         Segments: IntoIterator<Item = &'a Segment>,
     {
@@ -208,8 +283,13 @@ impl Segment {
                     match text.splitn(2, '\n').collect::<Vec<&str>>().as_slice() {
                         [_text, next] => {
                             line.push(Segment::new(_text, style.clone(), false));
-                            let mut cropped_line =
-                                Segment::adjust_line_length(&line, length, style.clone(), padding);
+                            let mut cropped_line = Segment::adjust_line_length_with_tab_size(
+                                &line,
+                                length,
+                                style.clone(),
+                                padding,
+                                tab_size,
+                            );
                             if include_new_lines {
                                 cropped_line.push(new_line_segment.clone());
                             }
@@ -229,7 +309,9 @@ impl Segment {
             }
         }
         if !line.is_empty() {
-            res.push(Segment::adjust_line_length(&line, length, style, padding));
+            res.push(Segment::adjust_line_length_with_tab_size(
+                &line, length, style, padding, tab_size,
+            ));
         }
         res
     }
@@ -249,7 +331,8 @@ impl Segment {
         (max_width, lines.len())
     }
 
-    /// Set the shape of a list of lines (enclosing rectangle)
+    /// Set the shape of a list of lines (enclosing rectangle), lines are cropped or padded to
+    /// the width and the height
     pub fn set_shape(
         lines: &[&[Segment]],
         width: usize,
@@ -261,13 +344,14 @@ impl Segment {
         let pad_line = [Segment::new(&" ".repeat(width), style.clone(), false)];
         lines
             .iter()
-            .zip_longest((0..height).into_iter())
+            .zip_longest(0..height)
             .map(|e| match e {
                 Both(line, _) | Left(line) => {
                     Segment::adjust_line_length(line, width, style.clone(), None)
                 }
                 Right(_) => pad_line.to_vec(),
             })
+            .take(height)
             .collect()
     }
 
@@ -308,6 +392,15 @@ impl Segment {
     }
 }
 
+/// Spaces a tab at a cell position is expanded to, tabs are removed when `tab_size` is 0
+pub fn tab_stop_distance(cell_position: usize, tab_size: usize) -> usize {
+    if tab_size == 0 {
+        0
+    } else {
+        tab_size - cell_position % tab_size
+    }
+}
+
 struct SimplifiedSegments<'a, Segments: Iterator<Item = &'a Segment>> {
     inner: Segments,
     last_segment: Option<Segment>,
@@ -336,7 +429,7 @@ impl<'a, Segments: Iterator<Item = &'a Segment>> Iterator for SimplifiedSegments
 
         if let Some(last_segment) = last_segment {
             let mut last_segment = last_segment.clone();
-            while let Some(segment) = self.inner.next() {
+            for segment in self.inner.by_ref() {
                 if last_segment.style == segment.style && !segment.is_control {
                     last_segment = Segment::new(
                         &format!("{}{}", last_segment.text, segment.text),
@@ -359,7 +452,7 @@ impl<'a, Segments: Iterator<Item = &'a Segment>> Iterator for SimplifiedSegments
 mod tests {
     use crate::Segment;
     use color::Color;
-    use style::{Style, StyleAttribute, StyleBuilder};
+    use style::{StyleAttribute, StyleBuilder};
 
     #[test]
     fn test_line() {
@@ -441,6 +534,70 @@ mod tests {
         assert_eq!(Segment::adjust_line_length(&line, 5, None, None), line);
     }
 
+    #[test]
+    fn test_adjust_line_length_crop() {
+        // segments after the one that is cropped are dropped
+        let line = [
+            Segment::new("Hel", None, false),
+            Segment::new("lo, ", None, false),
+            Segment::new("World!", None, false),
+        ];
+        let expected = [
+            Segment::new("Hel", None, false),
+            Segment::new("lo", None, false),
+        ];
+        assert_eq!(Segment::adjust_line_length(&line, 5, None, None), expected);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let bold = StyleBuilder::new()
+            .with_attribute(StyleAttribute::BOLD, true)
+            .build();
+        let line = [
+            Segment::new("a\tbc", None, false),
+            Segment::new("\x1b[2K", None, true),
+            Segment::new("\t日本\t", Some(bold.clone()), false),
+        ];
+        assert_eq!(
+            Segment::expand_tabs(&line, 4),
+            [
+                Segment::new("a   bc", None, false),
+                Segment::new("\x1b[2K", None, true),
+                Segment::new("  日本    ", Some(bold), false),
+            ]
+        );
+        let line = [Segment::new("ab\tc", None, false)];
+        assert_eq!(
+            Segment::expand_tabs(&line, 0),
+            [Segment::new("abc", None, false)]
+        );
+        assert_eq!(Segment::expand_tabs(&line, 8)[0].text(), "ab      c");
+    }
+
+    #[test]
+    fn test_adjust_line_length_tabs() {
+        let line = [Segment::new("a\tb", None, false)];
+        assert_eq!(
+            Segment::adjust_line_length(&line, 10, None, None),
+            [
+                Segment::new("a       b", None, false),
+                Segment::new(" ", None, false),
+            ]
+        );
+        assert_eq!(
+            Segment::adjust_line_length(&line, 4, None, None),
+            [Segment::new("a   ", None, false)]
+        );
+        assert_eq!(
+            Segment::adjust_line_length_with_tab_size(&line, 4, None, None, 2),
+            [
+                Segment::new("a b", None, false),
+                Segment::new(" ", None, false),
+            ]
+        );
+    }
+
     #[test]
     fn test_split_and_crop_lines() {
         let original = [
@@ -484,6 +641,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::cloned_ref_to_slice_refs)]
     fn test_set_shape() {
         let segment = Segment::new("Hello", None, false);
         let gap_segment = Segment::new("     ", None, false);
@@ -499,6 +657,18 @@ mod tests {
                 vec![Segment::new(&" ".repeat(10), None, false)]
             ]
         );
+        assert_eq!(
+            Segment::set_shape(
+                &[
+                    std::slice::from_ref(&segment),
+                    std::slice::from_ref(&segment)
+                ],
+                5,
+                Some(1),
+                None
+            ),
+            [[segment]]
+        );
     }
 
     #[test]
@@ -0,0 +1,17 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use style::Style;
+
+fn bench_style(c: &mut Criterion) {
+    let link = Style::parse("bold red on white link https://example.org").unwrap();
+    let base = Style::parse("italic blue").unwrap();
+    let mut group = c.benchmark_group("style");
+    group.bench_function("clone", |b| b.iter(|| black_box(&link).clone()));
+    group.bench_function("combine", |b| {
+        b.iter(|| black_box(&base).combine(Some(black_box(&link))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_style);
+criterion_main!(benches);
@@ -1,3 +1,5 @@
 mod style;
 
-pub use self::style::{Error, Style, StyleAttribute, StyleBuilder, NULL_STYLE};
+pub use self::style::{
+    Error, Style, StyleAttribute, StyleBuilder, StyleStack, UnderlineStyle, NULL_STYLE,
+};
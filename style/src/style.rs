@@ -1,14 +1,25 @@
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
 use std::ops::BitAnd;
 use std::option::Option::Some;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use lazy_static::lazy_static;
+use lru::LruCache;
 
 use color::{blend_rgb, terminal_theme::TerminalTheme, Color, ColorSystem};
 
 lazy_static! {
+    static ref STYLE_NAMES: [&'static str; 13] = {
+        [
+            "bold", "dim", "italic", "underline", "blink", "blink2", "reverse", "conceal", "strike",
+            "underline2", "frame", "encircle", "overline",
+        ]
+    };
     static ref STYLE_MAP: [&'static str; 13] = {
         [
             "1", "2", "3", "4", "5", "6", "7", "8", "9", "21", "51", "52", "53",
@@ -41,6 +52,33 @@ lazy_static! {
         m
     };
     pub static ref NULL_STYLE: Style = Style::default();
+    /// Data of the null style, shared by all of them
+    static ref NULL_STYLE_DATA: Arc<StyleData> = Arc::new(StyleData {
+        style_definition: "none".to_string(),
+        color: None,
+        background_color: None,
+        set_attributes: Default::default(),
+        attributes: Default::default(),
+        underline_style: None,
+        underline_color: None,
+        link: None,
+        link_id: "".to_string(),
+        null: true,
+    });
+    /// Codes that turn attributes off, some of them turn off more than one attribute
+    static ref STYLE_OFF_MAP: [(StyleAttribute, &'static str); 9] = {
+        [
+            (StyleAttribute::BOLD | StyleAttribute::DIM, "22"),
+            (StyleAttribute::ITALIC, "23"),
+            (StyleAttribute::UNDERLINE | StyleAttribute::UNDERLINE2, "24"),
+            (StyleAttribute::BLINK | StyleAttribute::BLINK2, "25"),
+            (StyleAttribute::REVERSE, "27"),
+            (StyleAttribute::CONCEAL, "28"),
+            (StyleAttribute::STRIKE, "29"),
+            (StyleAttribute::FRAME | StyleAttribute::ENCIRCLE, "54"),
+            (StyleAttribute::OVERLINE, "55"),
+        ]
+    };
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -94,13 +132,66 @@ impl StyleAttribute {
     }
 }
 
+/// Shape of the line drawn under text, besides the straight and double underlines of
+/// `StyleAttribute`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum UnderlineStyle {
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Name of the underline style in style definitions
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnderlineStyle::Curly => "undercurl",
+            UnderlineStyle::Dotted => "underdotted",
+            UnderlineStyle::Dashed => "underdashed",
+        }
+    }
+
+    /// Underline style of a name in style definitions
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "undercurl" => Some(UnderlineStyle::Curly),
+            "underdotted" => Some(UnderlineStyle::Dotted),
+            "underdashed" => Some(UnderlineStyle::Dashed),
+            _ => None,
+        }
+    }
+
+    /// ANSI code of the underline style, terminals with standard colors get a straight underline
+    /// as they don't support the extended underlines
+    fn ansi_code(&self, color_system: ColorSystem) -> &'static str {
+        match (color_system, self) {
+            (ColorSystem::Standard | ColorSystem::Windows, _) => "4",
+            (_, UnderlineStyle::Curly) => "4:3",
+            (_, UnderlineStyle::Dotted) => "4:4",
+            (_, UnderlineStyle::Dashed) => "4:5",
+        }
+    }
+
+    /// CSS `text-decoration-style` of the underline style
+    fn css(&self) -> &'static str {
+        match self {
+            UnderlineStyle::Curly => "wavy",
+            UnderlineStyle::Dotted => "dotted",
+            UnderlineStyle::Dashed => "dashed",
+        }
+    }
+}
+
 /// A terminal style.
 /// A terminal style consists of a color (`color`), a background color (`bgcolor`), and a number of attributes, such
 /// as bold, italic etc. The attributes have 3 states: they can either be on
 /// (``True``), off (``False``), or not set (``None``).
-#[derive(Debug, Eq)]
-pub struct Style {
-    ansi: String,
+/// Styles are immutable and share their data, so cloning them is cheap.
+#[derive(Clone, Debug)]
+pub struct Style(Arc<StyleData>);
+
+#[derive(Clone, Debug)]
+struct StyleData {
     style_definition: String,
     /// Color of terminal text. Defaults to None.
     color: Option<Color>,
@@ -108,36 +199,58 @@ pub struct Style {
     background_color: Option<Color>,
     set_attributes: StyleAttribute,
     attributes: StyleAttribute,
+    /// Curly, dotted or dashed underline. Defaults to None.
+    underline_style: Option<UnderlineStyle>,
+    /// Color of the underline, the color of the text when not set. Defaults to None.
+    underline_color: Option<Color>,
     /// Link URL. Defaults to None.
     link: Option<String>,
+    /// Assigned once, when the link is set
     link_id: String,
     null: bool,
 }
 
+/// Number of combined styles kept by each thread
+const COMBINE_CACHE_SIZE: usize = 1024;
+
+/// Combined style of a pair, along with the pair
+type CombinedStyle = (Style, Style, Style);
+
+thread_local! {
+    /// Recently combined styles, keyed by the address of the shared data of the pair. The
+    /// cache keeps the pair alive, so the addresses can't be reused while they are cached.
+    static COMBINE_CACHE: RefCell<LruCache<(usize, usize), CombinedStyle>> =
+        RefCell::new(LruCache::new(NonZeroUsize::new(COMBINE_CACHE_SIZE).unwrap()));
+}
+
+/// Link ids are unique, prefixed by the process id so other programs writing to the same
+/// terminal don't share them
+fn next_link_id() -> String {
+    static NEXT_LINK_ID: AtomicUsize = AtomicUsize::new(1);
+    format!(
+        "{}-{}",
+        std::process::id(),
+        NEXT_LINK_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
 #[derive(Clone)]
 pub struct StyleBuilder {
     color: Option<Color>,
     background_color: Option<Color>,
     attributes_set: HashSet<StyleAttribute>,
     attributes: StyleAttribute,
+    underline_style: Option<UnderlineStyle>,
+    underline_color: Option<Color>,
     link: Option<String>,
 }
 
-struct StyleStack(VecDeque<Style>);
+/// A stack of styles, where each pushed style is combined with the current one
+pub struct StyleStack(VecDeque<Style>);
 
 impl Default for Style {
     fn default() -> Self {
-        Self {
-            ansi: "".to_string(),
-            style_definition: "none".to_string(),
-            color: None,
-            background_color: None,
-            set_attributes: Default::default(),
-            attributes: Default::default(),
-            link: None,
-            link_id: "".to_string(),
-            null: true,
-        }
+        Self(NULL_STYLE_DATA.clone())
     }
 }
 
@@ -148,6 +261,8 @@ impl Default for StyleBuilder {
             background_color: None,
             attributes_set: HashSet::with_capacity(13),
             attributes: Default::default(),
+            underline_style: None,
+            underline_color: None,
             link: None,
         }
     }
@@ -174,6 +289,16 @@ impl StyleBuilder {
         self
     }
 
+    pub fn with_underline_style(mut self, underline_style: UnderlineStyle) -> Self {
+        self.underline_style = Some(underline_style);
+        self
+    }
+
+    pub fn with_underline_color(mut self, color: Color) -> Self {
+        self.underline_color = Some(color);
+        self
+    }
+
     pub fn with_link(mut self, link: &str) -> Self {
         self.link = Some(link.to_string());
         self
@@ -206,7 +331,11 @@ impl StyleBuilder {
             .cloned()
             .map(|flag| (flag, self.attributes.enabled(flag)))
             .collect();
-        Style::new(self.color, self.background_color, &attributes, self.link)
+        let mut data = StyleData::new(self.color, self.background_color, &attributes, self.link);
+        data.underline_style = self.underline_style;
+        data.underline_color = self.underline_color;
+        data.update();
+        Style(Arc::new(data))
     }
 }
 
@@ -217,44 +346,9 @@ impl Style {
         attributes: &[(StyleAttribute, bool)],
         link: Option<String>,
     ) -> Self {
-        let set_attributes: StyleAttribute = attributes
-            .iter()
-            .map(|(flag, _)| flag)
-            .fold(StyleAttribute::default(), |f1, f2| f1 | *f2);
-
-        let attributes: StyleAttribute = if set_attributes.bits > 0 {
-            attributes
-                .iter()
-                .fold(StyleAttribute::default(), |accum, (flag, value)| {
-                    if *value {
-                        accum | *flag
-                    } else {
-                        accum
-                    }
-                })
-        } else {
-            StyleAttribute::default()
-        };
-
-        let null = !(set_attributes.bits > 0
-            || color.is_some()
-            || background_color.is_some()
-            || link.is_some());
-
-        let mut obj = Self {
-            color,
-            background_color,
-            set_attributes,
-            attributes,
-            link: link.clone(),
-            link_id: link
-                .map(|_| uuid::Uuid::new_v4().to_string())
-                .unwrap_or(Default::default()),
-            null,
-            ..Default::default()
-        };
-        obj.load_style_definition();
-        obj
+        let mut data = StyleData::new(color, background_color, attributes, link);
+        data.update();
+        Self(Arc::new(data))
     }
 
     pub fn null() -> Self {
@@ -263,40 +357,52 @@ impl Style {
 
     pub fn from_color(color: Option<Color>, background_color: Option<Color>) -> Self {
         let null = !(color.is_some() || background_color.is_some());
-        Self {
-            ansi: "".to_string(),
+        Self(Arc::new(StyleData {
             style_definition: "none".to_string(),
             color,
             background_color,
             set_attributes: StyleAttribute::default(),
             attributes: StyleAttribute::default(),
+            underline_style: None,
+            underline_color: None,
             link: None,
             link_id: "".to_string(),
             null,
-        }
+        }))
     }
 
     #[inline]
     fn flag_value(&self, flag: StyleAttribute) -> Option<bool> {
-        if self.set_attributes.enabled(flag) {
-            Some(self.attributes.enabled(flag))
-        } else {
-            None
-        }
+        self.0.flag_value(flag)
+    }
+
+    /// Attributes that are set and enabled
+    pub fn enabled_attributes(&self) -> StyleAttribute {
+        self.0.attributes & self.0.set_attributes
     }
 
     /// The foreground color or None if it is not set
     pub fn color(&self) -> Option<&Color> {
-        self.color.as_ref()
+        self.0.color.as_ref()
     }
 
     /// The background color or None if it is not set
     pub fn background_color(&self) -> Option<&Color> {
-        self.background_color.as_ref()
+        self.0.background_color.as_ref()
+    }
+
+    /// The curly, dotted or dashed underline or None if it is not set
+    pub fn underline_style(&self) -> Option<UnderlineStyle> {
+        self.0.underline_style
+    }
+
+    /// The underline color or None if it is not set
+    pub fn underline_color(&self) -> Option<&Color> {
+        self.0.underline_color.as_ref()
     }
 
     pub fn link(&self) -> &Option<String> {
-        &self.link
+        &self.0.link
     }
 
     /// bold text flag
@@ -366,17 +472,17 @@ impl Style {
 
     /// Get a link id, used in ansi code for links
     pub fn link_id(&self) -> &str {
-        &self.link_id
+        &self.0.link_id
     }
 
     /// A Style is false if it has no attributes, colors, or links
     pub fn as_bool(&self) -> bool {
-        !self.null
+        !self.0.null
     }
 
     /// Check if the style specified a transparent background
     pub fn transparent_background(&self) -> bool {
-        if let Some(color) = &self.background_color {
+        if let Some(color) = &self.0.background_color {
             color.is_default()
         } else {
             false
@@ -385,7 +491,7 @@ impl Style {
 
     /// A Style with background only
     pub fn background_style(&self) -> Style {
-        if let Some(color) = &self.background_color {
+        if let Some(color) = &self.0.background_color {
             StyleBuilder::new()
                 .with_background_color(color.clone())
                 .build()
@@ -394,95 +500,26 @@ impl Style {
         }
     }
 
-    fn load_style_definition(&mut self) {
-        // calculate, store and return
-        let mut attributes: Vec<&str> = Vec::new();
-
-        // TODO: maybe use some loop here?
-        if let Some(bold) = self.bold() {
-            attributes.push(if bold { "bold" } else { "not bold" });
-        }
-
-        if let Some(dim) = self.dim() {
-            attributes.push(if dim { "dim" } else { "not dim" });
-        }
-
-        if let Some(italic) = self.italic() {
-            attributes.push(if italic { "italic" } else { "not italic" });
-        }
-
-        if let Some(underline) = self.underline() {
-            attributes.push(if underline {
-                "underline"
-            } else {
-                "not underline"
-            });
-        }
-
-        if let Some(blink) = self.blink() {
-            attributes.push(if blink { "blink" } else { "not blink" });
-        }
-
-        if let Some(blink2) = self.blink2() {
-            attributes.push(if blink2 { "blink2" } else { "not blink2" });
-        }
-
-        if let Some(reverse) = self.reverse() {
-            attributes.push(if reverse { "reverse" } else { "not reverse" });
-        }
-
-        if let Some(conceal) = self.conceal() {
-            attributes.push(if conceal { "conceal" } else { "not conceal" });
-        }
-
-        if let Some(strike) = self.strike() {
-            attributes.push(if strike { "strike" } else { "not strike" });
-        }
-
-        if let Some(underline2) = self.underline2() {
-            attributes.push(if underline2 {
-                "underline2"
-            } else {
-                "not underline2"
-            });
-        }
-
-        if let Some(frame) = self.frame() {
-            attributes.push(if frame { "frame" } else { "not frame" });
-        }
-
-        if let Some(encircle) = self.encircle() {
-            attributes.push(if encircle { "encircle" } else { "not encircle" });
-        }
-
-        if let Some(overline) = self.overline() {
-            attributes.push(if overline { "overline" } else { "not overline" });
-        }
-
-        if let Some(color) = self.color() {
-            attributes.push(color.name.as_str());
-        }
-
-        if let Some(color) = self.background_color() {
-            attributes.push("on");
-            attributes.push(color.name.as_str());
-        }
-
-        if let Some(link) = self.link() {
-            attributes.push("link");
-            attributes.push(link.as_str());
-        }
+    /// Re-generate style definition from attributes
+    pub fn style_definition(&self) -> &str {
+        &self.0.style_definition
+    }
 
-        let mut res: String = attributes.join(" ");
-        if res.is_empty() {
-            res = "none".to_string();
-        }
-        self.style_definition = res;
+    /// ANSI code of the underline style, if any
+    fn underline_style_code(&self, color_system: ColorSystem) -> Option<&'static str> {
+        self.underline_style()
+            .map(|underline_style| underline_style.ansi_code(color_system))
     }
 
-    /// Re-generate style definition from attributes
-    pub fn style_definition(&self) -> &str {
-        &self.style_definition
+    /// ANSI codes of the underline color, if any. Left out with standard colors as those
+    /// terminals don't support underline colors.
+    fn underline_color_codes(&self, color_system: ColorSystem) -> Option<Vec<String>> {
+        match color_system {
+            ColorSystem::Standard | ColorSystem::Windows => None,
+            ColorSystem::EightBit | ColorSystem::TrueColor => self
+                .underline_color()
+                .map(|color| color.downgrade(color_system).get_underline_ansi_codes()),
+        }
     }
 
     /// Generate ANSI codes for this style
@@ -490,7 +527,12 @@ impl Style {
         let mut ansi_codes: Vec<String> = Vec::new();
         for (i, flag) in StyleAttribute::all_flags().iter().enumerate() {
             if matches!(self.flag_value(*flag), Some(true)) {
-                ansi_codes.push(STYLE_MAP[i as usize].to_string());
+                ansi_codes.push(STYLE_MAP[i].to_string());
+            }
+        }
+        if let Some(code) = self.underline_style_code(color_system) {
+            if !ansi_codes.iter().any(|ansi_code| ansi_code == code) {
+                ansi_codes.push(code.to_string());
             }
         }
         if let Some(color) = self.color() {
@@ -511,53 +553,173 @@ impl Style {
                     .cloned(),
             );
         }
+        ansi_codes.extend(self.underline_color_codes(color_system).unwrap_or_default());
         ansi_codes.join(";")
     }
 
+    /// Generate the ANSI codes that change a terminal rendering the `previous` style to render
+    /// this one. Only the differences are included, unless resetting is shorter. Empty if both
+    /// styles render the same.
+    pub fn ansi_transition_codes(&self, previous: &Style, color_system: ColorSystem) -> String {
+        let color_codes = |color: Option<&Color>, foreground: bool| {
+            color.map(|color| {
+                color
+                    .downgrade(color_system)
+                    .get_ansi_codes(Some(foreground))
+            })
+        };
+        let enabled = self.enabled_attributes();
+        let previous_enabled = previous.enabled_attributes();
+        let color = color_codes(self.color(), true);
+        let background_color = color_codes(self.background_color(), false);
+        let previous_color = color_codes(previous.color(), true);
+        let previous_background_color = color_codes(previous.background_color(), false);
+        let underline_style = self.underline_style_code(color_system);
+        let previous_underline_style = previous.underline_style_code(color_system);
+        let underline_color = self.underline_color_codes(color_system);
+        let previous_underline_color = previous.underline_color_codes(color_system);
+        if enabled == previous_enabled
+            && color == previous_color
+            && background_color == previous_background_color
+            && underline_style == previous_underline_style
+            && underline_color == previous_underline_color
+        {
+            return String::new();
+        }
+        let reset = match self.ansi_codes(color_system) {
+            codes if codes.is_empty() => "0".to_string(),
+            codes => format!("0;{}", codes),
+        };
+
+        let mut codes: Vec<String> = Vec::new();
+        let removed = previous_enabled - enabled;
+        let mut added = enabled - previous_enabled;
+        let underlines = StyleAttribute::UNDERLINE | StyleAttribute::UNDERLINE2;
+        for (flags, code) in STYLE_OFF_MAP.iter() {
+            let turn_off = if *flags == underlines {
+                // an underline style replaces a straight underline, but not a double one, and
+                // only the code turning off underlines turns off the underline style
+                removed.enabled(StyleAttribute::UNDERLINE2)
+                    || (underline_style.is_none()
+                        && (removed.intersects(*flags) || previous_underline_style.is_some()))
+            } else {
+                removed.intersects(*flags)
+            };
+            if turn_off {
+                codes.push(code.to_string());
+                // attributes that share the code are turned off too
+                added |= enabled & *flags;
+            }
+        }
+        for (i, flag) in StyleAttribute::all_flags().iter().enumerate() {
+            if added.enabled(*flag) {
+                codes.push(STYLE_MAP[i].to_string());
+            }
+        }
+        if let Some(code) = underline_style {
+            // turning underlines on or off resets the underline style
+            if (underline_style != previous_underline_style
+                || (removed | added).intersects(underlines))
+                && !codes.iter().any(|added_code| added_code == code)
+            {
+                codes.push(code.to_string());
+            }
+        }
+        if color != previous_color {
+            codes.extend(color.unwrap_or_else(|| vec!["39".to_string()]));
+        }
+        if background_color != previous_background_color {
+            codes.extend(background_color.unwrap_or_else(|| vec!["49".to_string()]));
+        }
+        if underline_color != previous_underline_color {
+            codes.extend(underline_color.unwrap_or_else(|| vec!["59".to_string()]));
+        }
+        let codes = codes.join(";");
+        if codes.len() < reset.len() {
+            codes
+        } else {
+            reset
+        }
+    }
+
+    /// Copy of the style with a new link, or without a link
     pub fn update_link(&self, link: Option<&str>) -> Self {
-        let mut ret = self.clone();
-        ret.link = link.map(str::to_string);
-        ret
+        let mut data = (*self.0).clone();
+        data.link = link.map(str::to_string);
+        data.link_id = link.map(|_| next_link_id()).unwrap_or_default();
+        data.update();
+        Self(Arc::new(data))
     }
 
+    /// Copy of the style with a new underline style, or without one
+    pub fn update_underline_style(&self, underline_style: Option<UnderlineStyle>) -> Self {
+        let mut data = (*self.0).clone();
+        data.underline_style = underline_style;
+        data.update();
+        Self(Arc::new(data))
+    }
+
+    /// Copy of the style with a new underline color, or without one
+    pub fn update_underline_color(&self, underline_color: Option<Color>) -> Self {
+        let mut data = (*self.0).clone();
+        data.underline_color = underline_color;
+        data.update();
+        Self(Arc::new(data))
+    }
+
+    /// Combine with another style, the values set in `style2` take precedence.
+    /// Recently combined styles are cached.
     pub fn combine(&self, style2: Option<&Self>) -> Self {
-        match (self, style2) {
-            (style, None) => style.clone(),
-            (style, Some(style2)) => {
-                if style2.null {
-                    return style.clone();
-                }
-                if style.null {
-                    return style2.clone();
-                }
-                let mut new_style = style.clone();
-                new_style.color = match (&style.color, &style2.color) {
-                    (Some(color), _) => Some(color.clone()),
-                    (None, other) => other.clone(),
-                };
-                new_style.background_color =
-                    match (&style.background_color, &style2.background_color) {
-                        (Some(color), _) => Some(color.clone()),
-                        (None, other) => other.clone(),
-                    };
-                new_style.attributes = (style.attributes & !style2.set_attributes)
-                    | (style2.attributes & style2.set_attributes);
-                new_style.set_attributes = style.set_attributes | style2.set_attributes;
-                new_style.link = match (&style.link, &style2.link) {
-                    (Some(link), _) => Some(link.clone()),
-                    (None, other) => other.clone(),
-                };
-                new_style.link_id = match (style.link_id.as_str(), style2.link_id.as_str()) {
-                    ("", "") => "".to_string(),
-                    ("", id) => id.to_string(),
-                    (id, "") => id.to_string(),
-                    _ => unreachable!(),
-                };
-                new_style.null = style.null || style2.null;
-                new_style.load_style_definition();
-                new_style
+        let style2 = match style2 {
+            Some(style2) if !style2.0.null => style2,
+            _ => return self.clone(),
+        };
+        if self.0.null {
+            return style2.clone();
+        }
+        let key = (
+            Arc::as_ptr(&self.0) as usize,
+            Arc::as_ptr(&style2.0) as usize,
+        );
+        COMBINE_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if let Some((_, _, combined)) = cache.get(&key) {
+                return combined.clone();
             }
+            let combined = self.combine_uncached(style2);
+            cache.put(key, (self.clone(), style2.clone(), combined.clone()));
+            combined
+        })
+    }
+
+    fn combine_uncached(&self, style2: &Self) -> Self {
+        let (style, style2) = (&self.0, &style2.0);
+        let mut data = (**style).clone();
+        data.color = style2.color.clone().or_else(|| style.color.clone());
+        data.background_color = style2
+            .background_color
+            .clone()
+            .or_else(|| style.background_color.clone());
+        data.attributes = (style.attributes & !style2.set_attributes)
+            | (style2.attributes & style2.set_attributes);
+        data.set_attributes = style.set_attributes | style2.set_attributes;
+        // turning off the underline turns off the underline style too
+        data.underline_style = if style2.flag_value(StyleAttribute::UNDERLINE) == Some(false) {
+            style2.underline_style
+        } else {
+            style2.underline_style.or(style.underline_style)
+        };
+        data.underline_color = style2
+            .underline_color
+            .clone()
+            .or_else(|| style.underline_color.clone());
+        if style2.link.is_some() {
+            data.link = style2.link.clone();
+            data.link_id = style2.link_id.clone();
         }
+        data.null = style.null || style2.null;
+        data.load_style_definition();
+        Self(Arc::new(data))
     }
 
     pub fn chain<'a, Styles>(styles: Styles) -> Style
@@ -577,7 +739,7 @@ impl Style {
             return Ok(Style::null());
         }
         let mut style_builder = StyleBuilder::new();
-        let mut words = style_definition.split_ascii_whitespace().into_iter();
+        let mut words = style_definition.split_ascii_whitespace();
         while let Some(original_word) = words.next() {
             let word = original_word.to_lowercase();
             match word.as_str() {
@@ -604,21 +766,34 @@ impl Style {
                         .ok_or(Error::SyntaxError("URL expected after 'link'".to_string()))?;
                     style_builder = style_builder.with_link(link);
                 }
+                "underline_color" => {
+                    let color_word = words.next().ok_or(Error::SyntaxError(
+                        "color expected after 'underline_color'".to_string(),
+                    ))?;
+                    let color = Color::parse(color_word)?;
+                    style_builder = style_builder.with_underline_color(color);
+                }
                 attribute if STYLE_ATTRIBUTES.contains_key(attribute) => {
                     style_builder = style_builder.attribute_from_str(attribute, true);
                 }
-                word => {
-                    let color = Color::parse(word)?;
-                    style_builder = style_builder.with_color(color);
-                }
+                word => match UnderlineStyle::from_name(word) {
+                    Some(underline_style) => {
+                        style_builder = style_builder.with_underline_style(underline_style);
+                    }
+                    None => {
+                        let color = Color::parse(word)?;
+                        style_builder = style_builder.with_color(color);
+                    }
+                },
             }
         }
         Ok(style_builder.build())
     }
 
     // Get a CSS style rule
+    // There is no SVG export yet, so underline styles and colors are only exported to HTML.
     pub fn get_html_style(&self, theme: Option<TerminalTheme>) -> String {
-        let theme = theme.unwrap_or(Default::default());
+        let theme = theme.unwrap_or_default();
         let mut css: Vec<String> = Vec::new();
         let (mut color, mut background_color) =
             (self.color().cloned(), self.background_color().cloned());
@@ -629,10 +804,10 @@ impl Style {
         }
 
         if self.dim().unwrap_or(false) {
-            let foreground_color = if color.is_none() {
-                theme.foreground_color
+            let foreground_color = if let Some(color) = color {
+                color.get_true_color(Some(&theme), None)
             } else {
-                color.unwrap().get_true_color(Some(&theme), None)
+                theme.foreground_color
             };
             color = Some(Color::from_triplet(blend_rgb(
                 foreground_color,
@@ -659,16 +834,31 @@ impl Style {
             css.push("font-style: italic".to_string());
         }
 
-        if self.underline().unwrap_or(false) {
-            css.push("text-decoration: underline".to_string());
+        // every line goes in a single declaration, separate ones would override each other
+        let mut decoration_lines: Vec<&str> = Vec::new();
+        if self.underline_style().is_some() || self.underline().unwrap_or(false) {
+            decoration_lines.push("underline");
         }
-
         if self.strike().unwrap_or(false) {
-            css.push("text-decoration: line-through".to_string());
+            decoration_lines.push("line-through");
         }
-
         if self.overline().unwrap_or(false) {
-            css.push("text-decoration: overline".to_string());
+            decoration_lines.push("overline");
+        }
+        if !decoration_lines.is_empty() {
+            css.push(format!(
+                "text-decoration-line: {}",
+                decoration_lines.join(" ")
+            ));
+        }
+
+        if let Some(underline_style) = self.underline_style() {
+            css.push(format!("text-decoration-style: {}", underline_style.css()));
+        }
+
+        if let Some(underline_color) = self.underline_color() {
+            let theme_color = underline_color.get_true_color(Some(&theme), None);
+            css.push(format!("text-decoration-color: {}", theme_color.hex()));
         }
 
         css.join("; ")
@@ -717,8 +907,7 @@ impl Style {
     {
         styles
             .into_iter()
-            .filter(Option::is_some)
-            .next()
+            .find(Option::is_some)
             .unwrap_or(None)
             .cloned()
     }
@@ -726,21 +915,29 @@ impl Style {
 
 impl PartialEq for Style {
     fn eq(&self, other: &Self) -> bool {
-        self.color == other.color
-            && self.background_color == self.background_color
-            && self.set_attributes == other.set_attributes
-            && self.attributes == other.attributes
-            && self.link == other.link
+        let (style, other) = (&self.0, &other.0);
+        Arc::ptr_eq(style, other)
+            || style.color == other.color
+                && style.background_color == other.background_color
+                && style.set_attributes == other.set_attributes
+                && style.attributes == other.attributes
+                && style.underline_style == other.underline_style
+                && style.underline_color == other.underline_color
+                && style.link == other.link
     }
 }
 
+impl Eq for Style {}
+
 impl Hash for Style {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.color.hash(state);
-        self.background_color.hash(state);
-        self.attributes.hash(state);
-        self.set_attributes.hash(state);
-        self.link.hash(state);
+        self.0.color.hash(state);
+        self.0.background_color.hash(state);
+        self.0.attributes.hash(state);
+        self.0.set_attributes.hash(state);
+        self.0.underline_style.hash(state);
+        self.0.underline_color.hash(state);
+        self.0.link.hash(state);
     }
 }
 
@@ -750,24 +947,96 @@ impl Display for Style {
     }
 }
 
-impl Clone for Style {
-    fn clone(&self) -> Self {
+impl StyleData {
+    fn new(
+        color: Option<Color>,
+        background_color: Option<Color>,
+        attributes: &[(StyleAttribute, bool)],
+        link: Option<String>,
+    ) -> Self {
+        let set_attributes: StyleAttribute = attributes
+            .iter()
+            .map(|(flag, _)| flag)
+            .fold(StyleAttribute::default(), |f1, f2| f1 | *f2);
+
+        let attributes: StyleAttribute = if set_attributes.bits > 0 {
+            attributes
+                .iter()
+                .fold(StyleAttribute::default(), |accum, (flag, value)| {
+                    if *value {
+                        accum | *flag
+                    } else {
+                        accum
+                    }
+                })
+        } else {
+            StyleAttribute::default()
+        };
+
         Self {
-            ansi: self.ansi.clone(),
-            style_definition: self.style_definition.clone(),
-            color: self.color.clone(),
-            background_color: self.background_color.clone(),
-            set_attributes: self.set_attributes,
-            attributes: self.attributes,
-            link: self.link.clone(),
-            link_id: self
-                .link
-                .as_ref()
-                .map(|_| uuid::Uuid::new_v4().to_string())
-                .unwrap_or(Default::default()),
-            null: false,
+            style_definition: String::new(),
+            color,
+            background_color,
+            set_attributes,
+            attributes,
+            underline_style: None,
+            underline_color: None,
+            link_id: link.as_ref().map(|_| next_link_id()).unwrap_or_default(),
+            link,
+            null: true,
         }
     }
+
+    /// Update the null flag and the style definition after changing the values of the style
+    fn update(&mut self) {
+        self.null = !(self.set_attributes.bits > 0
+            || self.color.is_some()
+            || self.background_color.is_some()
+            || self.underline_style.is_some()
+            || self.underline_color.is_some()
+            || self.link.is_some());
+        self.load_style_definition();
+    }
+
+    #[inline]
+    fn flag_value(&self, flag: StyleAttribute) -> Option<bool> {
+        if self.set_attributes.enabled(flag) {
+            Some(self.attributes.enabled(flag))
+        } else {
+            None
+        }
+    }
+
+    fn load_style_definition(&mut self) {
+        let mut attributes: Vec<String> = Vec::new();
+        for (flag, name) in StyleAttribute::all_flags().iter().zip(STYLE_NAMES.iter()) {
+            match self.flag_value(*flag) {
+                Some(true) => attributes.push(name.to_string()),
+                Some(false) => attributes.push(format!("not {}", name)),
+                None => {}
+            }
+        }
+        if let Some(underline_style) = self.underline_style {
+            attributes.push(underline_style.name().to_string());
+        }
+        if let Some(color) = &self.color {
+            attributes.push(color.name.clone());
+        }
+        if let Some(color) = &self.background_color {
+            attributes.push(format!("on {}", color.name));
+        }
+        if let Some(color) = &self.underline_color {
+            attributes.push(format!("underline_color {}", color.name));
+        }
+        if let Some(link) = &self.link {
+            attributes.push(format!("link {}", link));
+        }
+        self.style_definition = if attributes.is_empty() {
+            "none".to_string()
+        } else {
+            attributes.join(" ")
+        };
+    }
 }
 
 impl StyleStack {
@@ -777,7 +1046,7 @@ impl StyleStack {
 
     pub fn current(&self) -> &Style {
         // we can safely unwrap, we will check so it is never empty
-        self.0.get(self.0.len() - 1).unwrap()
+        self.0.back().unwrap()
     }
 
     pub fn push(&mut self, new_style: Style) {
@@ -894,6 +1163,97 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_ansi_transition_codes() {
+        let style = |definition| Style::parse(definition).unwrap();
+        let transition =
+            |from, to| style(to).ansi_transition_codes(&style(from), ColorSystem::TrueColor);
+        assert_eq!(transition("bold red", "bold red"), "");
+        assert_eq!(transition("bold red", "bold not italic red"), "");
+        assert_eq!(transition("", "bold"), "1");
+        assert_eq!(transition("bold red", "none"), "0");
+        assert_eq!(transition("bold red", "bold blue"), "34");
+        assert_eq!(transition("bold red on white", "bold italic red"), "3;49");
+        assert_eq!(transition("bold dim", "dim"), "0;2");
+        assert_eq!(transition("bold dim red", "dim red"), "22;2");
+        assert_eq!(transition("underline2 italic", "italic"), "24");
+        assert_eq!(
+            transition("bold italic underline strike red", "blue"),
+            "0;34"
+        );
+        assert_eq!(
+            style("red").ansi_transition_codes(&style("#ff0000"), ColorSystem::Standard),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_underline_style() {
+        let style = Style::parse("undercurl red underline_color yellow").unwrap();
+        assert_eq!(style.underline_style(), Some(UnderlineStyle::Curly));
+        assert_eq!(
+            style.underline_color(),
+            Color::parse("yellow").ok().as_ref()
+        );
+        assert_eq!(style.to_string(), "undercurl red underline_color yellow");
+        assert_eq!(Style::parse(&style.to_string()).unwrap(), style);
+        assert!(Style::parse("underline_color").is_err());
+        assert!(Style::parse("underline_color nope").is_err());
+
+        assert_eq!(style.ansi_codes(ColorSystem::TrueColor), "4:3;31;58;5;3");
+        assert_eq!(style.ansi_codes(ColorSystem::Standard), "4;31");
+        let style = Style::parse("underdotted underline_color #ff0000").unwrap();
+        assert_eq!(style.ansi_codes(ColorSystem::TrueColor), "4:4;58;2;255;0;0");
+        assert_eq!(style.ansi_codes(ColorSystem::EightBit), "4:4;58;5;196");
+        assert_eq!(style.ansi_codes(ColorSystem::Windows), "4");
+
+        let style = |definition| Style::parse(definition).unwrap();
+        let transition =
+            |from, to| style(to).ansi_transition_codes(&style(from), ColorSystem::TrueColor);
+        assert_eq!(transition("undercurl", "undercurl"), "");
+        assert_eq!(
+            transition("bold italic underline", "bold italic undercurl"),
+            "4:3"
+        );
+        assert_eq!(
+            transition("bold italic undercurl", "bold italic underline"),
+            "24;4"
+        );
+        assert_eq!(transition("bold italic undercurl", "bold italic"), "24");
+        assert_eq!(
+            transition("bold italic", "bold italic underline_color #ff0000"),
+            "58;2;255;0;0"
+        );
+        assert_eq!(
+            transition("bold italic underline_color red", "bold italic"),
+            "59"
+        );
+        assert_eq!(
+            style("undercurl").ansi_transition_codes(
+                &style("undercurl underline_color red"),
+                ColorSystem::Standard
+            ),
+            ""
+        );
+
+        assert_eq!(
+            style("underdashed underline_color red").get_html_style(None),
+            "text-decoration-line: underline; text-decoration-style: dashed; text-decoration-color: #800000"
+        );
+        assert_eq!(
+            style("undercurl strike").get_html_style(None),
+            "text-decoration-line: underline line-through; text-decoration-style: wavy"
+        );
+        assert_eq!(
+            style("undercurl underline_color red").combine(Some(&style("not underline"))),
+            style("not underline underline_color red")
+        );
+        assert_eq!(
+            style("undercurl underline_color red").combine(Some(&style("underdotted"))),
+            style("underdotted underline_color red")
+        );
+    }
+
     #[test]
     fn test_eq() {
         let red_builder = StyleBuilder::new()
@@ -906,6 +1266,22 @@ pub mod tests {
         assert_ne!(red_builder.build(), green_builder.build());
     }
 
+    #[test]
+    fn test_eq_background_color() {
+        // the background color was compared with itself
+        assert_ne!(
+            Style::parse("red on white").unwrap(),
+            Style::parse("red on black").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_clone_null() {
+        // clones of the null style were not null
+        assert!(!Style::null().clone().as_bool());
+        assert!(Style::parse("bold").unwrap().clone().as_bool());
+    }
+
     #[test]
     fn test_hash() {
         let style_null = Style::null();
@@ -925,6 +1301,7 @@ pub mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_bool() {
         assert_eq!(Style::null().as_bool(), false);
         assert_eq!(
@@ -1041,7 +1418,7 @@ pub mod tests {
 
     #[test]
     fn test_link_id() {
-        assert_eq!(Style::null().link_id, "");
+        assert_eq!(Style::null().link_id(), "");
         assert_eq!(Style::parse("").expect("null style expected").link_id(), "");
         assert_eq!(Style::parse("red").expect("a red only style").link_id(), "");
         assert!(
@@ -1055,7 +1432,7 @@ pub mod tests {
 
     #[test]
     fn test_get_html_style() {
-        let expected = "color: #7f7fbf; background-color: #800000; font-weight: bold; font-style: italic; text-decoration: underline; text-decoration: line-through; text-decoration: overline";
+        let expected = "color: #7f7fbf; background-color: #800000; font-weight: bold; font-style: italic; text-decoration-line: underline line-through overline";
         let style = Style::new(
             Color::parse("red").ok(),
             Color::parse("blue").ok(),
@@ -1098,7 +1475,11 @@ pub mod tests {
             .with_link("https://foo.bar")
             .build();
         assert_eq!(style.clone(), style.clone());
-        assert_ne!(style.clone().link_id, style.link_id);
+        assert_eq!(style.clone().link_id(), style.link_id());
+        assert_ne!(
+            style.update_link(Some("https://foo.bar")).link_id(),
+            style.link_id()
+        );
     }
 
     #[test]
@@ -1142,6 +1523,32 @@ pub mod tests {
         assert_eq!(red_bold.combine(Some(&italic)), expected)
     }
 
+    #[test]
+    fn test_combine_precedence() {
+        // the colors and link of the combined style replace the ones of the base style
+        let base = Style::parse("bold red on white link https://foo.bar").unwrap();
+        let other = Style::parse("not bold blue on black link https://bar.baz").unwrap();
+        assert_eq!(
+            base.combine(Some(&other)),
+            Style::parse("not bold blue on black link https://bar.baz").unwrap()
+        );
+        assert_eq!(
+            base.combine(Some(&Style::parse("italic").unwrap())),
+            Style::parse("bold italic red on white link https://foo.bar").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_combine_cached() {
+        let base = Style::parse("bold red").unwrap();
+        let link = Style::parse("italic link https://foo.bar").unwrap();
+        let combined = base.combine(Some(&link));
+        assert_eq!(combined, base.combine_uncached(&link));
+        assert_eq!(combined.link_id(), link.link_id());
+        assert!(Arc::ptr_eq(&combined.0, &base.combine(Some(&link)).0));
+        assert_eq!(combined.to_string(), "bold italic red link https://foo.bar");
+    }
+
     #[test]
     fn test_pick_first() {
         let void: Vec<Option<&Style>> = vec![];
@@ -0,0 +1,419 @@
+use std::collections::HashSet;
+
+use lazy_static::lazy_static;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use cells::cell_len;
+use color::Color;
+use console::options::{ConsoleOptions, JustifyMethod, UpdateConsoleOptions};
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, Measurement};
+use padding::PaddingDimensions;
+use segment::Segment;
+use style::{Style, StyleAttribute, StyleBuilder};
+use text::{OverflowMethod, Text};
+
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+const TAB_SIZE: usize = 4;
+const NUMBERS_COLUMN_DEFAULT_PADDING: usize = 2;
+
+lazy_static! {
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+    static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+}
+
+/// Construct a Syntax object to render syntax highlighted code
+pub struct Syntax {
+    code: String,
+    lexer: String,
+    theme: String,
+    line_numbers: bool,
+    start_line: usize,
+    line_range: Option<(usize, usize)>,
+    highlight_lines: HashSet<usize>,
+    code_width: Option<usize>,
+    word_wrap: bool,
+    background_color: Option<Color>,
+    padding: PaddingDimensions,
+}
+
+fn syntect_color(color: syntect::highlighting::Color) -> Color {
+    Color::from_rgb((color.r, color.g, color.b))
+}
+
+/// Convert a syntect style to a Style, the background is taken from the theme
+fn token_style(style: syntect::highlighting::Style) -> Style {
+    let font_style = style.font_style;
+    StyleBuilder::new()
+        .with_color(syntect_color(style.foreground))
+        .with_attribute(StyleAttribute::BOLD, font_style.contains(FontStyle::BOLD))
+        .with_attribute(
+            StyleAttribute::ITALIC,
+            font_style.contains(FontStyle::ITALIC),
+        )
+        .with_attribute(
+            StyleAttribute::UNDERLINE,
+            font_style.contains(FontStyle::UNDERLINE),
+        )
+        .build()
+}
+
+impl Syntax {
+    /// Code to highlight and the name (or file extension) of the language
+    pub fn new(code: &str, lexer: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            lexer: lexer.to_string(),
+            theme: DEFAULT_THEME.to_string(),
+            line_numbers: false,
+            start_line: 1,
+            line_range: None,
+            highlight_lines: HashSet::new(),
+            code_width: None,
+            word_wrap: false,
+            background_color: None,
+            padding: PaddingDimensions::default(),
+        }
+    }
+
+    /// Names of the available themes
+    pub fn themes() -> impl Iterator<Item = &'static str> {
+        THEME_SET.themes.keys().map(String::as_str)
+    }
+
+    /// Theme used to highlight the code, unknown themes fall back to `DEFAULT_THEME`
+    pub fn with_theme(mut self, theme: &str) -> Self {
+        self.theme = theme.to_string();
+        self
+    }
+
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    pub fn with_start_line(mut self, start_line: usize) -> Self {
+        self.start_line = start_line;
+        self
+    }
+
+    /// Only render the lines in the (1 based, inclusive) range
+    pub fn with_line_range(mut self, start: usize, end: usize) -> Self {
+        self.line_range = Some((start, end));
+        self
+    }
+
+    pub fn with_highlight_lines<Lines>(mut self, lines: Lines) -> Self
+    where
+        Lines: IntoIterator<Item = usize>,
+    {
+        self.highlight_lines = lines.into_iter().collect();
+        self
+    }
+
+    pub fn with_code_width(mut self, code_width: usize) -> Self {
+        self.code_width = Some(code_width);
+        self
+    }
+
+    pub fn with_word_wrap(mut self, word_wrap: bool) -> Self {
+        self.word_wrap = word_wrap;
+        self
+    }
+
+    pub fn with_background_color(mut self, color: Color) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    pub fn with_padding(mut self, padding: PaddingDimensions) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    fn theme(&self) -> &'static Theme {
+        THEME_SET
+            .themes
+            .get(&self.theme)
+            .unwrap_or_else(|| &THEME_SET.themes[DEFAULT_THEME])
+    }
+
+    /// Style with the theme foreground and background colors
+    fn base_style(&self) -> Style {
+        let settings = &self.theme().settings;
+        let background_color = self
+            .background_color
+            .clone()
+            .or_else(|| settings.background.map(syntect_color));
+        Style::from_color(settings.foreground.map(syntect_color), background_color)
+    }
+
+    fn code_lines(&self) -> usize {
+        self.code.lines().count()
+    }
+
+    fn numbers_column_width(&self) -> usize {
+        if self.line_numbers {
+            (self.start_line + self.code_lines()).to_string().len() + NUMBERS_COLUMN_DEFAULT_PADDING
+        } else {
+            0
+        }
+    }
+
+    /// Highlight the code and return a Text instance
+    pub fn highlight(&self) -> Text {
+        let syntax = SYNTAX_SET
+            .find_syntax_by_token(&self.lexer)
+            .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, self.theme());
+        let code = self.code.replace('\t', &" ".repeat(TAB_SIZE));
+        let mut text = Text::new("", Some(self.base_style()));
+        for line in LinesWithEndings::from(&code) {
+            match highlighter.highlight_line(line, &SYNTAX_SET) {
+                Ok(ranges) => {
+                    for (style, piece) in ranges {
+                        text.append(piece, Some(token_style(style)));
+                    }
+                }
+                Err(_) => {
+                    text.append(line, None);
+                }
+            }
+        }
+        text
+    }
+
+    fn render_code(&self, console: &Console, options: &ConsoleOptions) -> Vec<Segment> {
+        let base_style = self.base_style();
+        let background_style = Some(base_style.background_style()).filter(Style::as_bool);
+        let numbers_column_width = self.numbers_column_width();
+        let code_width = self.code_width.unwrap_or_else(|| {
+            if self.line_numbers {
+                options.max_width.saturating_sub(numbers_column_width + 1)
+            } else {
+                options.max_width
+            }
+        });
+        let mut render_options = options.update_width(code_width);
+        render_options.update(UpdateConsoleOptions {
+            justify: Some(JustifyMethod::Left),
+            ..Default::default()
+        });
+        let number_style = base_style.combine(Some(
+            &StyleBuilder::new()
+                .with_attribute(StyleAttribute::DIM, true)
+                .build(),
+        ));
+        let highlight_number_style = base_style.combine(Some(
+            &StyleBuilder::new()
+                .with_attribute(StyleAttribute::BOLD, true)
+                .build(),
+        ));
+        let line_pointer = if options.legacy_windows { "> " } else { "❱ " };
+        let (first_line, last_line) = self.line_range.unwrap_or((1, usize::MAX));
+
+        let mut segments = Vec::new();
+        let lines = self.highlight().split("\n", false, false);
+        for (line_number, line) in lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(line_number, _)| first_line <= *line_number && *line_number <= last_line)
+        {
+            let wrapped_lines = if self.word_wrap {
+                console.render_lines(
+                    &line,
+                    Some(&render_options),
+                    background_style.clone(),
+                    Some(true),
+                    None,
+                )
+            } else {
+                let line_segments = line.with_overflow(OverflowMethod::Crop).render("");
+                vec![Segment::adjust_line_length_with_tab_size(
+                    &line_segments,
+                    code_width,
+                    background_style.clone(),
+                    Some(true),
+                    console.tab_size(),
+                )]
+            };
+            let line_number = line_number + self.start_line - 1;
+            for (index, wrapped_line) in wrapped_lines.into_iter().enumerate() {
+                if self.line_numbers {
+                    if index > 0 {
+                        segments.push(Segment::new(
+                            &" ".repeat(numbers_column_width + 1),
+                            background_style.clone(),
+                            false,
+                        ));
+                    } else {
+                        let line_column = format!(
+                            "{:>width$} ",
+                            line_number,
+                            width = numbers_column_width - NUMBERS_COLUMN_DEFAULT_PADDING
+                        );
+                        if self.highlight_lines.contains(&line_number) {
+                            segments.push(Segment::new(
+                                line_pointer,
+                                Some(
+                                    Style::parse("red")
+                                        .unwrap()
+                                        .combine(background_style.as_ref()),
+                                ),
+                                false,
+                            ));
+                            segments.push(Segment::new(
+                                &line_column,
+                                Some(highlight_number_style.clone()),
+                                false,
+                            ));
+                        } else {
+                            segments.push(Segment::new("  ", Some(number_style.clone()), false));
+                            segments.push(Segment::new(
+                                &line_column,
+                                Some(number_style.clone()),
+                                false,
+                            ));
+                        }
+                    }
+                }
+                segments.extend(wrapped_line);
+                segments.push(Segment::line(None));
+            }
+        }
+        segments
+    }
+}
+
+impl Renderable for Syntax {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let (top, right, bottom, left) = self.padding.as_tuple();
+        if !self.padding.as_bool() {
+            return self.render_code(console, options).into();
+        }
+        let background_style = Some(self.base_style().background_style()).filter(Style::as_bool);
+        let width = options.max_width;
+        let code_options = options.update_width(width.saturating_sub(left + right));
+        let code = self.render_code(console, &code_options);
+        let blank_line = Segment::new(&" ".repeat(width), background_style.clone(), false);
+        let left = Segment::new(&" ".repeat(left), background_style.clone(), false);
+        let right = Segment::new(&" ".repeat(right), background_style, false);
+
+        let mut segments = Vec::new();
+        for _ in 0..top {
+            segments.push(blank_line.clone());
+            segments.push(Segment::line(None));
+        }
+        for line in Segment::split_lines(&code) {
+            segments.push(left.clone());
+            segments.extend(line);
+            segments.push(right.clone());
+            segments.push(Segment::line(None));
+        }
+        for _ in 0..bottom {
+            segments.push(blank_line.clone());
+            segments.push(Segment::line(None));
+        }
+        segments.into()
+    }
+}
+
+impl Measure for Syntax {
+    fn measure(&self, _console: &Console, _max_width: usize) -> Measurement {
+        let padding = self.padding.left + self.padding.right;
+        let numbers_column_width = self.numbers_column_width();
+        let line_numbers_width = numbers_column_width + if self.line_numbers { 1 } else { 0 };
+        let code_width = self.code_width.unwrap_or_else(|| {
+            self.code
+                .lines()
+                .map(|line| cell_len(&line.replace('\t', &" ".repeat(TAB_SIZE))))
+                .max()
+                .unwrap_or(0)
+        });
+        Measurement::new(
+            numbers_column_width,
+            line_numbers_width + padding + code_width,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+
+    const CODE: &str = "fn main() {\n    println!(\"Hello, World!\");\n}\n";
+
+    fn render(syntax: &Syntax, width: usize) -> String {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        console
+            .render(syntax, None)
+            .iter()
+            .map(Segment::text)
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight() {
+        let text = Syntax::new(CODE, "rust").highlight();
+        assert_eq!(text.plain(), CODE);
+        assert!(text.spans().len() > 3);
+        let plain = Syntax::new(CODE, "not a language").highlight();
+        assert_eq!(plain.plain(), CODE);
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&Syntax::new(CODE, "rust"), 30),
+            "fn main() {                   \n    println!(\"Hello, World!\");\n}                             \n"
+        );
+    }
+
+    #[test]
+    fn test_render_line_numbers() {
+        let syntax = Syntax::new(CODE, "rust")
+            .with_line_numbers(true)
+            .with_line_range(1, 2)
+            .with_highlight_lines(vec![2])
+            .with_word_wrap(true);
+        assert_eq!(
+            render(&syntax, 24),
+            "  1 fn main() {         \n❱ 2     println!(\"Hello,\n    World!\");           \n"
+        );
+    }
+
+    #[test]
+    fn test_render_padding() {
+        let syntax = Syntax::new("x = 1", "python").with_padding(PaddingDimensions::all(1));
+        assert_eq!(render(&syntax, 8), "        \n x = 1  \n        \n");
+    }
+
+    #[test]
+    fn test_measure() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build();
+        assert_eq!(
+            Measurement::get(&console, &Syntax::new(CODE, "rust"), None),
+            Measurement::new(0, 30)
+        );
+        assert_eq!(
+            Measurement::get(
+                &console,
+                &Syntax::new(CODE, "rust").with_line_numbers(true),
+                None
+            ),
+            Measurement::new(3, 34)
+        );
+    }
+}
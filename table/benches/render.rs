@@ -0,0 +1,53 @@
+use std::io;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use color::ColorSystem;
+use console::ConsoleBuilder;
+use style::Style;
+use table::{Column, Table};
+use text::Text;
+
+const ROWS: usize = 2_000;
+
+fn table() -> Table {
+    let mut table = Table::new()
+        .with_header_style(Style::parse("bold magenta").unwrap())
+        .with_border_style(Style::parse("blue").unwrap())
+        .with_column(Column::new("id").with_style(Style::parse("cyan").unwrap()))
+        .with_column(Column::new("name").with_style(Style::parse("italic").unwrap()))
+        .with_column(Column::new("link"));
+    let link = Style::parse("underline link https://example.org").unwrap();
+    for row in 0..ROWS {
+        table.add_row(vec![
+            Box::new(Text::new(&row.to_string(), None)),
+            Box::new(Text::new(
+                &format!("row number {}", row),
+                Some(Style::parse("green").unwrap()),
+            )),
+            Box::new(Text::new("example", Some(link.clone()))),
+        ]);
+    }
+    table
+}
+
+fn bench_render(c: &mut Criterion) {
+    let table = table();
+    let console = ConsoleBuilder::new()
+        .with_file(Box::new(io::sink()))
+        .with_width(100)
+        .with_color_system(Some(ColorSystem::TrueColor))
+        .build();
+    let mut group = c.benchmark_group("table");
+    group.sample_size(10);
+    group.bench_function("render", |b| {
+        b.iter(|| {
+            let segments = console.render(black_box(&table), None);
+            console.render_segments(&segments)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_render);
+criterion_main!(benches);
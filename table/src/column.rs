@@ -0,0 +1,103 @@
+use console::options::{JustifyMethod, OverflowMethod};
+use style::Style;
+use text::Text;
+
+/// Defines a column within a Table
+#[derive(Clone, Debug)]
+pub struct Column {
+    /// Renderable for the header (typically a string)
+    pub header: Text,
+    /// The style of the header, combined with the table header style
+    pub header_style: Option<Style>,
+    /// The style of the column
+    pub style: Option<Style>,
+    /// How to justify text within the column
+    pub justify: JustifyMethod,
+    /// Overflow method
+    pub overflow: OverflowMethod,
+    /// Width of the column, or `None` to auto calculate width
+    pub width: Option<usize>,
+    /// Minimum width of column, or `None` for no minimum
+    pub min_width: Option<usize>,
+    /// Maximum width of column, or `None` for no maximum
+    pub max_width: Option<usize>,
+    /// Prevent wrapping of text within the column
+    pub no_wrap: bool,
+    /// Ratio of the available width used by the column when the table expands, or `None` for a fixed width
+    pub ratio: Option<usize>,
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self {
+            header: Text::default(),
+            header_style: None,
+            style: None,
+            justify: JustifyMethod::Left,
+            overflow: OverflowMethod::Ellipsis,
+            width: None,
+            min_width: None,
+            max_width: None,
+            no_wrap: false,
+            ratio: None,
+        }
+    }
+}
+
+impl Column {
+    pub fn new(header: &str) -> Self {
+        Self::from_text(Text::new(header, None))
+    }
+
+    pub fn from_text(header: Text) -> Self {
+        Self {
+            header,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_header_style(mut self, header_style: Style) -> Self {
+        self.header_style = Some(header_style);
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_justify(mut self, justify: JustifyMethod) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowMethod) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_min_width(mut self, min_width: usize) -> Self {
+        self.min_width = Some(min_width);
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_no_wrap(mut self, no_wrap: bool) -> Self {
+        self.no_wrap = no_wrap;
+        self
+    }
+
+    pub fn with_ratio(mut self, ratio: usize) -> Self {
+        self.ratio = Some(ratio);
+        self
+    }
+}
@@ -0,0 +1,777 @@
+mod column;
+
+use console::options::{ConsoleOptions, UpdateConsoleOptions};
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use measure::{Measure, MeasuredRenderable, Measurement};
+use padding::{Padding, PaddingDimensions};
+use render_box::{RenderBox, RenderBoxLevel, HEAVY_HEAD};
+use segment::Segment;
+use style::Style;
+use text::{JustifyMethod, Text};
+use utils::iter::loop_last;
+use utils::ratio::{ratio_distribute, ratio_reduce};
+
+pub use self::column::Column;
+
+/// A cell of the table, padded and styled
+struct Cell<'a> {
+    renderable: Padding<&'a dyn MeasuredRenderable>,
+    style: Style,
+}
+
+/// A console renderable to draw a table
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Box<dyn MeasuredRenderable>>>,
+    title: Option<Text>,
+    width: Option<usize>,
+    min_width: Option<usize>,
+    render_box: Option<RenderBox>,
+    safe_box: Option<bool>,
+    padding: PaddingDimensions,
+    pad_edge: bool,
+    expand: bool,
+    show_header: bool,
+    show_edge: bool,
+    show_lines: bool,
+    style: Option<Style>,
+    header_style: Option<Style>,
+    border_style: Option<Style>,
+    title_style: Option<Style>,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            title: None,
+            width: None,
+            min_width: None,
+            render_box: Some(HEAVY_HEAD.clone()),
+            safe_box: None,
+            padding: PaddingDimensions::symmetric(0, 1),
+            pad_edge: true,
+            expand: false,
+            show_header: true,
+            show_edge: true,
+            show_lines: false,
+            style: None,
+            header_style: None,
+            border_style: None,
+            title_style: None,
+        }
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a table with no lines, headers, or padding, useful to lay out grids of renderables
+    pub fn grid() -> Self {
+        Self {
+            render_box: None,
+            padding: PaddingDimensions::default(),
+            show_header: false,
+            show_edge: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_column(mut self, column: Column) -> Self {
+        self.add_column(column);
+        self
+    }
+
+    pub fn with_title(mut self, title: Text) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_min_width(mut self, min_width: usize) -> Self {
+        self.min_width = Some(min_width);
+        self
+    }
+
+    /// Box used to draw the table borders, `None` to draw no borders
+    pub fn with_box(mut self, render_box: Option<RenderBox>) -> Self {
+        self.render_box = render_box;
+        self
+    }
+
+    pub fn with_safe_box(mut self, safe_box: bool) -> Self {
+        self.safe_box = Some(safe_box);
+        self
+    }
+
+    pub fn with_padding(mut self, padding: PaddingDimensions) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_pad_edge(mut self, pad_edge: bool) -> Self {
+        self.pad_edge = pad_edge;
+        self
+    }
+
+    pub fn with_expand(mut self, expand: bool) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    pub fn with_show_header(mut self, show_header: bool) -> Self {
+        self.show_header = show_header;
+        self
+    }
+
+    pub fn with_show_edge(mut self, show_edge: bool) -> Self {
+        self.show_edge = show_edge;
+        self
+    }
+
+    pub fn with_show_lines(mut self, show_lines: bool) -> Self {
+        self.show_lines = show_lines;
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Style of the header row, by default the console `table.header` style
+    pub fn with_header_style(mut self, header_style: Style) -> Self {
+        self.header_style = Some(header_style);
+        self
+    }
+
+    pub fn with_border_style(mut self, border_style: Style) -> Self {
+        self.border_style = Some(border_style);
+        self
+    }
+
+    pub fn with_title_style(mut self, title_style: Style) -> Self {
+        self.title_style = Some(title_style);
+        self
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn add_column(&mut self, column: Column) -> &mut Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Add a row of renderables, columns are added if the row has more cells than the table
+    pub fn add_row(&mut self, cells: Vec<Box<dyn MeasuredRenderable>>) -> &mut Self {
+        while self.columns.len() < cells.len() {
+            self.columns.push(Column::default());
+        }
+        self.rows.push(cells);
+        self
+    }
+
+    /// Number of cells used by borders
+    fn extra_width(&self) -> usize {
+        match self.render_box {
+            Some(_) => self.columns.len().saturating_sub(1) + if self.show_edge { 2 } else { 0 },
+            None => 0,
+        }
+    }
+
+    /// Horizontal padding of a column, edge columns have no outer padding without `pad_edge`
+    fn padding_width(&self, column_index: usize) -> usize {
+        let mut padding_width = self.padding.left + self.padding.right;
+        if !self.pad_edge {
+            if column_index == 0 {
+                padding_width -= self.padding.left;
+            }
+            if column_index + 1 == self.columns.len() {
+                padding_width -= self.padding.right;
+            }
+        }
+        padding_width
+    }
+
+    /// Number of cells in each column, including the header
+    fn cell_count(&self) -> usize {
+        self.rows.len() + self.show_header as usize
+    }
+
+    /// Get a cell of a column, the header is the first cell when it is shown
+    fn get_cell(&self, console: &Console, column_index: usize, row_index: usize) -> Cell<'_> {
+        let column = &self.columns[column_index];
+        let column_style = column.style.clone().unwrap_or_default();
+        let (renderable, style): (&dyn MeasuredRenderable, Style) =
+            match row_index.checked_sub(self.show_header as usize) {
+                None => {
+                    let header_style = self
+                        .header_style
+                        .clone()
+                        .unwrap_or_else(|| console.get_style("table.header"))
+                        .combine(column.header_style.as_ref());
+                    (&column.header, column_style.combine(Some(&header_style)))
+                }
+                Some(row_index) => match self.rows[row_index].get(column_index) {
+                    Some(renderable) => (renderable.as_ref(), column_style),
+                    None => (&"", column_style),
+                },
+            };
+        let mut padding = self.padding;
+        if !self.pad_edge {
+            if column_index == 0 {
+                padding.left = 0;
+            }
+            if column_index + 1 == self.columns.len() {
+                padding.right = 0;
+            }
+            if row_index == 0 {
+                padding.top = 0;
+            }
+            if row_index + 1 == self.cell_count() {
+                padding.bottom = 0;
+            }
+        }
+        Cell {
+            renderable: Padding::new(renderable, padding, None, None),
+            style,
+        }
+    }
+
+    /// Get all the cells of a column, including the header
+    fn get_cells<'a>(
+        &'a self,
+        console: &'a Console,
+        column_index: usize,
+    ) -> impl Iterator<Item = Cell<'a>> + 'a {
+        (0..self.cell_count()).map(move |row_index| self.get_cell(console, column_index, row_index))
+    }
+
+    /// Get the minimum and maximum width of the column
+    fn measure_column(
+        &self,
+        console: &Console,
+        max_width: usize,
+        column_index: usize,
+    ) -> Measurement {
+        let column = &self.columns[column_index];
+        let padding_width = self.padding_width(column_index);
+        if let Some(width) = column.width {
+            return Measurement::new(width + padding_width, width + padding_width)
+                .with_maximum(max_width);
+        }
+        let (minimum, maximum) = self
+            .get_cells(console, column_index)
+            .map(|cell| Measurement::get(console, &cell.renderable, Some(max_width)))
+            .fold(None, |widths, m| match widths {
+                Some((minimum, maximum)) => Some((m.minimum.max(minimum), m.maximum.max(maximum))),
+                None => Some(m.as_tuple()),
+            })
+            .unwrap_or((1, max_width));
+        Measurement::new(minimum, maximum)
+            .with_maximum(max_width)
+            .clamp(
+                column.min_width.map(|width| width + padding_width),
+                column.max_width.map(|width| width + padding_width),
+            )
+    }
+
+    /// Calculate the widths of each column, including padding, not including borders
+    fn calculate_column_widths(&self, console: &Console, max_width: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = (0..self.columns.len())
+            .map(|index| {
+                self.measure_column(console, max_width, index)
+                    .maximum
+                    .max(1)
+            })
+            .collect();
+        let mut table_width: usize = widths.iter().sum();
+
+        if self.expand {
+            let ratios: Vec<i32> = self
+                .columns
+                .iter()
+                .filter_map(|column| column.ratio)
+                .map(|ratio| ratio as i32)
+                .collect();
+            if ratios.iter().any(|ratio| *ratio > 0) {
+                let fixed_widths: Vec<usize> = widths
+                    .iter()
+                    .zip(&self.columns)
+                    .map(|(width, column)| if column.ratio.is_some() { 0 } else { *width })
+                    .collect();
+                let flex_minimums: Vec<i32> = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.ratio.is_some())
+                    .map(|(index, column)| {
+                        (column.width.unwrap_or(1) + self.padding_width(index)) as i32
+                    })
+                    .collect();
+                let flexible_width = max_width.saturating_sub(fixed_widths.iter().sum());
+                let mut flex_widths =
+                    ratio_distribute(flexible_width as i32, &ratios, Some(&flex_minimums))
+                        .into_iter();
+                for (index, column) in self.columns.iter().enumerate() {
+                    if column.ratio.is_some() {
+                        let flex_width = flex_widths.next().unwrap_or(0).max(0) as usize;
+                        widths[index] = fixed_widths[index] + flex_width;
+                    }
+                }
+                table_width = widths.iter().sum();
+            }
+        }
+
+        if table_width > max_width {
+            let wrapable: Vec<bool> = self
+                .columns
+                .iter()
+                .map(|column| column.width.is_none() && !column.no_wrap)
+                .collect();
+            widths = collapse_widths(&widths, &wrapable, max_width);
+            table_width = widths.iter().sum();
+            // last resort, reduce columns evenly
+            if table_width > max_width {
+                let excess_width = (table_width - max_width) as i32;
+                let values: Vec<i32> = widths.iter().map(|width| *width as i32).collect();
+                widths = ratio_reduce(excess_width, &vec![1; widths.len()], &values, &values)
+                    .into_iter()
+                    .map(|width| width.max(0) as usize)
+                    .collect();
+                table_width = widths.iter().sum();
+            }
+            widths = widths
+                .iter()
+                .enumerate()
+                .map(|(index, width)| self.measure_column(console, *width, index).maximum)
+                .collect();
+        }
+
+        let min_width = self
+            .min_width
+            .map(|min_width| min_width.saturating_sub(self.extra_width()));
+        if (table_width < max_width && self.expand)
+            || min_width.is_some_and(|min_width| table_width < min_width)
+        {
+            let max_width = min_width.map_or(max_width, |min_width| min_width.min(max_width));
+            let ratios: Vec<i32> = widths.iter().map(|width| *width as i32).collect();
+            if ratios.iter().sum::<i32>() > 0 && max_width > table_width {
+                let pad_widths = ratio_distribute((max_width - table_width) as i32, &ratios, None);
+                widths = widths
+                    .iter()
+                    .zip(pad_widths)
+                    .map(|(width, pad)| width + pad.max(0) as usize)
+                    .collect();
+            }
+        }
+        widths
+    }
+
+    fn render_title(&self, console: &Console, options: &ConsoleOptions) -> Vec<Segment> {
+        match &self.title {
+            Some(title) => {
+                let style = self
+                    .title_style
+                    .clone()
+                    .unwrap_or_else(|| console.get_style("table.title"));
+                let title = Text::new("", Some(style))
+                    .join(vec![title.clone()])
+                    .with_justify(JustifyMethod::Center);
+                console.render(&title, Some(options))
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Render a row of cells, including the line that follows it
+    fn render_row(
+        &self,
+        console: &Console,
+        options: &ConsoleOptions,
+        layout: &TableLayout,
+        row_index: usize,
+    ) -> Vec<Segment> {
+        let header_row = row_index == 0 && self.show_header;
+        let last = row_index + 1 == self.cell_count();
+        let widths = &layout.widths;
+        let mut cells: Vec<Vec<Vec<Segment>>> = Vec::new();
+        for (column_index, (width, column)) in widths.iter().zip(&self.columns).enumerate() {
+            let cell = self.get_cell(console, column_index, row_index);
+            let mut cell_options = options.update_width(*width);
+            cell_options.update(UpdateConsoleOptions {
+                justify: Some(column.justify),
+                overflow: Some(column.overflow),
+                no_wrap: Some(column.no_wrap),
+                ..Default::default()
+            });
+            cells.push(console.render_lines(
+                &cell.renderable,
+                Some(&cell_options),
+                Some(layout.table_style.combine(Some(&cell.style))).filter(Style::as_bool),
+                None,
+                None,
+            ));
+        }
+        let max_height = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        let cells: Vec<Vec<Vec<Segment>>> = cells
+            .iter()
+            .zip(widths)
+            .map(|(lines, width)| {
+                let lines: Vec<&[Segment]> = lines.iter().map(Vec::as_slice).collect();
+                Segment::set_shape(&lines, *width, Some(max_height), None)
+            })
+            .collect();
+
+        let mut segments = Vec::new();
+        let new_line = Segment::line(None);
+        match &layout.render_box {
+            Some(render_box) => {
+                let (left, right, divider) = if header_row {
+                    (
+                        &render_box.head_left,
+                        &render_box.head_right,
+                        &render_box.head_vertical,
+                    )
+                } else {
+                    (
+                        &render_box.mid_left,
+                        &render_box.mid_right,
+                        &render_box.mid_vertical,
+                    )
+                };
+                for line_number in 0..max_height {
+                    if self.show_edge {
+                        segments.push(layout.border(left));
+                    }
+                    for (last_cell, cell) in loop_last(&cells) {
+                        segments.extend(cell[line_number].iter().cloned());
+                        if !last_cell {
+                            segments.push(layout.border(divider));
+                        }
+                    }
+                    if self.show_edge {
+                        segments.push(layout.border(right));
+                    }
+                    segments.push(new_line.clone());
+                }
+                if header_row {
+                    segments.push(layout.border(&render_box.get_row(
+                        widths,
+                        Some(RenderBoxLevel::Head),
+                        Some(self.show_edge),
+                    )));
+                    segments.push(new_line);
+                } else if self.show_lines && !last {
+                    segments.push(layout.border(&render_box.get_row(
+                        widths,
+                        Some(RenderBoxLevel::Row),
+                        Some(self.show_edge),
+                    )));
+                    segments.push(new_line);
+                }
+            }
+            None => {
+                for line_number in 0..max_height {
+                    for cell in &cells {
+                        segments.extend(cell[line_number].iter().cloned());
+                    }
+                    segments.push(new_line.clone());
+                }
+            }
+        }
+        segments
+    }
+}
+
+/// Reduce widths so that the total is under max_width
+fn collapse_widths(widths: &[usize], wrapable: &[bool], max_width: usize) -> Vec<usize> {
+    let mut widths: Vec<i32> = widths.iter().map(|width| *width as i32).collect();
+    let mut total_width: i32 = widths.iter().sum();
+    let mut excess_width = total_width - max_width as i32;
+    if wrapable.iter().any(|wrap| *wrap) {
+        while total_width > 0 && excess_width > 0 {
+            let wrapable_widths = widths
+                .iter()
+                .zip(wrapable)
+                .filter(|(_, wrap)| **wrap)
+                .map(|(width, _)| *width);
+            let max_column = wrapable_widths.clone().max().unwrap_or(0);
+            let second_max_column = wrapable_widths
+                .filter(|width| *width != max_column)
+                .max()
+                .unwrap_or(0);
+            let column_difference = max_column - second_max_column;
+            let ratios: Vec<i32> = widths
+                .iter()
+                .zip(wrapable)
+                .map(|(width, wrap)| (*wrap && *width == max_column) as i32)
+                .collect();
+            if ratios.iter().all(|ratio| *ratio == 0) || column_difference == 0 {
+                break;
+            }
+            let max_reduce = vec![excess_width.min(column_difference); widths.len()];
+            widths = ratio_reduce(excess_width, &ratios, &max_reduce, &widths);
+            total_width = widths.iter().sum();
+            excess_width = total_width - max_width as i32;
+        }
+    }
+    widths
+        .into_iter()
+        .map(|width| width.max(0) as usize)
+        .collect()
+}
+
+/// Column widths and borders shared by the rows of a table render
+struct TableLayout {
+    widths: Vec<usize>,
+    render_box: Option<RenderBox>,
+    table_style: Style,
+    border_style: Style,
+}
+
+impl TableLayout {
+    fn border(&self, text: &str) -> Segment {
+        Segment::new(text, Some(self.border_style.clone()), false)
+    }
+}
+
+impl Renderable for Table {
+    /// Rows are rendered as the segments are consumed, only the column widths are computed up front
+    fn rich_console<'a>(
+        &'a self,
+        console: &'a Console,
+        options: &'a ConsoleOptions,
+    ) -> RenderResult<'a> {
+        if self.columns.is_empty() {
+            return vec![Segment::line(None)].into();
+        }
+        let max_width = self.width.unwrap_or(options.max_width);
+        let extra_width = self.extra_width();
+        let widths = self.calculate_column_widths(console, max_width.saturating_sub(extra_width));
+        let table_width = widths.iter().sum::<usize>() + extra_width;
+        let render_options = options.update_width(table_width);
+
+        let title = self.render_title(console, &render_options);
+        let table_style = self.style.clone().unwrap_or_default();
+        let layout = TableLayout {
+            border_style: table_style.combine(self.border_style.as_ref()),
+            table_style,
+            render_box: self
+                .render_box
+                .as_ref()
+                .map(|render_box| render_box.substitute(options, self.safe_box)),
+            widths,
+        };
+        let (top, bottom) = match &layout.render_box {
+            Some(render_box) if self.show_edge => (
+                vec![
+                    layout.border(&render_box.get_top(&layout.widths)),
+                    Segment::line(None),
+                ],
+                vec![
+                    layout.border(&render_box.get_bottom(&layout.widths)),
+                    Segment::line(None),
+                ],
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+        let rows = (0..self.cell_count())
+            .flat_map(move |row_index| self.render_row(console, options, &layout, row_index));
+        RenderResult::from_segments(title.into_iter().chain(top).chain(rows).chain(bottom))
+    }
+}
+
+impl Measure for Table {
+    fn measure(&self, console: &Console, max_width: usize) -> Measurement {
+        let max_width = self.width.unwrap_or(max_width);
+        let extra_width = self.extra_width();
+        let max_width: usize = self
+            .calculate_column_widths(console, max_width.saturating_sub(extra_width))
+            .iter()
+            .sum();
+        let measurements: Vec<Measurement> = (0..self.columns.len())
+            .map(|index| self.measure_column(console, max_width, index))
+            .collect();
+        let minimum_width = measurements.iter().map(|m| m.minimum).sum::<usize>() + extra_width;
+        let maximum_width = self
+            .width
+            .unwrap_or_else(|| measurements.iter().map(|m| m.maximum).sum::<usize>() + extra_width);
+        Measurement::new(minimum_width, maximum_width).clamp(self.min_width, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::{AmbiguousWidth, CellWidthConfig, ConsoleBuilder};
+    use render_box::{ASCII, SIMPLE_HEAVY};
+    use std::rc::Rc;
+
+    fn render(table: &Table, width: usize) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(width)
+            .build();
+        console.begin_capture();
+        console.print(table).unwrap();
+        console.end_capture()
+    }
+
+    fn table() -> Table {
+        let mut table = Table::new()
+            .with_box(Some(ASCII.clone()))
+            .with_column(Column::new("foo"))
+            .with_column(Column::new("bar").with_justify(JustifyMethod::Right));
+        table.add_row(vec![Box::new("Hello"), Box::new("World!")]);
+        table.add_row(vec![Box::new("a")]);
+        table
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&table(), 80),
+            "+----------------+\n\
+             | foo   |    bar |\n\
+             |-------+--------|\n\
+             | Hello | World! |\n\
+             | a     |        |\n\
+             +----------------+\n"
+        );
+    }
+
+    #[test]
+    fn test_render_collapsed() {
+        assert_eq!(
+            render(&table(), 12),
+            "+----------+\n\
+             | f… | bar |\n\
+             |----+-----|\n\
+             | H… | Wo… |\n\
+             | a  |     |\n\
+             +----------+\n"
+        );
+    }
+
+    #[test]
+    fn test_render_simple_heavy() {
+        let table = table()
+            .with_box(Some(SIMPLE_HEAVY.clone()))
+            .with_show_edge(false);
+        assert_eq!(
+            render(&table, 80),
+            " foo        bar \n━━━━━━━━━━━━━━━━\n Hello   World! \n a              \n"
+        );
+    }
+
+    #[test]
+    fn test_render_wide_ambiguous() {
+        let mut table = Table::new()
+            .with_box(Some(ASCII.clone()))
+            .with_column(Column::new("○"))
+            .with_column(Column::new("bar"));
+        table.add_row(vec![Box::new("○○"), Box::new("§")]);
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .with_cell_width_config(
+                CellWidthConfig::default().with_ambiguous_width(AmbiguousWidth::Wide),
+            )
+            .build();
+        console.begin_capture();
+        console.print(&table).unwrap();
+        assert_eq!(
+            console.end_capture(),
+            "+------------+\n\
+             | ○   | bar |\n\
+             |------+-----|\n\
+             | ○○ | §  |\n\
+             +------------+\n"
+        );
+    }
+
+    #[test]
+    fn test_render_ratio() {
+        let mut table = Table::grid()
+            .with_expand(true)
+            .with_column(Column::new("").with_width(3))
+            .with_column(Column::new("").with_ratio(1))
+            .with_column(Column::new("").with_justify(JustifyMethod::Right));
+        table.add_row(vec![Box::new("a"), Box::new("b"), Box::new("c")]);
+        assert_eq!(render(&table, 10), "a  b     c\n");
+    }
+
+    #[test]
+    fn test_measure() {
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build();
+        assert_eq!(
+            Measurement::get(&console, &table(), None),
+            Measurement::new(18, 18)
+        );
+    }
+
+    #[test]
+    fn test_collapse_widths() {
+        assert_eq!(collapse_widths(&[10, 5], &[true, true], 12), [7, 5]);
+        assert_eq!(collapse_widths(&[10, 5], &[false, true], 12), [10, 2]);
+        assert_eq!(collapse_widths(&[10, 10], &[true, true], 12), [6, 6]);
+    }
+
+    /// A cell that counts how many times it is rendered
+    struct Counted(Rc<std::cell::Cell<usize>>);
+
+    impl Renderable for Counted {
+        fn rich_console(&self, _console: &Console, _options: &ConsoleOptions) -> RenderResult<'_> {
+            self.0.set(self.0.get() + 1);
+            vec![Segment::new("x", None, false)].into()
+        }
+    }
+
+    impl Measure for Counted {
+        fn measure(&self, _console: &Console, _max_width: usize) -> Measurement {
+            Measurement::new(1, 1)
+        }
+    }
+
+    #[test]
+    fn test_render_rows_lazily() {
+        let renders = Rc::new(std::cell::Cell::new(0));
+        let mut table = Table::new().with_column(Column::new("foo"));
+        for _ in 0..100_000 {
+            table.add_row(vec![Box::new(Counted(renders.clone()))]);
+        }
+        let console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(80)
+            .build();
+        let options = console.options();
+        let mut segments = console.render_iter(&table, &options);
+        let mut next_line = || segments.find(|segment| segment.text() == "\n");
+        // top border, header and header separator
+        for _ in 0..3 {
+            next_line();
+        }
+        assert_eq!(renders.get(), 0);
+        next_line();
+        next_line();
+        assert_eq!(renders.get(), 2);
+    }
+}
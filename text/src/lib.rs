@@ -1,5 +1,13 @@
+mod text;
+
 use style::Style;
 
+pub use self::text::{
+    justify_lines, JustifyMethod, OverflowMethod, Text, DEFAULT_JUSTIFY, DEFAULT_OVERFLOW,
+};
+pub use utils::hyphenate::Hyphenator;
+pub use utils::wrap::BreakMode;
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Span {
     start: usize,
@@ -7,9 +15,6 @@ pub struct Span {
     style: Style,
 }
 
-#[derive(Clone)]
-pub struct Text {}
-
 impl Span {
     pub fn new(start: usize, end: usize, style: Style) -> Self {
         Self { start, end, style }
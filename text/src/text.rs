@@ -0,0 +1,1093 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::iter;
+use std::sync::Arc;
+
+use cells::{cell_len, get_character_cell_size, set_cell_size};
+use segment::{tab_stop_distance, Segment, DEFAULT_TAB_SIZE};
+use style::Style;
+use utils::hyphenate::Hyphenator;
+use utils::iter::loop_last;
+use utils::pick::pick_bool;
+use utils::wrap::{hyphenate_line, BreakMode, HYPHEN, SOFT_HYPHEN};
+
+use crate::Span;
+
+/// How to justify lines of text
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JustifyMethod {
+    /// Keep lines as they are, without padding
+    Default,
+    Full,
+    Left,
+    Center,
+    Right,
+}
+
+/// What to do with text that does not fit in the available width
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowMethod {
+    Crop,
+    Fold,
+    Ellipsis,
+}
+
+pub const DEFAULT_JUSTIFY: JustifyMethod = JustifyMethod::Default;
+pub const DEFAULT_OVERFLOW: OverflowMethod = OverflowMethod::Fold;
+
+/// Text with color / style.
+/// Spans offsets are byte offsets within the plain text, they must always fall in `char` boundaries.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Text {
+    /// Plain text
+    text: String,
+    /// Base style for the whole text
+    style: Style,
+    /// Justify method override
+    justify: Option<JustifyMethod>,
+    /// Overflow method override
+    overflow: Option<OverflowMethod>,
+    /// Disable text wrapping
+    no_wrap: Option<bool>,
+    /// Where lines can be broken when wrapping, at whitespace by default
+    break_mode: Option<BreakMode>,
+    /// Cells between tab stops, the tab size of the console when not set
+    tab_size: Option<usize>,
+    /// Hyphenates words that don't fit in a line, only soft hyphens are used when not set
+    hyphenator: Option<Arc<Hyphenator>>,
+    /// Character to end the text with when rendering
+    end: String,
+    /// Styled regions of the text
+    spans: Vec<Span>,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self::new("", None)
+    }
+}
+
+impl Text {
+    pub fn new(text: &str, style: Option<Style>) -> Self {
+        Self {
+            text: text.to_string(),
+            style: style.unwrap_or_default(),
+            justify: None,
+            overflow: None,
+            no_wrap: None,
+            break_mode: None,
+            tab_size: None,
+            hyphenator: None,
+            end: "\n".to_string(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Construct a Text with a style applied to the whole text as a span
+    pub fn styled(text: &str, style: Style) -> Self {
+        let mut ret = Self::new(text, None);
+        ret.stylize(style, 0, None);
+        ret
+    }
+
+    /// Construct a Text from pieces of text, each of them with an optional style
+    pub fn assemble<'a, Parts>(parts: Parts) -> Self
+    where
+        Parts: IntoIterator<Item = (&'a str, Option<Style>)>,
+    {
+        let mut ret = Self::default();
+        for (text, style) in parts {
+            ret.append(text, style);
+        }
+        ret
+    }
+
+    pub fn with_justify(mut self, justify: JustifyMethod) -> Self {
+        self.justify = Some(justify);
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowMethod) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
+    pub fn with_no_wrap(mut self, no_wrap: bool) -> Self {
+        self.no_wrap = Some(no_wrap);
+        self
+    }
+
+    pub fn with_break_mode(mut self, break_mode: BreakMode) -> Self {
+        self.break_mode = Some(break_mode);
+        self
+    }
+
+    pub fn with_tab_size(mut self, tab_size: usize) -> Self {
+        self.tab_size = Some(tab_size);
+        self
+    }
+
+    /// Break words that don't fit in a line at the hyphenation points found by a hyphenator, like
+    /// `Hyphenator::english()`
+    pub fn with_hyphenator(mut self, hyphenator: Arc<Hyphenator>) -> Self {
+        self.hyphenator = Some(hyphenator);
+        self
+    }
+
+    pub fn with_end(mut self, end: &str) -> Self {
+        self.end = end.to_string();
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Get a copy of this text with the same attributes but a different plain text and no spans
+    pub fn blank_copy(&self, plain: &str) -> Self {
+        Self {
+            text: plain.to_string(),
+            style: self.style.clone(),
+            justify: self.justify,
+            overflow: self.overflow,
+            no_wrap: self.no_wrap,
+            break_mode: self.break_mode,
+            tab_size: self.tab_size,
+            hyphenator: self.hyphenator.clone(),
+            end: self.end.clone(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Get the text as a single string
+    pub fn plain(&self) -> &str {
+        &self.text
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    pub fn justify(&self) -> Option<JustifyMethod> {
+        self.justify
+    }
+
+    pub fn overflow(&self) -> Option<OverflowMethod> {
+        self.overflow
+    }
+
+    pub fn no_wrap(&self) -> Option<bool> {
+        self.no_wrap
+    }
+
+    pub fn break_mode(&self) -> Option<BreakMode> {
+        self.break_mode
+    }
+
+    pub fn tab_size(&self) -> Option<usize> {
+        self.tab_size
+    }
+
+    pub fn hyphenator(&self) -> Option<&Hyphenator> {
+        self.hyphenator.as_deref()
+    }
+
+    pub fn end(&self) -> &str {
+        &self.end
+    }
+
+    /// Length of the plain text in bytes
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Get the number of cells required to render this text
+    pub fn cell_len(&self) -> usize {
+        cell_len(&self.text)
+    }
+
+    /// Add text with an optional style
+    pub fn append(&mut self, text: &str, style: Option<Style>) -> &mut Self {
+        if !text.is_empty() {
+            let offset = self.len();
+            self.text.push_str(text);
+            if let Some(style) = style {
+                if style.as_bool() {
+                    self.spans
+                        .push(Span::new(offset, offset + text.len(), style));
+                }
+            }
+        }
+        self
+    }
+
+    /// Append another Text instance, keeping its styles
+    pub fn append_text(&mut self, text: &Text) -> &mut Self {
+        let offset = self.len();
+        self.text.push_str(&text.text);
+        if text.style.as_bool() && !text.is_empty() {
+            self.spans
+                .push(Span::new(offset, offset + text.len(), text.style.clone()));
+        }
+        self.spans
+            .extend(text.spans.iter().map(|span| span.with_offset(offset)));
+        self
+    }
+
+    /// Apply a style to the text, or a portion of the text
+    pub fn stylize(&mut self, style: Style, start: usize, end: Option<usize>) {
+        let length = self.len();
+        let end = end.unwrap_or(length).min(length);
+        if style.as_bool() && start < end {
+            self.spans.push(Span::new(start, end, style));
+        }
+    }
+
+    /// Get the style of a character at a given (byte) offset
+    pub fn get_style_at_offset(&self, offset: usize) -> Style {
+        self.spans
+            .iter()
+            .map(Span::as_tuple)
+            .filter(|(start, end, _)| *start <= offset && offset < *end)
+            .fold(self.style.clone(), |style, (_, _, span_style)| {
+                style.combine(Some(span_style))
+            })
+    }
+
+    /// Join text together with this text as separator
+    pub fn join<Lines>(&self, lines: Lines) -> Text
+    where
+        Lines: IntoIterator<Item = Text>,
+    {
+        let mut new_text = self.blank_copy("");
+        for (last, line) in loop_last(lines) {
+            new_text.append_text(&line);
+            if !last {
+                new_text.append_text(self);
+            }
+        }
+        new_text
+    }
+
+    /// Divide text in to a number of lines at the given (byte) offsets
+    pub fn divide<Offsets>(&self, offsets: Offsets) -> Vec<Text>
+    where
+        Offsets: IntoIterator<Item = usize>,
+    {
+        let mut offsets = offsets.into_iter().peekable();
+        if offsets.peek().is_none() {
+            return vec![self.clone()];
+        }
+        let divide_offsets: Vec<usize> = iter::once(0)
+            .chain(offsets)
+            .chain(iter::once(self.len()))
+            .collect();
+        let line_ranges: Vec<(usize, usize)> = divide_offsets
+            .windows(2)
+            .map(|range| (range[0], range[1]))
+            .collect();
+        let mut lines: Vec<Text> = line_ranges
+            .iter()
+            .map(|(start, end)| self.blank_copy(&self.text[*start..*end]))
+            .collect();
+
+        for span in &self.spans {
+            let (span_start, span_end, style) = span.as_tuple();
+            let mut line_index =
+                line_ranges.partition_point(|(_, line_end)| *line_end <= span_start);
+            while let Some((line_start, line_end)) = line_ranges.get(line_index) {
+                if *line_start >= span_end {
+                    break;
+                }
+                let start = span_start.max(*line_start) - line_start;
+                let end = span_end.min(*line_end) - line_start;
+                if end > start {
+                    lines[line_index]
+                        .spans
+                        .push(Span::new(start, end, style.clone()));
+                }
+                line_index += 1;
+            }
+        }
+        lines
+    }
+
+    /// Split the text in to lines on a separator
+    pub fn split(&self, separator: &str, include_separator: bool, allow_blank: bool) -> Vec<Text> {
+        let text = self.plain();
+        if separator.is_empty() || !text.contains(separator) {
+            return vec![self.clone()];
+        }
+        let mut lines: Vec<Text> = if include_separator {
+            self.divide(
+                text.match_indices(separator)
+                    .map(|(index, found)| index + found.len()),
+            )
+        } else {
+            self.divide(
+                text.match_indices(separator)
+                    .flat_map(|(index, found)| [index, index + found.len()]),
+            )
+            .into_iter()
+            .filter(|line| line.plain() != separator)
+            .collect()
+        };
+        if !allow_blank && text.ends_with(separator) {
+            lines.pop();
+        }
+        lines
+    }
+
+    /// Remove a number of bytes from the end of the text
+    pub fn right_crop(&mut self, amount: usize) {
+        let max_offset = self.len().saturating_sub(amount);
+        self.text.truncate(max_offset);
+        self.trim_spans();
+    }
+
+    /// Strip whitespace from end of text
+    pub fn rstrip(&mut self) {
+        let whitespace = self.len() - self.text.trim_end().len();
+        self.right_crop(whitespace);
+    }
+
+    /// Remove whitespace beyond a certain width at the end of the text
+    pub fn rstrip_end(&mut self, size: usize) {
+        let text_length = self.cell_len();
+        if text_length > size {
+            let mut excess = text_length - size;
+            let mut crop = 0;
+            for character in self.text.chars().rev() {
+                if excess == 0 || !character.is_whitespace() {
+                    break;
+                }
+                excess = excess.saturating_sub(get_character_cell_size(character));
+                crop += character.len_utf8();
+            }
+            self.right_crop(crop);
+        }
+    }
+
+    /// Truncate text if it is longer than a given width
+    pub fn truncate(&mut self, max_width: usize, overflow: Option<OverflowMethod>, pad: bool) {
+        let overflow = overflow.or(self.overflow).unwrap_or(DEFAULT_OVERFLOW);
+        let length = self.cell_len();
+        if length > max_width {
+            let new_text = match overflow {
+                OverflowMethod::Ellipsis if max_width > 0 => {
+                    format!("{}…", set_cell_size(&self.text, max_width - 1))
+                }
+                _ => set_cell_size(&self.text, max_width),
+            };
+            self.set_plain(&new_text);
+        }
+        if pad && length < max_width {
+            self.text.push_str(&" ".repeat(max_width - length));
+        }
+    }
+
+    /// Pad the left with a given character
+    pub fn pad_left(&mut self, count: usize, character: char) {
+        if count > 0 {
+            let padding: String = iter::repeat_n(character, count).collect();
+            let offset = padding.len();
+            self.text.insert_str(0, &padding);
+            self.spans = self
+                .spans
+                .iter()
+                .map(|span| span.with_offset(offset))
+                .collect();
+        }
+    }
+
+    /// Pad the right with a given character
+    pub fn pad_right(&mut self, count: usize, character: char) {
+        if count > 0 {
+            self.text.extend(iter::repeat_n(character, count));
+        }
+    }
+
+    /// Align text to a given width
+    pub fn align(&mut self, align: JustifyMethod, width: usize, character: char) {
+        self.truncate(width, None, false);
+        let excess_space = width.saturating_sub(self.cell_len());
+        match align {
+            JustifyMethod::Center => {
+                let left = excess_space / 2;
+                self.pad_left(left, character);
+                self.pad_right(excess_space - left, character);
+            }
+            JustifyMethod::Right => self.pad_left(excess_space, character),
+            _ => self.pad_right(excess_space, character),
+        }
+    }
+
+    /// Replace tabs with spaces up to the next tab stop, every `tab_size` cells from the start of
+    /// each line. The tab size of the text is used when not given, or `DEFAULT_TAB_SIZE`.
+    pub fn expand_tabs(&mut self, tab_size: Option<usize>) {
+        if !self.text.contains('\t') {
+            return;
+        }
+        let tab_size = tab_size.or(self.tab_size).unwrap_or(DEFAULT_TAB_SIZE);
+        let mut tabs = Vec::new();
+        let mut cell_position = 0;
+        let mut part_start = 0;
+        for (index, separator) in self.text.match_indices(['\t', '\n']) {
+            if separator == "\n" {
+                cell_position = 0;
+            } else {
+                cell_position += cell_len(&self.text[part_start..index]);
+                let spaces = tab_stop_distance(cell_position, tab_size);
+                cell_position += spaces;
+                tabs.push((index, " ".repeat(spaces)));
+            }
+            part_start = index + 1;
+        }
+        self.replace_chars(&tabs);
+    }
+
+    /// Word wrap the text in to lines of a given width
+    pub fn wrap(
+        &self,
+        width: usize,
+        justify: Option<JustifyMethod>,
+        overflow: Option<OverflowMethod>,
+        no_wrap: Option<bool>,
+    ) -> Vec<Text> {
+        self.wrap_iter(width, justify, overflow, no_wrap).collect()
+    }
+
+    /// Word wrap the text in to lines of a given width, each line of the text is wrapped as the
+    /// lines are consumed
+    pub fn wrap_iter(
+        &self,
+        width: usize,
+        justify: Option<JustifyMethod>,
+        overflow: Option<OverflowMethod>,
+        no_wrap: Option<bool>,
+    ) -> impl Iterator<Item = Text> {
+        let wrap_justify = justify.or(self.justify).unwrap_or(DEFAULT_JUSTIFY);
+        let wrap_overflow = overflow.or(self.overflow).unwrap_or(DEFAULT_OVERFLOW);
+        let no_wrap = pick_bool(&[no_wrap, self.no_wrap]);
+        let break_mode = self.break_mode;
+        let hyphenator = self.hyphenator.clone();
+
+        self.split("\n", false, true)
+            .into_iter()
+            .flat_map(move |line| {
+                let (mut new_lines, hyphens) = if no_wrap {
+                    (vec![line], Vec::new())
+                } else {
+                    let divides = hyphenate_line(
+                        line.plain(),
+                        width,
+                        Some(wrap_overflow == OverflowMethod::Fold),
+                        break_mode,
+                        hyphenator.as_deref(),
+                    );
+                    let hyphens: Vec<bool> = divides.iter().map(|(_, hyphen)| *hyphen).collect();
+                    (
+                        line.divide(divides.into_iter().map(|(offset, _)| offset)),
+                        hyphens,
+                    )
+                };
+                for (index, line) in new_lines.iter_mut().enumerate() {
+                    line.remove_soft_hyphens();
+                    if hyphens.get(index).copied().unwrap_or(false) {
+                        line.push_hyphen();
+                    }
+                    line.rstrip_end(width);
+                }
+                justify_lines(&mut new_lines, width, wrap_justify, wrap_overflow);
+                for line in new_lines.iter_mut() {
+                    line.truncate(width, Some(wrap_overflow), false);
+                }
+                new_lines
+            })
+    }
+
+    /// Render the text as segments, combining the base style with the styles of the spans
+    pub fn render(&self, end: &str) -> Vec<Segment> {
+        let text = self.plain();
+        let mut segments = Vec::new();
+        if self.spans.is_empty() {
+            if !text.is_empty() {
+                segments.push(Segment::new(text, segment_style(&self.style), false));
+            }
+        } else {
+            let styles: Vec<&Style> = iter::once(&self.style)
+                .chain(self.spans.iter().map(|span| &span.style))
+                .collect();
+            // (offset, leaving, style index), style index 0 is the base style
+            let mut events: Vec<(usize, bool, usize)> = vec![(0, false, 0)];
+            for (index, span) in self.spans.iter().enumerate() {
+                events.push((span.start.min(text.len()), false, index + 1));
+                events.push((span.end.min(text.len()), true, index + 1));
+            }
+            events.push((text.len(), true, 0));
+            events.sort_by_key(|(offset, leaving, _)| (*offset, *leaving));
+
+            let mut stack: Vec<usize> = Vec::new();
+            let mut style_cache: HashMap<Vec<usize>, Option<Style>> = HashMap::new();
+            for (current, next) in events.iter().zip(events.iter().skip(1)) {
+                let (offset, leaving, style_index) = *current;
+                if leaving {
+                    if let Some(position) = stack.iter().position(|i| *i == style_index) {
+                        stack.remove(position);
+                    }
+                } else {
+                    stack.push(style_index);
+                }
+                let (next_offset, _, _) = *next;
+                if next_offset > offset {
+                    let mut active = stack.clone();
+                    active.sort_unstable();
+                    let style = style_cache
+                        .entry(active)
+                        .or_insert_with_key(|active| {
+                            let style = active.iter().fold(Style::null(), |style, index| {
+                                style.combine(Some(styles[*index]))
+                            });
+                            segment_style(&style)
+                        })
+                        .clone();
+                    segments.push(Segment::new(&text[offset..next_offset], style, false));
+                }
+            }
+        }
+        if !end.is_empty() {
+            segments.push(Segment::new(end, None, false));
+        }
+        segments
+    }
+
+    /// Remove the soft hyphens of the text, keeping the styles of the other characters
+    fn remove_soft_hyphens(&mut self) {
+        let soft_hyphens: Vec<(usize, String)> = self
+            .text
+            .match_indices(SOFT_HYPHEN)
+            .map(|(index, _)| (index, String::new()))
+            .collect();
+        self.replace_chars(&soft_hyphens);
+    }
+
+    /// Replace characters at the given (byte) offsets, in order, the spans that cover a character
+    /// cover its replacement
+    fn replace_chars(&mut self, replacements: &[(usize, String)]) {
+        if replacements.is_empty() {
+            return;
+        }
+        let mut text = String::with_capacity(self.text.len());
+        // ends of the replaced characters, and of their replacements in the new text
+        let mut ends: Vec<(usize, usize)> = Vec::with_capacity(replacements.len());
+        let mut last = 0;
+        for (offset, replacement) in replacements {
+            text.push_str(&self.text[last..*offset]);
+            text.push_str(replacement);
+            last = offset
+                + self.text[*offset..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+            ends.push((last, text.len()));
+        }
+        text.push_str(&self.text[last..]);
+        let new_offset = |offset: usize| match ends.partition_point(|(end, _)| *end <= offset) {
+            0 => offset,
+            index => {
+                let (end, new_end) = ends[index - 1];
+                new_end + offset - end
+            }
+        };
+        self.spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                Span::new(
+                    new_offset(span.start),
+                    new_offset(span.end),
+                    span.style.clone(),
+                )
+            })
+            .filter(Span::as_bool)
+            .collect();
+        self.text = text;
+    }
+
+    /// Add the hyphen of a line broken inside a word, styled like the end of the word
+    fn push_hyphen(&mut self) {
+        let end = self.len();
+        self.text.push_str(HYPHEN);
+        for span in self.spans.iter_mut().filter(|span| span.end == end) {
+            span.end = self.text.len();
+        }
+    }
+
+    fn set_plain(&mut self, new_text: &str) {
+        self.text = new_text.to_string();
+        self.trim_spans();
+    }
+
+    /// Remove or modify any spans that are over the end of the text. Spans ending inside a
+    /// character, like the ellipsis added by `truncate`, are extended to the end of it so the
+    /// text can be sliced at their offsets.
+    fn trim_spans(&mut self) {
+        let max_offset = self.len();
+        let text = &self.text;
+        self.spans = self
+            .spans
+            .iter()
+            .filter(|span| span.start < max_offset)
+            .map(|span| {
+                let span = span.right_crop(max_offset);
+                let end = (span.end..max_offset)
+                    .find(|offset| text.is_char_boundary(*offset))
+                    .unwrap_or(max_offset);
+                Span::new(span.start, end, span.style)
+            })
+            .collect();
+    }
+}
+
+fn segment_style(style: &Style) -> Option<Style> {
+    if style.as_bool() {
+        Some(style.clone())
+    } else {
+        None
+    }
+}
+
+/// Justify and overflow text lines to a given width
+pub fn justify_lines(
+    lines: &mut [Text],
+    width: usize,
+    justify: JustifyMethod,
+    overflow: OverflowMethod,
+) {
+    match justify {
+        JustifyMethod::Default => {}
+        JustifyMethod::Left => {
+            for line in lines.iter_mut() {
+                line.truncate(width, Some(overflow), true);
+            }
+        }
+        JustifyMethod::Center => {
+            for line in lines.iter_mut() {
+                line.rstrip();
+                line.truncate(width, Some(overflow), false);
+                let excess = width.saturating_sub(line.cell_len());
+                line.pad_left(excess / 2, ' ');
+                line.pad_right(excess - excess / 2, ' ');
+            }
+        }
+        JustifyMethod::Right => {
+            for line in lines.iter_mut() {
+                line.rstrip();
+                line.truncate(width, Some(overflow), false);
+                let excess = width.saturating_sub(line.cell_len());
+                line.pad_left(excess, ' ');
+            }
+        }
+        JustifyMethod::Full => {
+            let last_index = lines.len().saturating_sub(1);
+            for line in lines.iter_mut().take(last_index) {
+                *line = justify_full(line, width);
+            }
+        }
+    }
+}
+
+/// Spread the words of a line so it fills the whole width
+fn justify_full(line: &Text, width: usize) -> Text {
+    let words = line.split(" ", false, false);
+    let words_size: usize = words.iter().map(Text::cell_len).sum();
+    let mut num_spaces = words.len().saturating_sub(1);
+    let mut spaces = vec![1; num_spaces];
+    let mut index = 0;
+    if !spaces.is_empty() {
+        while words_size + num_spaces < width {
+            let position = spaces.len() - index - 1;
+            spaces[position] += 1;
+            num_spaces += 1;
+            index = (index + 1) % spaces.len();
+        }
+    }
+    let mut tokens: Vec<Text> = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        tokens.push(word.clone());
+        if let Some(space) = spaces.get(index) {
+            let style = word.get_style_at_offset(word.len().saturating_sub(1));
+            let next_style = words
+                .get(index + 1)
+                .map(|next_word| next_word.get_style_at_offset(0))
+                .unwrap_or_else(|| line.style.clone());
+            let space_style = if style == next_style {
+                style
+            } else {
+                line.style.clone()
+            };
+            tokens.push(Text::new(&" ".repeat(*space), Some(space_style)));
+        }
+    }
+    line.blank_copy("").join(tokens)
+}
+
+impl Display for Text {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Self::new(text, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+    use style::{StyleAttribute, StyleBuilder};
+
+    fn bold() -> Style {
+        StyleBuilder::new()
+            .with_attribute(StyleAttribute::BOLD, true)
+            .build()
+    }
+
+    fn red() -> Style {
+        StyleBuilder::new()
+            .with_color(Color::parse("red").unwrap())
+            .build()
+    }
+
+    fn plains(lines: &[Text]) -> Vec<&str> {
+        lines.iter().map(Text::plain).collect()
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(Text::new("foo", None).len(), 3);
+        assert_eq!(Text::new("😽", None).cell_len(), 2);
+        assert!(Text::default().is_empty());
+    }
+
+    #[test]
+    fn test_append() {
+        let mut text = Text::new("foo", None);
+        text.append("bar", Some(bold()));
+        assert_eq!(text.plain(), "foobar");
+        assert_eq!(text.spans(), [Span::new(3, 6, bold())]);
+
+        let mut other = Text::new("baz", Some(red()));
+        other.stylize(bold(), 1, Some(2));
+        text.append_text(&other);
+        assert_eq!(text.plain(), "foobarbaz");
+        assert_eq!(
+            text.spans(),
+            [
+                Span::new(3, 6, bold()),
+                Span::new(6, 9, red()),
+                Span::new(7, 8, bold())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stylize() {
+        let mut text = Text::new("Hello, World!", None);
+        text.stylize(bold(), 7, Some(11));
+        text.stylize(red(), 20, None);
+        text.stylize(Style::null(), 0, None);
+        assert_eq!(text.spans(), [Span::new(7, 11, bold())]);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let text = Text::assemble(vec![("foo", None), ("bar", Some(bold()))]);
+        assert_eq!(text.plain(), "foobar");
+        assert_eq!(text.spans(), [Span::new(3, 6, bold())]);
+    }
+
+    #[test]
+    fn test_get_style_at_offset() {
+        let mut text = Text::new("Hello, World!", Some(red()));
+        text.stylize(bold(), 7, Some(11));
+        assert_eq!(text.get_style_at_offset(0), red());
+        assert_eq!(text.get_style_at_offset(8), red().combine(Some(&bold())));
+    }
+
+    #[test]
+    fn test_join() {
+        let separator = Text::new(", ", Some(red()));
+        let text = separator.join(vec![Text::new("foo", None), Text::styled("bar", bold())]);
+        assert_eq!(text.plain(), "foo, bar");
+        assert_eq!(
+            text.spans(),
+            [Span::new(3, 5, red()), Span::new(5, 8, bold())]
+        );
+    }
+
+    #[test]
+    fn test_divide() {
+        let mut text = Text::new("foo bar baz", None);
+        text.stylize(bold(), 2, Some(9));
+        let lines = text.divide(vec![4, 8]);
+        assert_eq!(plains(&lines), ["foo ", "bar ", "baz"]);
+        assert_eq!(lines[0].spans(), [Span::new(2, 4, bold())]);
+        assert_eq!(lines[1].spans(), [Span::new(0, 4, bold())]);
+        assert_eq!(lines[2].spans(), [Span::new(0, 1, bold())]);
+        assert_eq!(text.divide(vec![]), [text.clone()]);
+    }
+
+    #[test]
+    fn test_split() {
+        let text = Text::new("foo\nbar\n\nbaz\n", None);
+        assert_eq!(
+            plains(&text.split("\n", false, false)),
+            ["foo", "bar", "", "baz"]
+        );
+        assert_eq!(
+            plains(&text.split("\n", false, true)),
+            ["foo", "bar", "", "baz", ""]
+        );
+        assert_eq!(
+            plains(&text.split("\n", true, false)),
+            ["foo\n", "bar\n", "\n", "baz\n"]
+        );
+    }
+
+    #[test]
+    fn test_rstrip() {
+        let mut text = Text::new("foo   ", None);
+        text.stylize(bold(), 2, None);
+        text.rstrip();
+        assert_eq!(text.plain(), "foo");
+        assert_eq!(text.spans(), [Span::new(2, 3, bold())]);
+
+        let mut text = Text::new("foo      ", None);
+        text.rstrip_end(5);
+        assert_eq!(text.plain(), "foo  ");
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut text = Text::new("Hello, World!", None);
+        text.truncate(5, Some(OverflowMethod::Crop), false);
+        assert_eq!(text.plain(), "Hello");
+
+        let mut text = Text::new("Hello, World!", None);
+        text.truncate(6, Some(OverflowMethod::Ellipsis), false);
+        assert_eq!(text.plain(), "Hello…");
+
+        let mut text = Text::new("foo", None);
+        text.truncate(5, None, true);
+        assert_eq!(text.plain(), "foo  ");
+    }
+
+    #[test]
+    fn test_trim_spans_ellipsis() {
+        // the span ends at byte 6, inside the ellipsis that replaces ", World!"
+        let mut text = Text::new("Hello, World!", None);
+        text.stylize(bold(), 0, Some(6));
+        text.truncate(6, Some(OverflowMethod::Ellipsis), false);
+        assert_eq!(text.spans(), [Span::new(0, "Hello…".len(), bold())]);
+        let segments = text.render("");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text(), "Hello…");
+    }
+
+    #[test]
+    fn test_pad() {
+        let mut text = Text::new("foo", None);
+        text.stylize(bold(), 0, None);
+        text.pad_left(2, '-');
+        text.pad_right(1, '.');
+        assert_eq!(text.plain(), "--foo.");
+        assert_eq!(text.spans(), [Span::new(2, 5, bold())]);
+    }
+
+    #[test]
+    fn test_align() {
+        let mut text = Text::new("foo", None);
+        text.align(JustifyMethod::Center, 10, ' ');
+        assert_eq!(text.plain(), "   foo    ");
+
+        let mut text = Text::new("foo", None);
+        text.align(JustifyMethod::Right, 5, '-');
+        assert_eq!(text.plain(), "--foo");
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let mut text = Text::new("a\tbc\t日\td\n\tx", None);
+        text.stylize(bold(), 1, Some(3));
+        text.stylize(red(), 5, None);
+        text.expand_tabs(Some(4));
+        assert_eq!(text.plain(), "a   bc  日  d\n    x");
+        // the spaces of a tab take the styles of the tab
+        assert_eq!(
+            text.spans(),
+            [Span::new(1, 5, bold()), Span::new(8, 20, red())]
+        );
+
+        let mut text = Text::new("a\tb", None);
+        text.expand_tabs(None);
+        assert_eq!(text.plain(), "a       b");
+        let mut text = Text::new("a\tb", None).with_tab_size(2);
+        text.expand_tabs(None);
+        assert_eq!(text.plain(), "a b");
+        text.expand_tabs(Some(4));
+        assert_eq!(text.plain(), "a b");
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = Text::new("foo bar baz", None);
+        assert_eq!(
+            plains(&text.wrap(4, None, None, None)),
+            ["foo ", "bar ", "baz"]
+        );
+        assert_eq!(plains(&text.wrap(7, None, None, None)), ["foo bar", "baz"]);
+        assert_eq!(
+            plains(&text.wrap(7, None, Some(OverflowMethod::Crop), Some(true))),
+            ["foo bar"]
+        );
+
+        let text = Text::new("Where there is a Will there is a Way", None);
+        assert_eq!(
+            plains(&text.wrap(10, None, None, None)),
+            ["Where ", "there is a", "Will there", "is a Way"]
+        );
+
+        let text = Text::new("abcdefghijklmnop", None);
+        assert_eq!(
+            plains(&text.wrap(5, None, None, None)),
+            ["abcde", "fghij", "klmno", "p"]
+        );
+        assert_eq!(
+            plains(&text.wrap(5, None, Some(OverflowMethod::Ellipsis), None)),
+            ["abcd…"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_justify() {
+        let text = Text::new("foo bar baz", None);
+        assert_eq!(
+            plains(&text.wrap(8, Some(JustifyMethod::Left), None, None)),
+            ["foo bar ", "baz     "]
+        );
+        assert_eq!(
+            plains(&text.wrap(8, Some(JustifyMethod::Right), None, None)),
+            [" foo bar", "     baz"]
+        );
+        assert_eq!(
+            plains(&text.wrap(8, Some(JustifyMethod::Center), None, None)),
+            ["foo bar ", "  baz   "]
+        );
+        let text = Text::new("a bb ccc dddd", None);
+        assert_eq!(
+            plains(&text.wrap(10, Some(JustifyMethod::Full), None, None)),
+            ["a  bb  ccc", "dddd"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_break_mode() {
+        let text = Text::new("日本語の文章です。", None);
+        assert_eq!(
+            plains(&text.wrap(6, None, None, None)),
+            ["日本語", "の文章", "です。"]
+        );
+        let text = text.with_break_mode(BreakMode::Unicode);
+        assert_eq!(text.break_mode(), Some(BreakMode::Unicode));
+        assert_eq!(
+            plains(&text.wrap(7, None, None, None)),
+            ["日本語", "の文章", "です。"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_soft_hyphens() {
+        let mut text = Text::new("a hy\u{ad}phen\u{ad}ation test", None);
+        text.stylize(bold(), 2, Some(17));
+        let lines = text.wrap(10, None, None, None);
+        assert_eq!(plains(&lines), ["a hyphen-", "ation test"]);
+        assert_eq!(lines[0].spans(), [Span::new(2, 9, bold())]);
+        assert_eq!(lines[1].spans(), [Span::new(0, 5, bold())]);
+        // soft hyphens are hidden when lines are not broken at them
+        assert_eq!(
+            plains(&text.wrap(20, None, None, None)),
+            ["a hyphenation test"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_hyphenator() {
+        let text = Text::new("Narrow dashboards fold words", None);
+        assert_eq!(
+            plains(&text.wrap(12, None, None, None)),
+            ["Narrow ", "dashboards ", "fold words"]
+        );
+        let text = text.with_hyphenator(Hyphenator::english());
+        assert_eq!(
+            plains(&text.wrap(12, None, None, None)),
+            ["Narrow dash-", "boards fold ", "words"]
+        );
+        assert_eq!(
+            plains(&text.wrap(6, Some(JustifyMethod::Right), None, None)),
+            ["Narrow", " dash-", "boards", "  fold", " words"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_custom_hyphenator() {
+        let hyphenator = Arc::new(Hyphenator::new("a1b"));
+        let text = Text::new("ababab", None).with_hyphenator(hyphenator.clone());
+        assert_eq!(text.hyphenator(), Some(hyphenator.as_ref()));
+        assert_eq!(plains(&text.wrap(4, None, None, None)), ["aba-", "bab"]);
+    }
+
+    #[test]
+    fn test_wrap_keeps_spans() {
+        let mut text = Text::new("foo bar", None);
+        text.stylize(bold(), 2, Some(5));
+        let lines = text.wrap(4, None, None, None);
+        assert_eq!(lines[0].spans(), [Span::new(2, 4, bold())]);
+        assert_eq!(lines[1].spans(), [Span::new(0, 1, bold())]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut text = Text::new("foo bar baz", Some(red()));
+        text.stylize(bold(), 4, Some(7));
+        assert_eq!(
+            text.render("\n"),
+            [
+                Segment::new("foo ", Some(red()), false),
+                Segment::new("bar", Some(red().combine(Some(&bold()))), false),
+                Segment::new(" baz", Some(red()), false),
+                Segment::new("\n", None, false),
+            ]
+        );
+        assert_eq!(
+            Text::new("foo", None).render(""),
+            [Segment::new("foo", None, false)]
+        );
+    }
+}
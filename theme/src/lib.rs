@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use style::{Error, Style};
+
+/// Style definitions used by default, indexed by style name
+pub const DEFAULT_STYLES: &[(&str, &str)] = &[
+    ("none", "none"),
+    ("reset", "default on default not bold not dim not italic not underline not blink not blink2 not reverse not conceal not strike"),
+    ("dim", "dim"),
+    ("bright", "not dim"),
+    ("bold", "bold"),
+    ("strong", "bold"),
+    ("code", "reverse bold"),
+    ("italic", "italic"),
+    ("emphasize", "italic"),
+    ("underline", "underline"),
+    ("blink", "blink"),
+    ("blink2", "blink2"),
+    ("reverse", "reverse"),
+    ("strike", "strike"),
+    ("black", "black"),
+    ("red", "red"),
+    ("green", "green"),
+    ("yellow", "yellow"),
+    ("magenta", "magenta"),
+    ("cyan", "cyan"),
+    ("white", "white"),
+    ("inspect.attr", "yellow italic"),
+    ("inspect.attr.dunder", "yellow italic dim"),
+    ("inspect.callable", "bold red"),
+    ("inspect.def", "italic bright_cyan"),
+    ("inspect.error", "bold red"),
+    ("inspect.equals", "none"),
+    ("inspect.help", "cyan"),
+    ("inspect.doc", "dim"),
+    ("inspect.value.border", "green"),
+    ("live.ellipsis", "bold red"),
+    ("layout.tree.row", "not dim red"),
+    ("layout.tree.column", "not dim blue"),
+    ("logging.keyword", "bold yellow"),
+    ("logging.level.notset", "dim"),
+    ("logging.level.trace", "dim"),
+    ("logging.level.debug", "green"),
+    ("logging.level.info", "blue"),
+    ("logging.level.warning", "red"),
+    ("logging.level.warn", "red"),
+    ("logging.level.error", "bold red"),
+    ("logging.level.critical", "bold reverse red"),
+    ("log.level", "none"),
+    ("log.time", "cyan dim"),
+    ("log.message", "none"),
+    ("log.path", "dim"),
+    ("log.span", "bold blue"),
+    ("log.field", "italic"),
+    ("log.duration", "magenta"),
+    ("repr.ellipsis", "yellow"),
+    ("repr.indent", "green dim"),
+    ("repr.error", "bold red"),
+    ("repr.str", "not bold not italic green"),
+    ("repr.brace", "bold"),
+    ("repr.comma", "bold"),
+    ("repr.ipv4", "bold bright_green"),
+    ("repr.ipv6", "bold bright_green"),
+    ("repr.eui48", "bold bright_green"),
+    ("repr.eui64", "bold bright_green"),
+    ("repr.tag_start", "bold"),
+    ("repr.tag_name", "bold bright_magenta"),
+    ("repr.tag_contents", "default"),
+    ("repr.tag_end", "bold"),
+    ("repr.attrib_name", "not italic yellow"),
+    ("repr.attrib_equal", "bold"),
+    ("repr.attrib_value", "not italic magenta"),
+    ("repr.number", "bold not italic cyan"),
+    ("repr.number_complex", "bold not italic cyan"),
+    ("repr.bool_true", "italic bright_green"),
+    ("repr.bool_false", "italic bright_red"),
+    ("repr.none", "italic magenta"),
+    ("repr.url", "not bold not italic underline bright_blue"),
+    ("repr.uuid", "not bold bright_yellow"),
+    ("repr.call", "bold magenta"),
+    ("repr.path", "magenta"),
+    ("repr.filename", "bright_magenta"),
+    ("rule.line", "bright_green"),
+    ("rule.text", "none"),
+    ("json.brace", "bold"),
+    ("json.bool_true", "italic bright_green"),
+    ("json.bool_false", "italic bright_red"),
+    ("json.null", "italic magenta"),
+    ("json.number", "bold not italic cyan"),
+    ("json.str", "not bold not italic green"),
+    ("json.key", "bold blue"),
+    ("prompt", "none"),
+    ("prompt.choices", "bold magenta"),
+    ("prompt.default", "bold cyan"),
+    ("prompt.invalid", "red"),
+    ("prompt.invalid.choice", "red"),
+    ("pretty", "none"),
+    ("scope.border", "blue"),
+    ("scope.key", "yellow italic"),
+    ("scope.key.special", "yellow italic dim"),
+    ("scope.equals", "red"),
+    ("table.header", "bold"),
+    ("table.footer", "bold"),
+    ("table.cell", "none"),
+    ("table.title", "italic"),
+    ("table.caption", "italic dim"),
+    ("traceback.error", "red italic"),
+    ("traceback.border.syntax_error", "bright_red"),
+    ("traceback.border", "red"),
+    ("traceback.text", "none"),
+    ("traceback.title", "bold red"),
+    ("traceback.exc_type", "bold bright_red"),
+    ("traceback.exc_value", "none"),
+    ("traceback.offset", "bold bright_red"),
+    ("bar.back", "grey23"),
+    ("bar.complete", "rgb(249,38,114)"),
+    ("bar.finished", "rgb(114,156,31)"),
+    ("bar.pulse", "rgb(249,38,114)"),
+    ("progress.description", "none"),
+    ("progress.filesize", "green"),
+    ("progress.filesize.total", "green"),
+    ("progress.download", "green"),
+    ("progress.elapsed", "yellow"),
+    ("progress.percentage", "magenta"),
+    ("progress.remaining", "cyan"),
+    ("progress.data.speed", "red"),
+    ("progress.spinner", "green"),
+    ("status.spinner", "green"),
+    ("tree", "none"),
+    ("tree.line", "none"),
+    ("markdown.paragraph", "none"),
+    ("markdown.text", "none"),
+    ("markdown.emph", "italic"),
+    ("markdown.strong", "bold"),
+    ("markdown.code", "bold cyan on black"),
+    ("markdown.code_block", "cyan on black"),
+    ("markdown.block_quote", "magenta"),
+    ("markdown.list", "cyan"),
+    ("markdown.item", "none"),
+    ("markdown.item.bullet", "bold yellow"),
+    ("markdown.item.number", "bold yellow"),
+    ("markdown.hr", "yellow"),
+    ("markdown.h1.border", "none"),
+    ("markdown.h1", "bold"),
+    ("markdown.h2", "bold underline"),
+    ("markdown.h3", "bold"),
+    ("markdown.h4", "bold dim"),
+    ("markdown.h5", "underline"),
+    ("markdown.h6", "italic"),
+    ("markdown.h7", "italic dim"),
+    ("markdown.link", "bright_blue"),
+    ("markdown.link_url", "blue"),
+    ("markdown.s", "strike"),
+    ("iso8601.date", "blue"),
+    ("iso8601.time", "magenta"),
+    ("iso8601.timezone", "yellow"),
+];
+
+lazy_static! {
+    static ref DEFAULT_THEME_STYLES: HashMap<String, Style> = DEFAULT_STYLES
+        .iter()
+        .map(|(name, definition)| {
+            (
+                name.to_string(),
+                Style::parse(definition).expect("default styles must be valid"),
+            )
+        })
+        .collect();
+}
+
+/// A container for style information, used by the console to look up styles by name
+#[derive(Clone, Debug)]
+pub struct Theme {
+    styles: HashMap<String, Style>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            styles: DEFAULT_THEME_STYLES.clone(),
+        }
+    }
+}
+
+impl Theme {
+    /// Create a theme from a mapping of names to styles, optionally inheriting the default styles
+    pub fn new(styles: HashMap<String, Style>, inherit: Option<bool>) -> Self {
+        let mut theme = if inherit.unwrap_or(true) {
+            Self::default()
+        } else {
+            Self {
+                styles: HashMap::new(),
+            }
+        };
+        theme.styles.extend(styles);
+        theme
+    }
+
+    /// Create a theme from a mapping of names to style definitions
+    pub fn from_definitions<'a, Definitions>(
+        definitions: Definitions,
+        inherit: Option<bool>,
+    ) -> Result<Self, Error>
+    where
+        Definitions: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let styles = definitions
+            .into_iter()
+            .map(|(name, definition)| Ok((name.to_string(), Style::parse(definition)?)))
+            .collect::<Result<HashMap<String, Style>, Error>>()?;
+        Ok(Self::new(styles, inherit))
+    }
+
+    /// Get a style by its name
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    pub fn styles(&self) -> &HashMap<String, Style> {
+        &self.styles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_styles() {
+        let theme = Theme::default();
+        assert_eq!(theme.styles().len(), DEFAULT_STYLES.len());
+        assert_eq!(
+            theme.get("markdown.strong"),
+            Some(&Style::parse("bold").unwrap())
+        );
+        assert!(theme.get("foo.bar").is_none());
+    }
+
+    #[test]
+    fn test_from_definitions() {
+        let theme = Theme::from_definitions(vec![("warning", "magenta")], Some(false)).unwrap();
+        assert_eq!(theme.styles().len(), 1);
+        assert_eq!(
+            theme.get("warning"),
+            Some(&Style::parse("magenta").unwrap())
+        );
+        assert!(Theme::from_definitions(vec![("warning", "on")], None).is_err());
+    }
+}
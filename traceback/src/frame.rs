@@ -0,0 +1,126 @@
+/// A frame of a captured backtrace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub function: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Frame {
+    pub fn new(function: &str) -> Self {
+        Self {
+            function: function.to_string(),
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    pub fn with_location(mut self, file: &str, line: usize, column: Option<usize>) -> Self {
+        self.file = Some(file.to_string());
+        self.line = Some(line);
+        self.column = column;
+        self
+    }
+
+    /// Name of the crate the function belongs to
+    pub fn crate_name(&self) -> &str {
+        let function = self.function.trim_start_matches('<');
+        function.split("::").next().unwrap_or(function)
+    }
+
+    /// Frames from the standard library and the runtime that calls into user code
+    pub fn is_std(&self) -> bool {
+        const STD_CRATES: &[&str] = &["std", "core", "alloc", "test", "backtrace"];
+        const RUNTIME_PREFIXES: &[&str] = &["__rust", "_start", "__libc_start", "<F as "];
+        if STD_CRATES.contains(&self.crate_name())
+            || RUNTIME_PREFIXES
+                .iter()
+                .any(|prefix| self.function.starts_with(prefix))
+            || self.function == "main"
+            || self.function == "<unknown>"
+            || self.function.contains(" as core::ops::function::")
+        {
+            return true;
+        }
+        self.file
+            .as_deref()
+            .is_some_and(|file| file.starts_with("/rustc/") || file.contains("/library/std/src/"))
+    }
+
+    /// Frames of the panic machinery, everything before them belongs to the panic hook
+    fn is_panic_entry(&self) -> bool {
+        const PANIC_PREFIXES: &[&str] = &[
+            "std::panicking::",
+            "core::panicking::",
+            "rust_begin_unwind",
+            "__rustc::rust_begin_unwind",
+        ];
+        PANIC_PREFIXES
+            .iter()
+            .any(|prefix| self.function.starts_with(prefix))
+    }
+}
+
+/// Remove the hash suffix from a demangled symbol
+fn strip_hash(function: &str) -> &str {
+    match function.rfind("::h") {
+        Some(index)
+            if function.len() - index == 19
+                && function[index + 3..].chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            &function[..index]
+        }
+        _ => function,
+    }
+}
+
+/// Parse the location of a frame as printed by `std::backtrace::Backtrace`: `at <file>:<line>:<column>`
+fn parse_location(location: &str) -> Option<(&str, usize, Option<usize>)> {
+    let mut parts = location.rsplitn(3, ':');
+    let last = parts.next()?;
+    let middle = parts.next()?;
+    match (parts.next(), middle.parse().ok(), last.parse().ok()) {
+        (Some(file), Some(line), column) => Some((file, line, column)),
+        _ => Some((middle, last.parse().ok()?, None)),
+    }
+}
+
+/// Parse the frames of a formatted `std::backtrace::Backtrace`, from the most recent call
+pub fn parse_backtrace(backtrace: &str) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::new();
+    for line in backtrace.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(location) = line.strip_prefix("at ") {
+            if let (Some(frame), Some((file, line, column))) =
+                (frames.last_mut(), parse_location(location))
+            {
+                *frame = frame.clone().with_location(file, line, column);
+            }
+            continue;
+        }
+        let function = match line.split_once(": ") {
+            Some((index, function)) if index.chars().all(|c| c.is_ascii_digit()) => function,
+            _ => line,
+        };
+        frames.push(Frame::new(strip_hash(function)));
+    }
+    frames
+}
+
+/// Drop the frames of the panic hook and machinery, which come before the code that panicked
+pub fn skip_panic_frames(frames: Vec<Frame>) -> Vec<Frame> {
+    match frames.iter().position(Frame::is_panic_entry) {
+        Some(start) => {
+            let length = frames[start..]
+                .iter()
+                .take_while(|frame| frame.is_panic_entry())
+                .count();
+            frames.into_iter().skip(start + length).collect()
+        }
+        None => frames,
+    }
+}
@@ -0,0 +1,440 @@
+mod frame;
+mod report;
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::panic::{self, PanicHookInfo};
+use std::path::Path;
+use std::thread;
+
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::{Console, ConsoleBuilder};
+use measure::{Measure, Measurement};
+use panel::Panel;
+use segment::Segment;
+use syntax::Syntax;
+use text::Text;
+
+pub use self::frame::{parse_backtrace, Frame};
+pub use self::report::ErrorReport;
+
+/// Settings to render tracebacks
+#[derive(Clone, Debug)]
+pub struct TracebackSettings {
+    width: Option<usize>,
+    extra_lines: usize,
+    code_theme: String,
+    word_wrap: bool,
+    max_frames: usize,
+    show_std: bool,
+    suppress: Vec<String>,
+}
+
+impl Default for TracebackSettings {
+    fn default() -> Self {
+        Self {
+            width: Some(100),
+            extra_lines: 3,
+            code_theme: syntax::DEFAULT_THEME.to_string(),
+            word_wrap: false,
+            max_frames: 100,
+            show_std: false,
+            suppress: Vec::new(),
+        }
+    }
+}
+
+impl TracebackSettings {
+    /// Maximum width of the traceback, `None` to use the whole console width
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Lines of source code shown around the line of each frame
+    pub fn with_extra_lines(mut self, extra_lines: usize) -> Self {
+        self.extra_lines = extra_lines;
+        self
+    }
+
+    pub fn with_code_theme(mut self, code_theme: &str) -> Self {
+        self.code_theme = code_theme.to_string();
+        self
+    }
+
+    pub fn with_word_wrap(mut self, word_wrap: bool) -> Self {
+        self.word_wrap = word_wrap;
+        self
+    }
+
+    /// Maximum number of frames shown, frames in the middle are hidden when there are more
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = max_frames;
+        self
+    }
+
+    /// Show the frames of the standard library and the runtime
+    pub fn with_show_std(mut self, show_std: bool) -> Self {
+        self.show_std = show_std;
+        self
+    }
+
+    /// Hide the frames of the given crates, or from source files whose path contains any of them
+    pub fn with_suppress<'a>(mut self, suppress: impl IntoIterator<Item = &'a str>) -> Self {
+        self.suppress = suppress.into_iter().map(str::to_string).collect();
+        self
+    }
+
+    fn is_suppressed(&self, frame: &Frame) -> bool {
+        (!self.show_std && frame.is_std())
+            || self.suppress.iter().any(|suppress| {
+                frame.crate_name() == suppress
+                    || frame
+                        .file
+                        .as_deref()
+                        .is_some_and(|file| file.contains(suppress.as_str()))
+            })
+    }
+
+    /// Frames to show, and the number of frames hidden after the first half of them
+    fn visible_frames(&self, frames: &[Frame]) -> (Vec<Frame>, usize) {
+        let frames: Vec<Frame> = frames
+            .iter()
+            .filter(|frame| !self.is_suppressed(frame))
+            .cloned()
+            .collect();
+        let max_frames = self.max_frames;
+        if max_frames == 0 || frames.len() <= max_frames {
+            return (frames, 0);
+        }
+        let head = max_frames / 2;
+        let tail = max_frames - head;
+        let hidden = frames.len() - max_frames;
+        let mut visible = frames[..head].to_vec();
+        visible.extend_from_slice(&frames[frames.len() - tail..]);
+        (visible, hidden)
+    }
+}
+
+/// A console renderable of a panic (or any error) with the frames of its backtrace
+#[derive(Clone, Debug)]
+pub struct Traceback {
+    title: String,
+    message: String,
+    /// Frames in call order, the most recent call last
+    frames: Vec<Frame>,
+    settings: TracebackSettings,
+}
+
+impl Traceback {
+    /// Traceback with the frames of a backtrace, which are listed from the most recent call
+    pub fn new(title: &str, message: &str, frames: Vec<Frame>) -> Self {
+        Self {
+            title: title.to_string(),
+            message: message.to_string(),
+            frames: frames.into_iter().rev().collect(),
+            settings: TracebackSettings::default(),
+        }
+    }
+
+    /// Traceback of the running panic, capturing a backtrace
+    pub fn from_panic(info: &PanicHookInfo) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let thread = thread::current();
+        let mut title = format!("thread '{}' panicked", thread.name().unwrap_or("<unnamed>"));
+        if let Some(location) = info.location() {
+            title.push_str(&format!(" at {}", location));
+        }
+        let frames = parse_backtrace(&Backtrace::force_capture().to_string());
+        Self::new(&title, message, frame::skip_panic_frames(frames))
+    }
+
+    pub fn with_settings(mut self, settings: TracebackSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+}
+
+/// Frames rendered inside the traceback panel, followed by the message if there is one
+struct Stack {
+    frames: Vec<Frame>,
+    hidden: usize,
+    settings: TracebackSettings,
+    message: Option<Text>,
+}
+
+impl Stack {
+    fn render_frame(
+        &self,
+        frame: &Frame,
+        console: &Console,
+        options: &ConsoleOptions,
+    ) -> Vec<Segment> {
+        let mut header = Text::new("", None);
+        if let Some(file) = &frame.file {
+            header.append(file, Some(console.get_style("repr.filename")));
+            if let Some(line) = frame.line {
+                header.append(":", None);
+                header.append(&line.to_string(), Some(console.get_style("repr.number")));
+            }
+            header.append(" in ", None);
+        }
+        header.append(&frame.function, Some(console.get_style("repr.call")));
+        let mut segments = console.render(&header, Some(options));
+        let source = frame
+            .file
+            .as_ref()
+            .zip(frame.line)
+            .and_then(|(file, line)| fs::read_to_string(file).ok().map(|code| (file, line, code)));
+        if let Some((file, line, code)) = source {
+            let lexer = Path::new(file)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("txt");
+            let syntax = Syntax::new(&code, lexer)
+                .with_theme(&self.settings.code_theme)
+                .with_line_numbers(true)
+                .with_line_range(
+                    line.saturating_sub(self.settings.extra_lines).max(1),
+                    line + self.settings.extra_lines,
+                )
+                .with_highlight_lines(vec![line])
+                .with_word_wrap(self.settings.word_wrap);
+            segments.extend(console.render(&syntax, Some(options)));
+        }
+        segments
+    }
+}
+
+impl Renderable for Stack {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let head = self.settings.max_frames / 2;
+        let mut segments = Vec::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            if index > 0 {
+                segments.push(Segment::line(None));
+            }
+            if self.hidden > 0 && index == head {
+                let hidden = Text::new(
+                    &format!("... {} frames hidden ...", self.hidden),
+                    Some(console.get_style("traceback.error")),
+                )
+                .with_justify(text::JustifyMethod::Center);
+                segments.extend(console.render(&hidden, Some(options)));
+                segments.push(Segment::line(None));
+            }
+            segments.extend(self.render_frame(frame, console, options));
+        }
+        if let Some(message) = &self.message {
+            if !self.frames.is_empty() {
+                segments.push(Segment::line(None));
+            }
+            segments.extend(console.render(message, Some(options)));
+        }
+        segments.into()
+    }
+}
+
+impl Measure for Stack {
+    fn measure(&self, _console: &Console, max_width: usize) -> Measurement {
+        Measurement::new(max_width, max_width)
+    }
+}
+
+/// Options constrained to the traceback width
+fn traceback_options(settings: &TracebackSettings, options: &ConsoleOptions) -> ConsoleOptions {
+    match settings.width {
+        Some(width) => options.update_width(width.min(options.max_width)),
+        None => options.clone(),
+    }
+}
+
+/// Render the frames that are not suppressed and the message in a panel, nothing if there are
+/// neither frames nor message
+fn render_frames(
+    frames: &[Frame],
+    message: Option<Text>,
+    settings: &TracebackSettings,
+    console: &Console,
+    options: &ConsoleOptions,
+) -> Vec<Segment> {
+    let (frames, hidden) = settings.visible_frames(frames);
+    if frames.is_empty() && message.is_none() {
+        return Vec::new();
+    }
+    let stack = Stack {
+        frames,
+        hidden,
+        settings: settings.clone(),
+        message,
+    };
+    let panel = Panel::new(Box::new(stack))
+        .with_title(Text::new(
+            "Traceback (most recent call last)",
+            Some(console.get_style("traceback.title")),
+        ))
+        .with_border_style(console.get_style("traceback.border"));
+    console.render(&panel, Some(options))
+}
+
+impl Renderable for Traceback {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let options = traceback_options(&self.settings, options);
+        let message = Text::assemble(vec![
+            (
+                self.title.as_str(),
+                Some(console.get_style("traceback.exc_type")),
+            ),
+            (": ", None),
+            (
+                self.message.as_str(),
+                Some(console.get_style("traceback.exc_value")),
+            ),
+        ]);
+        render_frames(
+            &self.frames,
+            Some(message),
+            &self.settings,
+            console,
+            &options,
+        )
+        .into()
+    }
+}
+
+/// Replace the default panic output with a rendered traceback, written to stderr
+pub fn install_panic_hook() {
+    install_panic_hook_with(TracebackSettings::default())
+}
+
+/// Replace the default panic output with a traceback rendered with the given settings
+pub fn install_panic_hook_with(settings: TracebackSettings) {
+    panic::set_hook(Box::new(move |info| {
+        let traceback = Traceback::from_panic(info).with_settings(settings.clone());
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::stderr()))
+            .with_force_terminal(io::stderr().is_terminal())
+            .build();
+        // nothing else can be done if stderr is not writable while panicking
+        let _ = console.print(&traceback);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKTRACE: &str = "   0: std::backtrace::Backtrace::force_capture
+             at /rustc/abc/library/std/src/backtrace.rs:312:13
+   1: traceback::install_panic_hook_with::{{closure}}
+             at ./src/lib.rs:280:21
+   2: std::panicking::rust_panic_with_hook
+             at /rustc/abc/library/std/src/panicking.rs:809:13
+   3: __rustc::rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:667:5
+   4: core::panicking::panic_fmt::h0123456789abcdef
+             at /rustc/abc/library/core/src/panicking.rs:75:14
+   5: app::config::load
+             at ./src/config.rs:10:5
+   6: app::main
+             at ./src/main.rs:3:5
+   7: core::ops::function::FnOnce::call_once
+             at /rustc/abc/library/core/src/ops/function.rs:250:5
+   8: main
+   9: __libc_start_main
+  10: _start
+";
+
+    #[test]
+    fn test_parse_backtrace() {
+        let frames = parse_backtrace(BACKTRACE);
+        assert_eq!(frames.len(), 11);
+        assert_eq!(
+            frames[4],
+            Frame::new("core::panicking::panic_fmt").with_location(
+                "/rustc/abc/library/core/src/panicking.rs",
+                75,
+                Some(14)
+            )
+        );
+        assert_eq!(frames[8], Frame::new("main"));
+        let frames = frame::skip_panic_frames(frames);
+        assert_eq!(frames[0].function, "app::config::load");
+    }
+
+    #[test]
+    fn test_filter_frames() {
+        let frames = frame::skip_panic_frames(parse_backtrace(BACKTRACE));
+        let traceback = Traceback::new("panicked", "boom", frames);
+        let functions = |traceback: &Traceback| {
+            traceback
+                .settings
+                .visible_frames(&traceback.frames)
+                .0
+                .into_iter()
+                .map(|frame| frame.function)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(functions(&traceback), ["app::main", "app::config::load"]);
+        let traceback = traceback.with_settings(
+            TracebackSettings::default()
+                .with_suppress(vec!["config.rs"])
+                .with_show_std(true)
+                .with_max_frames(2),
+        );
+        assert_eq!(traceback.settings.visible_frames(&traceback.frames).1, 3);
+        assert_eq!(functions(&traceback), ["_start", "app::main"]);
+    }
+
+    fn render(traceback: &Traceback) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(io::sink()))
+            .with_width(60)
+            .build();
+        console.begin_capture();
+        console.print(traceback).unwrap();
+        console.end_capture()
+    }
+
+    #[test]
+    fn test_render() {
+        // tests run in the package directory
+        let file = "tests/fixtures/config.rs";
+        let frames = vec![Frame::new("app::config::load").with_location(file, 5, None)];
+        let traceback = Traceback::new("thread 'main' panicked", "boom", frames)
+            .with_settings(TracebackSettings::default().with_extra_lines(1));
+        assert_eq!(
+            render(&traceback),
+            "╭─────────── Traceback (most recent call last) ────────────╮\n\
+             │ tests/fixtures/config.rs:5 in app::config::load          │\n\
+             │   4 pub fn load(path: &str) -> String {                  │\n\
+             │ ❱ 5     let config = fs::read_to_string(path).expect(\"co │\n\
+             │   6     config.trim().to_string()                        │\n\
+             │                                                          │\n\
+             │ thread 'main' panicked: boom                             │\n\
+             ╰──────────────────────────────────────────────────────────╯\n"
+        );
+    }
+
+    #[test]
+    fn test_render_without_frames() {
+        let traceback = Traceback::new("thread 'main' panicked", "boom", Vec::new());
+        assert_eq!(
+            render(&traceback),
+            "╭─────────── Traceback (most recent call last) ────────────╮\n\
+             │ thread 'main' panicked: boom                             │\n\
+             ╰──────────────────────────────────────────────────────────╯\n"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+
+use console::options::ConsoleOptions;
+use console::traits::{RenderResult, Renderable};
+use console::Console;
+use segment::Segment;
+use text::Text;
+
+use crate::{parse_backtrace, render_frames, traceback_options, Frame, TracebackSettings};
+
+/// A console renderable of an error and the chain of errors that caused it
+#[derive(Clone, Debug)]
+pub struct ErrorReport {
+    message: String,
+    causes: Vec<String>,
+    /// Frames in call order, the most recent call last
+    frames: Vec<Frame>,
+    settings: TracebackSettings,
+}
+
+impl ErrorReport {
+    pub fn new(error: &(dyn Error + 'static)) -> Self {
+        let mut causes = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+        Self {
+            message: error.to_string(),
+            causes,
+            frames: Vec::new(),
+            settings: TracebackSettings::default(),
+        }
+    }
+
+    /// Show the frames of a backtrace, usually the one captured when the error was created
+    pub fn with_backtrace(mut self, backtrace: &Backtrace) -> Self {
+        if backtrace.status() == BacktraceStatus::Captured {
+            self.frames = parse_backtrace(&backtrace.to_string())
+                .into_iter()
+                .rev()
+                .collect();
+        }
+        self
+    }
+
+    pub fn with_settings(mut self, settings: TracebackSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn causes(&self) -> &[String] {
+        &self.causes
+    }
+}
+
+/// Render text with a prefix in its first line, and the rest of lines indented to the prefix width
+fn render_indented(
+    prefix: Text,
+    text: Text,
+    console: &Console,
+    options: &ConsoleOptions,
+) -> Vec<Segment> {
+    let width = prefix.cell_len();
+    let lines = console.render_lines(
+        &text,
+        Some(&options.update_width(options.max_width.saturating_sub(width))),
+        None,
+        Some(false),
+        None,
+    );
+    let indent = Segment::new(&" ".repeat(width), None, false);
+    let mut segments = Vec::new();
+    for (index, line) in lines.into_iter().enumerate() {
+        if index == 0 {
+            segments.extend(console.render(&prefix.clone().with_end(""), Some(options)));
+        } else {
+            segments.push(indent.clone());
+        }
+        segments.extend(line);
+        segments.push(Segment::line(None));
+    }
+    segments
+}
+
+impl Renderable for ErrorReport {
+    fn rich_console(&self, console: &Console, options: &ConsoleOptions) -> RenderResult<'_> {
+        let options = traceback_options(&self.settings, options);
+        let mut segments = render_frames(&self.frames, None, &self.settings, console, &options);
+        let value_style = Some(console.get_style("traceback.exc_value"));
+        segments.extend(render_indented(
+            Text::new("Error: ", Some(console.get_style("traceback.exc_type"))),
+            Text::new(&self.message, value_style.clone()),
+            console,
+            &options,
+        ));
+        if self.causes.is_empty() {
+            return segments.into();
+        }
+        segments.push(Segment::line(None));
+        let heading = Text::new("Caused by:", Some(console.get_style("traceback.title")));
+        segments.extend(console.render(&heading, Some(&options)));
+        let number_width = (self.causes.len() - 1).to_string().len();
+        for (index, cause) in self.causes.iter().enumerate() {
+            let prefix = Text::assemble(vec![
+                ("    ", None),
+                (
+                    format!("{:>width$}", index, width = number_width).as_str(),
+                    Some(console.get_style("repr.number")),
+                ),
+                (": ", None),
+            ]);
+            segments.extend(render_indented(
+                prefix,
+                Text::new(cause, value_style.clone()),
+                console,
+                &options,
+            ));
+        }
+        segments.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ConsoleBuilder;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct ChainError {
+        message: &'static str,
+        source: Option<Box<ChainError>>,
+    }
+
+    impl fmt::Display for ChainError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl Error for ChainError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.source
+                .as_deref()
+                .map(|source| source as &(dyn Error + 'static))
+        }
+    }
+
+    fn render(report: &ErrorReport) -> String {
+        let mut console = ConsoleBuilder::new()
+            .with_file(Box::new(std::io::sink()))
+            .with_width(30)
+            .build();
+        console.begin_capture();
+        console.print(report).unwrap();
+        console.end_capture()
+    }
+
+    #[test]
+    fn test_report_chain() {
+        let error = ChainError {
+            message: "failed to load config",
+            source: Some(Box::new(ChainError {
+                message: "failed to read file config.toml from the disk",
+                source: Some(Box::new(ChainError {
+                    message: "not found",
+                    source: None,
+                })),
+            })),
+        };
+        let report = ErrorReport::new(&error);
+        assert_eq!(
+            report.causes(),
+            ["failed to read file config.toml from the disk", "not found"]
+        );
+        assert_eq!(
+            render(&report),
+            "Error: failed to load config\n\nCaused by:\n    0: failed to read file \n       config.toml from the \n       disk\n    1: not found\n"
+        );
+    }
+
+    #[test]
+    fn test_report_without_causes() {
+        let error = ChainError {
+            message: "boom",
+            source: None,
+        };
+        assert_eq!(
+            render(&ErrorReport::new(&error).with_backtrace(&Backtrace::disabled())),
+            "Error: boom\n"
+        );
+    }
+}
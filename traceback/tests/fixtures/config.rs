@@ -0,0 +1,7 @@
+use std::fs;
+
+/// Load the configuration, panicking if it can't be read
+pub fn load(path: &str) -> String {
+    let config = fs::read_to_string(path).expect("config not found");
+    config.trim().to_string()
+}
@@ -0,0 +1,60 @@
+//! Generate the line break class table from the `LineBreak.txt` file vendored in `ucd/`
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("ucd/LineBreak.txt");
+    println!("cargo:rerun-if-changed={}", path.display());
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("can't read {}: {}", path.display(), error));
+
+    let mut ranges: Vec<(u32, u32, &str)> = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split(';').map(str::trim);
+        let codepoints = fields.next().unwrap();
+        let class = fields.next().expect("a line break class");
+        let mut bounds = codepoints
+            .split("..")
+            .map(|codepoint| u32::from_str_radix(codepoint, 16).expect("a hex code point"));
+        let start = bounds.next().unwrap();
+        let end = bounds.next().unwrap_or(start);
+        match ranges.last_mut() {
+            Some((_, last_end, last_class)) if *last_end + 1 == start && *last_class == class => {
+                *last_end = end
+            }
+            _ => ranges.push((start, end, class)),
+        }
+    }
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "/// Line break class of the code points, the ones not listed are `XX`"
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "static LINE_BREAK: [(u32, u32, LineBreakClass); {}] = [",
+        ranges.len()
+    )
+    .unwrap();
+    for (start, end, class) in ranges {
+        writeln!(
+            output,
+            "    ({}, {}, LineBreakClass::{}),",
+            start, end, class
+        )
+        .unwrap();
+    }
+    writeln!(output, "];").unwrap();
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("line_break.rs");
+    fs::write(out_path, output).expect("write the generated table");
+}
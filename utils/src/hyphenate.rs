@@ -0,0 +1,178 @@
+//! Hyphenation of words with Knuth-Liang patterns
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref ENGLISH: Arc<Hyphenator> = Arc::new(
+        Hyphenator::new(include_str!("../hyphenation/hyph-en-us.txt")).with_min_lengths(2, 3)
+    );
+}
+
+/// Finds where words can be hyphenated with a set of Knuth-Liang patterns, like TeX does
+#[derive(Clone, PartialEq, Eq)]
+pub struct Hyphenator {
+    /// Hyphenation values of each pattern, one more than the characters of the pattern
+    patterns: HashMap<String, Vec<u8>>,
+    /// Characters of the longest pattern
+    max_pattern_len: usize,
+    /// Characters to keep before the first hyphenation point of a word
+    left_min: usize,
+    /// Characters to keep after the last hyphenation point of a word
+    right_min: usize,
+}
+
+impl Hyphenator {
+    /// Build a hyphenator from whitespace separated patterns in the TeX format, like `.ach4` or
+    /// `4a2bil`. Text after a `%` is a comment.
+    pub fn new(patterns: &str) -> Self {
+        let mut parsed = HashMap::new();
+        let mut max_pattern_len = 0;
+        for line in patterns.lines() {
+            let line = line.split('%').next().unwrap();
+            for pattern in line.split_whitespace() {
+                let mut letters = String::new();
+                let mut values = vec![0];
+                for char in pattern.chars() {
+                    match char.to_digit(10) {
+                        Some(value) => *values.last_mut().unwrap() = value as u8,
+                        None => {
+                            letters.push(char);
+                            values.push(0);
+                        }
+                    }
+                }
+                max_pattern_len = max_pattern_len.max(values.len() - 1);
+                parsed.insert(letters, values);
+            }
+        }
+        Self {
+            patterns: parsed,
+            max_pattern_len,
+            left_min: 1,
+            right_min: 1,
+        }
+    }
+
+    /// Hyphenator with the American English patterns of TeX
+    pub fn english() -> Arc<Hyphenator> {
+        ENGLISH.clone()
+    }
+
+    /// Characters to keep at least before and after a hyphen
+    pub fn with_min_lengths(mut self, left_min: usize, right_min: usize) -> Self {
+        self.left_min = left_min.max(1);
+        self.right_min = right_min.max(1);
+        self
+    }
+
+    /// Byte offsets a word can be hyphenated at, the word should only contain letters
+    pub fn hyphenation_points(&self, word: &str) -> Vec<usize> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() < self.left_min + self.right_min {
+            return Vec::new();
+        }
+        let dotted: Vec<char> = Some('.')
+            .into_iter()
+            .chain(
+                chars
+                    .iter()
+                    .map(|char| char.to_lowercase().next().unwrap_or(*char)),
+            )
+            .chain(Some('.'))
+            .collect();
+        // values between the characters of the dotted word, the value at `i` is before `dotted[i]`
+        let mut levels = vec![0; dotted.len() + 1];
+        let mut key = String::new();
+        for start in 0..dotted.len() {
+            key.clear();
+            for char in dotted[start..].iter().take(self.max_pattern_len) {
+                key.push(*char);
+                if let Some(values) = self.patterns.get(&key) {
+                    for (index, value) in values.iter().enumerate() {
+                        let level = &mut levels[start + index];
+                        *level = (*level).max(*value);
+                    }
+                }
+            }
+        }
+
+        let mut points = Vec::new();
+        let mut offset = 0;
+        for (index, char) in chars.iter().enumerate() {
+            // a break before the character at `index` is the value before `dotted[index + 1]`
+            if index >= self.left_min
+                && index <= chars.len() - self.right_min
+                && levels[index + 1] % 2 == 1
+            {
+                points.push(offset);
+            }
+            offset += char.len_utf8();
+        }
+        points
+    }
+
+    /// Split a word in to its syllables
+    pub fn syllables<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let mut start = 0;
+        let mut syllables = Vec::new();
+        for end in self
+            .hyphenation_points(word)
+            .into_iter()
+            .chain(Some(word.len()))
+        {
+            syllables.push(&word[start..end]);
+            start = end;
+        }
+        syllables
+    }
+}
+
+impl Debug for Hyphenator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hyphenator")
+            .field("patterns", &self.patterns.len())
+            .field("left_min", &self.left_min)
+            .field("right_min", &self.right_min)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyphenation_points() {
+        let hyphenator = Hyphenator::new("a1b % comment\n");
+        assert_eq!(hyphenator.hyphenation_points("ababab"), [1, 3, 5]);
+        assert_eq!(hyphenator.hyphenation_points("ABAB"), [1, 3]);
+        assert_eq!(
+            hyphenator
+                .clone()
+                .with_min_lengths(2, 2)
+                .hyphenation_points("ababab"),
+            [3]
+        );
+        // even values inhibit the breaks of lower odd values
+        let hyphenator = Hyphenator::new("a1b 2bab");
+        assert_eq!(hyphenator.hyphenation_points("ababab"), [5]);
+    }
+
+    #[test]
+    fn test_english() {
+        let english = Hyphenator::english();
+        let hyphenate = |word| english.syllables(word).join("-");
+        assert_eq!(hyphenate("hyphenation"), "hy-phen-ation");
+        assert_eq!(hyphenate("extensive"), "ex-ten-sive");
+        assert_eq!(hyphenate("Probability"), "Prob-a-bil-ity");
+        assert_eq!(hyphenate("recognize"), "rec-og-nize");
+        assert_eq!(hyphenate("dashboards"), "dash-boards");
+        assert_eq!(hyphenate("hello"), "hello");
+        assert_eq!(hyphenate("a"), "a");
+        assert_eq!(hyphenate(""), "");
+    }
+}
@@ -59,6 +59,7 @@ where
 mod tests {
     use super::*;
 
+    #[allow(clippy::redundant_static_lifetimes)]
     const TEST_ITERABLE: [&'static str; 4] = ["a", "b", "c", "d"];
 
     #[test]
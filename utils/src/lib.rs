@@ -1,4 +1,6 @@
+pub mod hyphenate;
 pub mod iter;
+pub mod line_break;
 pub mod pick;
 pub mod ratio;
 pub mod wrap;
@@ -0,0 +1,285 @@
+//! Line break opportunities of the Unicode line breaking algorithm, UAX #14
+
+use std::cmp::Ordering;
+
+/// Line break classes, named like in the Unicode standard
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineBreakClass {
+    /// Mandatory break
+    BK,
+    /// Carriage return
+    CR,
+    /// Line feed
+    LF,
+    /// Next line
+    NL,
+    /// Space
+    SP,
+    /// Zero width space
+    ZW,
+    /// Zero width joiner
+    ZWJ,
+    /// Combining mark
+    CM,
+    /// Word joiner
+    WJ,
+    /// Non-breaking ("glue")
+    GL,
+    /// Break after
+    BA,
+    /// Hyphen
+    HY,
+    /// Break before
+    BB,
+    /// Break opportunity before and after
+    B2,
+    /// Contingent break opportunity
+    CB,
+    /// Close punctuation
+    CL,
+    /// Close parenthesis
+    CP,
+    /// Exclamation / interrogation
+    EX,
+    /// Inseparable
+    IN,
+    /// Nonstarter
+    NS,
+    /// Open punctuation
+    OP,
+    /// Quotation
+    QU,
+    /// Infix numeric separator
+    IS,
+    /// Numeric
+    NU,
+    /// Postfix numeric
+    PO,
+    /// Prefix numeric
+    PR,
+    /// Symbols allowing break after
+    SY,
+    /// Ambiguous (alphabetic or ideographic)
+    AI,
+    /// Alphabetic
+    AL,
+    /// Conditional Japanese starter
+    CJ,
+    /// Emoji base
+    EB,
+    /// Emoji modifier
+    EM,
+    /// Hangul LV syllable
+    H2,
+    /// Hangul LVT syllable
+    H3,
+    /// Hebrew letter
+    HL,
+    /// Ideographic
+    ID,
+    /// Hangul L jamo
+    JL,
+    /// Hangul V jamo
+    JV,
+    /// Hangul T jamo
+    JT,
+    /// Regional indicator
+    RI,
+    /// Complex context dependent (South East Asian)
+    SA,
+    /// Surrogate
+    SG,
+    /// Unknown
+    XX,
+}
+
+include!(concat!(env!("OUT_DIR"), "/line_break.rs"));
+
+/// Get the line break class of a character
+pub fn line_break_class(char: char) -> LineBreakClass {
+    let codepoint = char as u32;
+    LINE_BREAK
+        .binary_search_by(|(start, end, _)| {
+            if *end < codepoint {
+                Ordering::Less
+            } else if *start > codepoint {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .map_or(LineBreakClass::XX, |index| LINE_BREAK[index].2)
+}
+
+/// Classes resolved as in LB1, South East Asian scripts are not split in words so they are
+/// treated as alphabetic
+fn resolved_class(char: char) -> LineBreakClass {
+    use LineBreakClass::*;
+    match line_break_class(char) {
+        AI | SG | XX | SA => AL,
+        CJ => NS,
+        class => class,
+    }
+}
+
+/// Fullwidth, wide and halfwidth punctuation, which LB30 doesn't keep together with letters
+fn is_east_asian_punctuation(char: char) -> bool {
+    matches!(char, '\u{3000}'..='\u{303f}' | '\u{fe30}'..='\u{fe6f}' | '\u{ff00}'..='\u{ffef}')
+}
+
+/// Break opportunities of the text, as the byte offsets a new line can start at. Mandatory breaks
+/// are included, the start and the end of the text are not.
+pub fn break_opportunities(text: &str) -> Vec<usize> {
+    use LineBreakClass::*;
+
+    let mut opportunities = Vec::new();
+    let mut chars = text.char_indices();
+    let (mut previous_char, mut previous) = match chars.next() {
+        // LB10
+        Some((_, char)) => match resolved_class(char) {
+            CM | ZWJ => (char, AL),
+            class => (char, class),
+        },
+        None => return opportunities,
+    };
+    // last class before a sequence of spaces, used by the rules with `SP*`
+    let mut before_spaces = previous;
+    // class before the previous one, for LB21a
+    let mut before_previous = None;
+    let mut after_zwj = previous_char == '\u{200d}';
+    let mut odd_regional_indicators = previous == RI;
+
+    for (index, char) in chars {
+        let class = resolved_class(char);
+        // LB9, combining marks take the class of the character they are attached to
+        if matches!(class, CM | ZWJ) && !matches!(previous, BK | CR | LF | NL | SP | ZW) {
+            after_zwj = class == ZWJ;
+            continue;
+        }
+        // LB10
+        let class = if matches!(class, CM | ZWJ) { AL } else { class };
+
+        let can_break = match (previous, class) {
+            // LB4, LB5
+            (CR, LF) => false,
+            (BK, _) | (CR, _) | (LF, _) | (NL, _) => true,
+            // LB6, LB7
+            (_, BK) | (_, CR) | (_, LF) | (_, NL) | (_, SP) | (_, ZW) => false,
+            // LB8
+            _ if before_spaces == ZW => true,
+            // LB8a
+            _ if after_zwj => false,
+            // LB11
+            (WJ, _) | (_, WJ) => false,
+            // LB12, LB12a
+            (GL, _) => false,
+            (SP, GL) | (BA, GL) | (HY, GL) => true,
+            (_, GL) => false,
+            // LB13
+            (_, CL) | (_, CP) | (_, EX) | (_, IS) | (_, SY) => false,
+            // LB14, LB15, LB16, LB17
+            _ if before_spaces == OP => false,
+            (_, OP) if before_spaces == QU => false,
+            (_, NS) if matches!(before_spaces, CL | CP) => false,
+            (_, B2) if before_spaces == B2 => false,
+            // LB18
+            (SP, _) => true,
+            // LB19, LB20
+            (QU, _) | (_, QU) => false,
+            (CB, _) | (_, CB) => true,
+            // LB21, LB21a, LB21b
+            (_, BA) | (_, HY) | (_, NS) | (BB, _) => false,
+            (HY, _) | (BA, _) if before_previous == Some(HL) => false,
+            (SY, HL) => false,
+            // LB22
+            (_, IN) => false,
+            // LB23, LB23a, LB24
+            (AL, NU) | (HL, NU) | (NU, AL) | (NU, HL) => false,
+            (PR, ID) | (PR, EB) | (PR, EM) | (ID, PO) | (EB, PO) | (EM, PO) => false,
+            (PR, AL) | (PR, HL) | (PO, AL) | (PO, HL) => false,
+            (AL, PR) | (AL, PO) | (HL, PR) | (HL, PO) => false,
+            // LB25
+            (CL, PO) | (CP, PO) | (CL, PR) | (CP, PR) | (NU, PO) | (NU, PR) => false,
+            (PO, OP) | (PO, NU) | (PR, OP) | (PR, NU) => false,
+            (HY, NU) | (IS, NU) | (NU, NU) | (SY, NU) => false,
+            // LB26, LB27
+            (JL, JL) | (JL, JV) | (JL, H2) | (JL, H3) => false,
+            (JV, JV) | (JV, JT) | (H2, JV) | (H2, JT) => false,
+            (JT, JT) | (H3, JT) => false,
+            (JL, PO) | (JV, PO) | (JT, PO) | (H2, PO) | (H3, PO) => false,
+            (PR, JL) | (PR, JV) | (PR, JT) | (PR, H2) | (PR, H3) => false,
+            // LB28, LB29
+            (AL, AL) | (AL, HL) | (HL, AL) | (HL, HL) => false,
+            (IS, AL) | (IS, HL) => false,
+            // LB30
+            (AL, OP) | (HL, OP) | (NU, OP) if !is_east_asian_punctuation(char) => false,
+            (CP, AL) | (CP, HL) | (CP, NU) if !is_east_asian_punctuation(previous_char) => false,
+            // LB30a, LB30b
+            (RI, RI) if odd_regional_indicators => false,
+            (EB, EM) => false,
+            // LB31
+            _ => true,
+        };
+        if can_break {
+            opportunities.push(index);
+        }
+
+        odd_regional_indicators = class == RI && !(previous == RI && odd_regional_indicators);
+        after_zwj = char == '\u{200d}';
+        before_previous = Some(previous);
+        previous = class;
+        previous_char = char;
+        if class != SP {
+            before_spaces = class;
+        }
+    }
+    opportunities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str) -> Vec<&str> {
+        let mut start = 0;
+        let mut parts = Vec::new();
+        for end in break_opportunities(text)
+            .into_iter()
+            .chain(Some(text.len()))
+        {
+            parts.push(&text[start..end]);
+            start = end;
+        }
+        parts
+    }
+
+    #[test]
+    fn test_line_break_class() {
+        assert_eq!(line_break_class('a'), LineBreakClass::AL);
+        assert_eq!(line_break_class(' '), LineBreakClass::SP);
+        assert_eq!(line_break_class('-'), LineBreakClass::HY);
+        assert_eq!(line_break_class('漢'), LineBreakClass::ID);
+        assert_eq!(line_break_class('。'), LineBreakClass::CL);
+        assert_eq!(line_break_class('\u{10ffff}'), LineBreakClass::XX);
+    }
+
+    #[test]
+    fn test_break_opportunities() {
+        assert_eq!(split(""), [""]);
+        assert_eq!(split("foo bar  baz"), ["foo ", "bar  ", "baz"]);
+        assert_eq!(split("well-known"), ["well-", "known"]);
+        assert_eq!(split("-1 (foo) 3.14%"), ["-1 ", "(foo) ", "3.14%"]);
+        assert_eq!(
+            split("日本語の文章。"),
+            ["日", "本", "語", "の", "文", "章。"]
+        );
+        // small kana and prolonged sound marks don't start lines
+        assert_eq!(split("ちょっとコーヒー"), ["ちょっ", "と", "コー", "ヒー"]);
+        assert_eq!(split("「括弧」です"), ["「括", "弧」", "で", "す"]);
+        assert_eq!(split("e\u{301}e\u{301} x"), ["e\u{301}e\u{301} ", "x"]);
+        assert_eq!(split("a\u{200b}b"), ["a\u{200b}", "b"]);
+        assert_eq!(split("a\u{2060}b c\u{a0}d"), ["a\u{2060}b ", "c\u{a0}d"]);
+        assert_eq!(split("🇪🇸🇫🇷👍🏽"), ["🇪🇸", "🇫🇷", "👍🏽"]);
+    }
+}
@@ -7,10 +7,8 @@ where
     // but we will denote an empty one as false
     // let mut peekable = values.into_iter().peekable();
     // assert!(peekable.peek().is_some(), "1 or more values required");
-    for e in values.into_iter() {
-        if let Some(value) = e {
-            return *value;
-        }
+    if let Some(value) = values.into_iter().flatten().next() {
+        return *value;
     }
     false
 }
@@ -19,6 +17,7 @@ where
 mod tests {
     use super::pick_bool;
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_pick_bool() {
         assert_eq!(pick_bool(&[]), false);
         assert_eq!(pick_bool(&[Some(true)]), true);
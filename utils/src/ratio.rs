@@ -17,16 +17,18 @@ pub fn ratio_reduce(total: i32, ratios: &[i32], maximums: &[i32], values: &[i32]
             result.push(value - distributed);
             total_remaining -= distributed;
             total_ratio -= ratio;
+        } else {
+            result.push(*value);
         }
     }
     result
 }
 
 pub fn ratio_distribute(total: i32, ratios: &[i32], minimums: Option<&[i32]>) -> Vec<i32> {
-    let ratios = if minimums.is_some() {
+    let ratios = if let Some(minimums) = minimums {
         ratios
             .iter()
-            .zip(minimums.unwrap().iter())
+            .zip(minimums.iter())
             .map(|(ratio, min)| if *min > 0 { *ratio } else { 0 })
             .collect()
     } else {
@@ -80,6 +82,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ratio_reduce_zero_ratio() {
+        // values with a zero ratio are kept, instead of being dropped from the result
+        assert_eq!(ratio_reduce(3, &[0, 1], &[3, 3], &[4, 4]), [4, 1]);
+        assert_eq!(
+            ratio_reduce(3, &[1, 0, 1], &[3, 3, 3], &[4, 4, 4]),
+            [2, 4, 3]
+        );
+    }
+
     #[test]
     fn test_ratio_distribute() {
         assert_eq!(ratio_distribute(10, &[1], None), [10]);
@@ -1,11 +1,29 @@
 use lazy_static::lazy_static;
-use regex::{Match, Regex};
+use regex::Regex;
 
+use super::hyphenate::Hyphenator;
 use super::iter::loop_last;
-use cells::{cell_len, chop_cells, DEFAULT_CELL_LEN_CACHE};
+use super::line_break::break_opportunities;
+use cells::{cell_len, chop_cells};
 
 lazy_static! {
     pub static ref WORDS: Regex = Regex::new(r#"\s*\S+\s*"#).unwrap();
+    static ref LETTERS: Regex = Regex::new(r#"\p{Alphabetic}+"#).unwrap();
+}
+
+/// Drawn at the end of a line broken inside a word
+pub const HYPHEN: &str = "-";
+/// Invisible hyphenation point, only drawn as a hyphen when a line is broken at it
+pub const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Where lines can be broken when wrapping text
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakMode {
+    /// Only at whitespace
+    Whitespace,
+    /// At the break opportunities of the Unicode line breaking algorithm, so text without spaces
+    /// like Chinese or Japanese can be broken between characters, and words after hyphens
+    Unicode,
 }
 
 struct WordsMatchIterator<'a> {
@@ -40,38 +58,315 @@ pub fn words<'a>(text: &'a str) -> impl Iterator<Item = (usize, usize, &'a str)>
     WordsMatchIterator::new(text)
 }
 
-pub fn divide_line(text: &str, width: usize, fold: Option<bool>) -> Vec<usize> {
-    let mut divides: Vec<usize> = Vec::new();
+/// Split text at its line break opportunities, each word keeps its trailing whitespace
+pub fn line_break_words(text: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    let mut starts = vec![0];
+    starts.extend(break_opportunities(text));
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .copied()
+        .chain(Some(text.len()))
+        .collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .filter(|(start, end)| start < end)
+        .map(move |(start, end)| (start, end, &text[start..end]))
+}
+
+/// Offsets to divide a line at to fit in a width, words that don't fit in a line are folded
+/// unless `fold` is false. Lines can also be broken at soft hyphens, see `hyphenate_line`.
+pub fn divide_line(
+    text: &str,
+    width: usize,
+    fold: Option<bool>,
+    break_mode: Option<BreakMode>,
+) -> Vec<usize> {
+    hyphenate_line(text, width, fold, break_mode, None)
+        .into_iter()
+        .map(|(offset, _)| offset)
+        .collect()
+}
+
+/// Offsets to divide a line at to fit in a width like `divide_line`, breaking words that don't
+/// fit at their soft hyphens, or else at the hyphenation points found by `hyphenator`.
+/// Each offset comes with whether a hyphen has to be drawn at the end of the line before it. Soft
+/// hyphens are measured as hidden, they are expected to be removed from the divided lines.
+pub fn hyphenate_line(
+    text: &str,
+    width: usize,
+    fold: Option<bool>,
+    break_mode: Option<BreakMode>,
+    hyphenator: Option<&Hyphenator>,
+) -> Vec<(usize, bool)> {
+    let mut divides: Vec<(usize, bool)> = Vec::new();
     let fold = fold.unwrap_or(true);
+    let hyphen_len = cell_len(HYPHEN);
     let mut line_position = 0;
-    for (mut start, end, word) in words(text) {
-        let mut len_cache = DEFAULT_CELL_LEN_CACHE.lock().unwrap();
-        let word_len = cell_len(word.trim_end(), &mut len_cache);
-        if line_position + word_len > width {
-            if word_len > width {
-                if fold {
-                    for (last, line) in loop_last(chop_cells(word, width, line_position)) {
-                        if last {
-                            line_position = cell_len(&line, &mut len_cache);
-                        } else {
-                            start += line.len();
-                            divides.push(start);
-                        }
-                    }
-                } else {
-                    if start > 0 {
-                        divides.push(start);
+    let words: Box<dyn Iterator<Item = (usize, usize, &str)>> =
+        match break_mode.unwrap_or(BreakMode::Whitespace) {
+            BreakMode::Whitespace => Box::new(words(text)),
+            BreakMode::Unicode => Box::new(line_break_words(text)),
+        };
+    for (start, _end, word) in words {
+        if line_position + word_len(word) <= width {
+            line_position += visible_len(word);
+            continue;
+        }
+        let points = hyphenation_points(word, hyphenator);
+        // start of the part of the word not placed in a line yet
+        let mut offset = 0;
+        loop {
+            let rest = &word[offset..];
+            let rest_len = word_len(rest);
+            if line_position + rest_len <= width {
+                line_position += visible_len(rest);
+                break;
+            }
+            let hyphenated = points
+                .iter()
+                .rev()
+                .copied()
+                .filter(|point| *point > offset)
+                .find(|point| {
+                    line_position + visible_len(&word[offset..*point]) + hyphen_len <= width
+                });
+            if let Some(point) = hyphenated {
+                divides.push((start + point, true));
+                offset = point;
+                line_position = 0;
+                continue;
+            }
+            let has_points = points.last().is_some_and(|point| *point > offset);
+            if line_position > 0 && (rest_len <= width || has_points || !fold) {
+                let divide = start + offset;
+                divides.push((divide, text[..divide].ends_with(SOFT_HYPHEN)));
+                line_position = 0;
+                continue;
+            }
+            if fold {
+                let visible = rest.replace(SOFT_HYPHEN, "");
+                let visible_offsets: Vec<usize> = rest
+                    .char_indices()
+                    .filter(|(_, char)| *char != SOFT_HYPHEN)
+                    .map(|(index, _)| index)
+                    .collect();
+                let mut folded_chars = 0;
+                for (last, line) in loop_last(chop_cells(&visible, width, line_position)) {
+                    if last {
+                        line_position = cell_len(&line);
+                    } else {
+                        // the next line starts at the first visible character not folded yet
+                        folded_chars += line.chars().count();
+                        divides.push((start + offset + visible_offsets[folded_chars], false));
                     }
-                    line_position = cell_len(word, &mut len_cache);
                 }
-            } else if line_position > 0 && start > 0 {
-                divides.push(start);
-                line_position = cell_len(word, &mut len_cache);
+            } else {
+                line_position = visible_len(rest);
             }
-        } else {
-            line_position += cell_len(word, &mut len_cache);
+            break;
         }
-        std::mem::drop(len_cache);
     }
     divides
 }
+
+/// Cells a word needs at the end of a line, without its trailing whitespace and with the hyphen
+/// drawn for a trailing soft hyphen
+fn word_len(word: &str) -> usize {
+    let word = word.trim_end();
+    let hyphen_len = if word.ends_with(SOFT_HYPHEN) {
+        cell_len(HYPHEN)
+    } else {
+        0
+    };
+    visible_len(word) + hyphen_len
+}
+
+/// Cells taken by text with its soft hyphens hidden
+fn visible_len(text: &str) -> usize {
+    if text.contains(SOFT_HYPHEN) {
+        cell_len(&text.replace(SOFT_HYPHEN, ""))
+    } else {
+        cell_len(text)
+    }
+}
+
+/// Offsets within a word it can be hyphenated at, after its soft hyphens if it has any, or else
+/// at the hyphenation points of its runs of letters
+fn hyphenation_points(word: &str, hyphenator: Option<&Hyphenator>) -> Vec<usize> {
+    let trimmed = word.trim_end();
+    if trimmed.contains(SOFT_HYPHEN) {
+        return trimmed
+            .match_indices(SOFT_HYPHEN)
+            .map(|(index, soft_hyphen)| index + soft_hyphen.len())
+            .filter(|point| *point < trimmed.len())
+            .collect();
+    }
+    let hyphenator = match hyphenator {
+        Some(hyphenator) => hyphenator,
+        None => return Vec::new(),
+    };
+    let mut points = Vec::new();
+    for letters in LETTERS.find_iter(trimmed) {
+        points.extend(
+            hyphenator
+                .hyphenation_points(letters.as_str())
+                .into_iter()
+                .map(|point| letters.start() + point),
+        );
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, width: usize, fold: bool, break_mode: BreakMode) -> Vec<&str> {
+        let mut start = 0;
+        let mut lines = Vec::new();
+        for end in divide_line(text, width, Some(fold), Some(break_mode))
+            .into_iter()
+            .chain(Some(text.len()))
+        {
+            lines.push(&text[start..end]);
+            start = end;
+        }
+        lines
+    }
+
+    /// Lines the text is wrapped in, drawn like `Text` does with soft hyphens removed
+    fn hyphenate(text: &str, width: usize, hyphenator: Option<&Hyphenator>) -> Vec<String> {
+        let mut start = 0;
+        let mut lines = Vec::new();
+        for (end, hyphen) in hyphenate_line(text, width, None, None, hyphenator)
+            .into_iter()
+            .chain(Some((text.len(), false)))
+        {
+            let mut line = text[start..end].replace(SOFT_HYPHEN, "");
+            if hyphen {
+                line.push_str(HYPHEN);
+            }
+            lines.push(line);
+            start = end;
+        }
+        lines
+    }
+
+    #[test]
+    fn test_divide_line_whitespace() {
+        assert_eq!(
+            wrap("foo bar baz", 7, true, BreakMode::Whitespace),
+            ["foo bar ", "baz"]
+        );
+        assert_eq!(
+            wrap("well-known words", 8, true, BreakMode::Whitespace),
+            ["well-kno", "wn words"]
+        );
+        assert_eq!(
+            wrap("日本語の文章です", 6, true, BreakMode::Whitespace),
+            ["日本語", "の文章", "です"]
+        );
+        assert_eq!(
+            wrap("日本語の文章です", 6, false, BreakMode::Whitespace),
+            ["日本語の文章です"]
+        );
+    }
+
+    #[test]
+    fn test_divide_line_unicode() {
+        assert_eq!(
+            wrap("foo bar baz", 7, true, BreakMode::Unicode),
+            ["foo bar ", "baz"]
+        );
+        assert_eq!(
+            wrap("well-known words", 8, true, BreakMode::Unicode),
+            ["well-", "known ", "words"]
+        );
+        // closing punctuation and small kana stay with the previous character
+        assert_eq!(
+            wrap("日本語の文章です。ちょっと", 6, true, BreakMode::Unicode),
+            ["日本語", "の文章", "です。", "ちょっ", "と"]
+        );
+        assert_eq!(
+            wrap("Rust是一种编程语言", 8, true, BreakMode::Unicode),
+            ["Rust是一", "种编程语", "言"]
+        );
+        // hangul syllables can be broken like ideographs
+        assert_eq!(
+            wrap("한국어 텍스트와 English text", 10, true, BreakMode::Unicode),
+            ["한국어 텍", "스트와 ", "English ", "text"]
+        );
+    }
+
+    #[test]
+    fn test_divide_line_unicode_fold() {
+        assert_eq!(
+            wrap("abcdefghij 日本", 4, true, BreakMode::Unicode),
+            ["abcd", "efgh", "ij ", "日本"]
+        );
+        assert_eq!(
+            wrap("abcdefghij 日本", 4, false, BreakMode::Unicode),
+            ["abcdefghij ", "日本"]
+        );
+    }
+
+    #[test]
+    fn test_divide_line_soft_hyphens() {
+        let text = "a hy\u{ad}phen\u{ad}ation test";
+        assert_eq!(
+            divide_line(text, 8, None, None),
+            [
+                text.find("phen").unwrap(),
+                text.find("ation").unwrap(),
+                text.find("test").unwrap()
+            ]
+        );
+        assert_eq!(
+            hyphenate(text, 8, None),
+            ["a hy-", "phen-", "ation ", "test"]
+        );
+        assert_eq!(hyphenate(text, 10, None), ["a hyphen-", "ation test"]);
+        assert_eq!(hyphenate(text, 20, None), ["a hyphenation test"]);
+        // without room for the hyphen the soft hyphens are ignored
+        assert_eq!(hyphenate("hy\u{ad}phen", 2, None), ["hy", "ph", "en"]);
+    }
+
+    #[test]
+    fn test_divide_line_hyphenator() {
+        let english = Hyphenator::english();
+        let english = Some(english.as_ref());
+        let text = "Narrow dashboards (with hyphenation)";
+        assert_eq!(
+            hyphenate(text, 12, None),
+            ["Narrow ", "dashboards ", "(with ", "hyphenation)"]
+        );
+        assert_eq!(
+            hyphenate(text, 12, english),
+            ["Narrow dash-", "boards (with ", "hyphenation)"]
+        );
+        assert_eq!(
+            hyphenate(text, 8, english),
+            ["Narrow ", "dash-", "boards ", "(with ", "hyphen-", "ation)"]
+        );
+        // words with soft hyphens are only broken at them
+        assert_eq!(
+            hyphenate("hyphen\u{ad}ation", 8, english),
+            ["hyphen-", "ation"]
+        );
+    }
+
+    #[test]
+    fn test_divide_line_hyphen_width() {
+        // the hyphen counts in the width of the line, wide characters are never cut
+        assert_eq!(hyphenate("ab 日本\u{ad}語", 6, None), ["ab ", "日本語"]);
+        assert_eq!(
+            hyphenate("ab 日本\u{ad}語", 5, None),
+            ["ab ", "日本-", "語"]
+        );
+        for line in hyphenate("ab 日本\u{ad}語", 4, None) {
+            assert!(cell_len(&line) <= 4, "{:?}", line);
+        }
+    }
+}